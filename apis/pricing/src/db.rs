@@ -1,15 +1,25 @@
 use crate::models::block_storage_request::BlockStorageRequest;
 use crate::models::block_storage_response::BlockStorageResponse;
+use crate::models::database_instance_request::DatabaseInstanceRequest;
+use crate::models::database_instance_response::DatabaseInstanceResponse;
+use crate::models::database_storage_request::DatabaseStorageRequest;
+use crate::models::database_storage_response::DatabaseStorageResponse;
 use crate::models::external_data_transfer_request::ExternalDataTransferRequest;
 use crate::models::external_data_transfer_response::ExternalDataTransferResponse;
 use crate::models::inter_region_data_transfer_request::InterRegionDataTransferRequest;
 use crate::models::inter_region_data_transfer_response::InterRegionDataTransferResponse;
+use crate::models::network_services_request::NetworkServicesRequest;
+use crate::models::network_services_response::NetworkServicesResponse;
+use crate::models::object_storage_request::ObjectStorageRequest;
+use crate::models::object_storage_response::ObjectStorageResponse;
 use crate::models::on_demand_response::OnDemandResponse;
 use crate::models::spot_response::SpotResponse;
+use crate::models::{cloud_provider::CloudProvider, region_id::RegionId};
 use crate::models::{on_demand_request::OnDemandRequest, spot_request::SpotRequest};
 use actix_web::web;
 use anyhow::Result;
 use sqlx::{FromRow, Pool, Postgres, QueryBuilder};
+use types::cloud_provider;
 
 pub async fn create_pool(database_url: &str) -> Pool<Postgres> {
     Pool::connect(database_url)
@@ -43,23 +53,58 @@ pub async fn validate_api_key(
     }
 }
 
+// Restrict a query to rows from the given providers
+fn push_providers(query: &mut QueryBuilder<'_, Postgres>, providers: &[CloudProvider]) {
+    query.push(" AND provider IN (");
+    let mut separated = query.separated(", ");
+    for provider in providers.iter() {
+        separated.push_bind(cloud_provider::CloudProvider::from(*provider).to_string());
+    }
+    separated.push_unseparated(") ");
+}
+
+// Restrict a query to rows in the given regions, matching on both the provider and the region code
+fn push_regions(query: &mut QueryBuilder<'_, Postgres>, column: &str, regions: &[RegionId]) {
+    query.push(format!(" AND (provider, {}) IN (", column));
+    for (index, region) in regions.iter().enumerate() {
+        if index > 0 {
+            query.push(", ");
+        }
+        query.push("(");
+        query.push_bind(region.provider());
+        query.push(", ");
+        query.push_bind(region.code());
+        query.push(")");
+    }
+    query.push(") ");
+}
+
+// Restrict a query to rows in a single region
+fn push_region(query: &mut QueryBuilder<'_, Postgres>, column: &str, region: &RegionId) {
+    query.push(" AND provider = ");
+    query.push_bind(region.provider());
+    query.push(format!(" AND {} = ", column));
+    query.push_bind(region.code());
+}
+
 pub async fn fetch_on_demand_data(
     pool: &web::Data<Pool<Postgres>>,
     req: OnDemandRequest,
 ) -> Result<Vec<OnDemandResponse>> {
+    let providers = req.providers.clone();
     let regions = req.regions.clone();
     let instance_types = req.instance_types.clone();
 
     let mut query = QueryBuilder::new("SELECT * FROM on_demand WHERE 1=1");
 
+    // Handle providers
+    if let Some(ref providers) = providers {
+        push_providers(&mut query, providers);
+    }
+
     // Handle regions
     if let Some(ref regions) = regions {
-        query.push(" AND region IN (");
-        let mut separated = query.separated(", ");
-        for region in regions.iter() {
-            separated.push_bind(region);
-        }
-        separated.push_unseparated(") ");
+        push_regions(&mut query, "region", regions);
     }
 
     // Handle instance types
@@ -111,20 +156,21 @@ pub async fn fetch_spot_data(
     pool: &web::Data<Pool<Postgres>>,
     req: SpotRequest,
 ) -> Result<Vec<SpotResponse>> {
+    let providers = req.providers.clone();
     let regions = req.regions.clone();
     let availability_zones = req.availability_zones.clone();
     let instance_types = req.instance_types.clone();
 
     let mut query = QueryBuilder::new("SELECT * FROM spot WHERE 1=1");
 
+    // Handle providers
+    if let Some(ref providers) = providers {
+        push_providers(&mut query, providers);
+    }
+
     // Handle regions
     if let Some(ref regions) = regions {
-        query.push("AND region IN (");
-        let mut separated = query.separated(", ");
-        for region in regions.iter() {
-            separated.push_bind(region);
-        }
-        separated.push_unseparated(") ");
+        push_regions(&mut query, "region", regions);
     }
 
     // Handle availability zones
@@ -176,10 +222,14 @@ pub async fn fetch_external_data_transfer(
 ) -> Result<Vec<ExternalDataTransferResponse>> {
     let mut query = QueryBuilder::new("SELECT * FROM external_data_transfer WHERE 1=1");
 
+    // Handle providers
+    if let Some(ref providers) = data_transfer_request.providers {
+        push_providers(&mut query, providers);
+    }
+
     // Handle from_region_code
     if let Some(ref from_region_code) = data_transfer_request.from_region_code {
-        query.push(" AND from_region_code = ");
-        query.push_bind(from_region_code);
+        push_region(&mut query, "from_region_code", from_region_code);
     }
 
     // Handle start_range if it exists
@@ -211,16 +261,19 @@ pub async fn fetch_inter_region_data_transfer(
 ) -> Result<Vec<InterRegionDataTransferResponse>, anyhow::Error> {
     let mut query = QueryBuilder::new("SELECT * FROM inter_region_data_transfer WHERE 1=1");
 
+    // Handle providers
+    if let Some(ref providers) = data_transfer_request.providers {
+        push_providers(&mut query, providers);
+    }
+
     // Handle from_region_code
-    if data_transfer_request.from_region_code.as_ref().is_some() {
-        query.push(" AND from_region_code = ");
-        query.push_bind(&data_transfer_request.from_region_code);
+    if let Some(ref from_region_code) = data_transfer_request.from_region_code {
+        push_region(&mut query, "from_region_code", from_region_code);
     }
 
     // Handle to_region_code
-    if data_transfer_request.to_region_code.as_ref().is_some() {
-        query.push(" AND to_region_code = ");
-        query.push_bind(&data_transfer_request.to_region_code);
+    if let Some(ref to_region_code) = data_transfer_request.to_region_code {
+        push_region(&mut query, "to_region_code", to_region_code);
     }
 
     // Handle sort_by
@@ -249,14 +302,14 @@ pub async fn fetch_storage(
 ) -> Result<Vec<BlockStorageResponse>> {
     let mut query = QueryBuilder::new("SELECT * FROM storage WHERE 1=1");
 
+    // Handle providers
+    if let Some(ref providers) = storage_request.providers {
+        push_providers(&mut query, providers);
+    }
+
     // Handle region
     if let Some(ref regions) = storage_request.regions {
-        query.push("AND region IN (");
-        let mut separated = query.separated(", ");
-        for region in regions.iter() {
-            separated.push_bind(region);
-        }
-        separated.push_unseparated(") ");
+        push_regions(&mut query, "region", regions);
     }
 
     // Handle volume_api_name
@@ -286,3 +339,229 @@ pub async fn fetch_storage(
 
     Ok(rows)
 }
+
+pub async fn fetch_object_storage(
+    pool: &web::Data<Pool<Postgres>>,
+    storage_request: ObjectStorageRequest,
+) -> Result<Vec<ObjectStorageResponse>> {
+    let mut query = QueryBuilder::new("SELECT * FROM object_storage WHERE 1=1");
+
+    // Handle providers
+    if let Some(ref providers) = storage_request.providers {
+        push_providers(&mut query, providers);
+    }
+
+    // Handle region
+    if let Some(ref regions) = storage_request.regions {
+        push_regions(&mut query, "region", regions);
+    }
+
+    // Handle storage_classes
+    if let Some(ref storage_classes) = storage_request.storage_classes {
+        query.push(" AND storage_class IN (");
+        let mut separated = query.separated(", ");
+        for storage_class in storage_classes.iter() {
+            separated.push_bind(storage_class);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle sort_by
+    if storage_request.sort_by.as_ref().is_some() {
+        query.push(format!(" ORDER BY {}", storage_request.sort_by.unwrap()));
+    }
+
+    // Handle sort_order
+    if storage_request.sort_order.as_ref().is_some() {
+        query.push(format!(" {}", storage_request.sort_order.unwrap()));
+    }
+
+    // Execute the query
+    let rows: Vec<ObjectStorageResponse> =
+        query.build_query_as().fetch_all(&***pool).await.unwrap();
+
+    Ok(rows)
+}
+
+pub async fn fetch_database_instances(
+    pool: &web::Data<Pool<Postgres>>,
+    instance_request: DatabaseInstanceRequest,
+) -> Result<Vec<DatabaseInstanceResponse>> {
+    let mut query = QueryBuilder::new("SELECT * FROM database_instances WHERE 1=1");
+
+    // Handle providers
+    if let Some(ref providers) = instance_request.providers {
+        push_providers(&mut query, providers);
+    }
+
+    // Handle region
+    if let Some(ref regions) = instance_request.regions {
+        push_regions(&mut query, "region", regions);
+    }
+
+    // Handle engines
+    if let Some(ref engines) = instance_request.engines {
+        query.push(" AND engine IN (");
+        let mut separated = query.separated(", ");
+        for value in engines.iter() {
+            separated.push_bind(value);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle deployment_options
+    if let Some(ref deployment_options) = instance_request.deployment_options {
+        query.push(" AND deployment_option IN (");
+        let mut separated = query.separated(", ");
+        for value in deployment_options.iter() {
+            separated.push_bind(value);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle instance_types
+    if let Some(ref instance_types) = instance_request.instance_types {
+        query.push(" AND instance_type IN (");
+        let mut separated = query.separated(", ");
+        for value in instance_types.iter() {
+            separated.push_bind(value);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle min_vcpu
+    if let Some(min_vcpu) = instance_request.min_vcpu {
+        query.push(" AND vcpu_count >= ");
+        query.push_bind(min_vcpu);
+    }
+
+    // Handle max_vcpu
+    if let Some(max_vcpu) = instance_request.max_vcpu {
+        query.push(" AND vcpu_count <= ");
+        query.push_bind(max_vcpu);
+    }
+
+    // Handle min_memory
+    if let Some(min_memory) = instance_request.min_memory {
+        query.push(" AND memory >= ");
+        query.push_bind(min_memory);
+    }
+
+    // Handle max_memory
+    if let Some(max_memory) = instance_request.max_memory {
+        query.push(" AND memory <= ");
+        query.push_bind(max_memory);
+    }
+
+    // Handle sort_by
+    if instance_request.sort_by.as_ref().is_some() {
+        query.push(format!(" ORDER BY {}", instance_request.sort_by.unwrap()));
+    }
+
+    // Handle sort_order
+    if instance_request.sort_order.as_ref().is_some() {
+        query.push(format!(" {}", instance_request.sort_order.unwrap()));
+    }
+
+    // Execute the query
+    let rows: Vec<DatabaseInstanceResponse> =
+        query.build_query_as().fetch_all(&***pool).await.unwrap();
+
+    Ok(rows)
+}
+
+pub async fn fetch_database_storage(
+    pool: &web::Data<Pool<Postgres>>,
+    storage_request: DatabaseStorageRequest,
+) -> Result<Vec<DatabaseStorageResponse>> {
+    let mut query = QueryBuilder::new("SELECT * FROM database_storage WHERE 1=1");
+
+    // Handle providers
+    if let Some(ref providers) = storage_request.providers {
+        push_providers(&mut query, providers);
+    }
+
+    // Handle region
+    if let Some(ref regions) = storage_request.regions {
+        push_regions(&mut query, "region", regions);
+    }
+
+    // Handle deployment_options
+    if let Some(ref deployment_options) = storage_request.deployment_options {
+        query.push(" AND deployment_option IN (");
+        let mut separated = query.separated(", ");
+        for value in deployment_options.iter() {
+            separated.push_bind(value);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle volume_types
+    if let Some(ref volume_types) = storage_request.volume_types {
+        query.push(" AND volume_type IN (");
+        let mut separated = query.separated(", ");
+        for value in volume_types.iter() {
+            separated.push_bind(value);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle sort_by
+    if storage_request.sort_by.as_ref().is_some() {
+        query.push(format!(" ORDER BY {}", storage_request.sort_by.unwrap()));
+    }
+
+    // Handle sort_order
+    if storage_request.sort_order.as_ref().is_some() {
+        query.push(format!(" {}", storage_request.sort_order.unwrap()));
+    }
+
+    // Execute the query
+    let rows: Vec<DatabaseStorageResponse> =
+        query.build_query_as().fetch_all(&***pool).await.unwrap();
+
+    Ok(rows)
+}
+
+pub async fn fetch_network_services(
+    pool: &web::Data<Pool<Postgres>>,
+    services_request: NetworkServicesRequest,
+) -> Result<Vec<NetworkServicesResponse>> {
+    let mut query = QueryBuilder::new("SELECT * FROM network_services WHERE 1=1");
+
+    // Handle providers
+    if let Some(ref providers) = services_request.providers {
+        push_providers(&mut query, providers);
+    }
+
+    // Handle region
+    if let Some(ref regions) = services_request.regions {
+        push_regions(&mut query, "region", regions);
+    }
+
+    // Handle services
+    if let Some(ref services) = services_request.services {
+        query.push(" AND service IN (");
+        let mut separated = query.separated(", ");
+        for service in services.iter() {
+            separated.push_bind(service);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    // Handle sort_by
+    if services_request.sort_by.as_ref().is_some() {
+        query.push(format!(" ORDER BY {}", services_request.sort_by.unwrap()));
+    }
+
+    // Handle sort_order
+    if services_request.sort_order.as_ref().is_some() {
+        query.push(format!(" {}", services_request.sort_order.unwrap()));
+    }
+
+    // Execute the query
+    let rows: Vec<NetworkServicesResponse> =
+        query.build_query_as().fetch_all(&***pool).await.unwrap();
+
+    Ok(rows)
+}
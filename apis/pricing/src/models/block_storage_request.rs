@@ -1,9 +1,12 @@
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
 #[derive(InputObject, Serialize, Deserialize, Debug, Clone)]
 pub struct BlockStorageRequest {
-    pub regions: Option<Vec<String>>,
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
     pub volume_api_name: Option<String>,
     pub storage_media: Option<String>,
     pub sort_by: Option<String>,
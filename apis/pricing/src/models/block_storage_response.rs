@@ -7,6 +7,12 @@ pub struct BlockStorageResponse {
     pub volume_api_name: Option<String>,
     pub storage_media: Option<String>,
     pub price_per_gb_month: Option<f64>,
+    pub price_per_iops_month: Option<f64>,
+    pub price_per_mibps_month: Option<f64>,
+    pub included_iops: Option<i32>,
+    pub included_throughput: Option<i32>,
+    pub snapshot_price_per_gb_month: Option<f64>,
+    pub provider: Option<String>,
 }
 
 #[Object]
@@ -15,6 +21,13 @@ impl BlockStorageResponse {
         self.region.clone()
     }
 
+    async fn region_id(&self) -> Option<String> {
+        match (&self.provider, &self.region) {
+            (Some(provider), Some(region)) => Some(format!("{}:{}", provider, region)),
+            _ => None,
+        }
+    }
+
     async fn volume_api_name(&self) -> Option<String> {
         self.volume_api_name.clone()
     }
@@ -26,4 +39,28 @@ impl BlockStorageResponse {
     async fn price_per_gb_month(&self) -> Option<f64> {
         self.price_per_gb_month
     }
+
+    async fn price_per_iops_month(&self) -> Option<f64> {
+        self.price_per_iops_month
+    }
+
+    async fn price_per_mibps_month(&self) -> Option<f64> {
+        self.price_per_mibps_month
+    }
+
+    async fn included_iops(&self) -> Option<i32> {
+        self.included_iops
+    }
+
+    async fn included_throughput(&self) -> Option<i32> {
+        self.included_throughput
+    }
+
+    async fn snapshot_price_per_gb_month(&self) -> Option<f64> {
+        self.snapshot_price_per_gb_month
+    }
+
+    async fn provider(&self) -> Option<String> {
+        self.provider.clone()
+    }
 }
@@ -0,0 +1,20 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use types::cloud_provider;
+
+#[derive(Enum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudProvider {
+    Aws,
+    Azure,
+    Gcp,
+}
+
+impl From<CloudProvider> for cloud_provider::CloudProvider {
+    fn from(provider: CloudProvider) -> Self {
+        match provider {
+            CloudProvider::Aws => cloud_provider::CloudProvider::Aws,
+            CloudProvider::Azure => cloud_provider::CloudProvider::Azure,
+            CloudProvider::Gcp => cloud_provider::CloudProvider::Gcp,
+        }
+    }
+}
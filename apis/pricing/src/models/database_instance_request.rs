@@ -0,0 +1,19 @@
+use async_graphql::InputObject;
+use serde::{Deserialize, Serialize};
+
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
+#[derive(InputObject, Serialize, Deserialize, Debug, Clone)]
+pub struct DatabaseInstanceRequest {
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
+    pub engines: Option<Vec<String>>,
+    pub deployment_options: Option<Vec<String>>,
+    pub instance_types: Option<Vec<String>>,
+    pub min_vcpu: Option<f64>,
+    pub max_vcpu: Option<f64>,
+    pub min_memory: Option<f64>,
+    pub max_memory: Option<f64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
@@ -0,0 +1,53 @@
+use async_graphql::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct DatabaseInstanceResponse {
+    pub region: String,
+    pub engine: String,
+    pub deployment_option: String,
+    pub instance_type: String,
+    pub vcpu_count: f32,
+    pub memory: f32,
+    pub price_per_hour: f64,
+    pub provider: String,
+}
+
+#[Object]
+impl DatabaseInstanceResponse {
+    async fn region(&self) -> &str {
+        &self.region
+    }
+
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
+    async fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    async fn deployment_option(&self) -> &str {
+        &self.deployment_option
+    }
+
+    async fn instance_type(&self) -> &str {
+        &self.instance_type
+    }
+
+    async fn vcpu_count(&self) -> f32 {
+        self.vcpu_count
+    }
+
+    async fn memory(&self) -> f32 {
+        self.memory
+    }
+
+    async fn price_per_hour(&self) -> f64 {
+        self.price_per_hour
+    }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
+}
@@ -0,0 +1,43 @@
+use async_graphql::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct DatabaseStorageResponse {
+    pub region: String,
+    pub deployment_option: String,
+    pub volume_type: String,
+    pub price_per_gb_month: f64,
+    pub backup_price_per_gb_month: Option<f64>,
+    pub provider: String,
+}
+
+#[Object]
+impl DatabaseStorageResponse {
+    async fn region(&self) -> &str {
+        &self.region
+    }
+
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
+    async fn deployment_option(&self) -> &str {
+        &self.deployment_option
+    }
+
+    async fn volume_type(&self) -> &str {
+        &self.volume_type
+    }
+
+    async fn price_per_gb_month(&self) -> f64 {
+        self.price_per_gb_month
+    }
+
+    async fn backup_price_per_gb_month(&self) -> Option<f64> {
+        self.backup_price_per_gb_month
+    }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
+}
@@ -1,9 +1,12 @@
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
 #[derive(InputObject, Debug, Serialize, Deserialize, Clone)]
 pub struct ExternalDataTransferRequest {
-    pub from_region_code: Option<String>,
+    pub providers: Option<Vec<CloudProvider>>,
+    pub from_region_code: Option<RegionId>,
     pub start_range: Option<i64>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
@@ -7,6 +7,7 @@ pub struct ExternalDataTransferResponse {
     pub start_range: i64,
     pub end_range: i64,
     pub price_per_gb: f64,
+    pub provider: String,
 }
 
 #[Object]
@@ -15,6 +16,10 @@ impl ExternalDataTransferResponse {
         &self.from_region_code
     }
 
+    pub async fn from_region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.from_region_code)
+    }
+
     pub async fn start_range(&self) -> i64 {
         self.start_range
     }
@@ -26,4 +31,8 @@ impl ExternalDataTransferResponse {
     pub async fn price_per_gb(&self) -> f64 {
         self.price_per_gb
     }
+
+    pub async fn provider(&self) -> &str {
+        &self.provider
+    }
 }
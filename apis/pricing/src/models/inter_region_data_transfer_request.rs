@@ -1,10 +1,13 @@
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
 #[derive(InputObject, Deserialize, Serialize, Debug, Clone)]
 pub struct InterRegionDataTransferRequest {
-    pub from_region_code: Option<String>,
-    pub to_region_code: Option<String>,
+    pub providers: Option<Vec<CloudProvider>>,
+    pub from_region_code: Option<RegionId>,
+    pub to_region_code: Option<RegionId>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
 }
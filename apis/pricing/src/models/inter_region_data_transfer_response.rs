@@ -6,6 +6,7 @@ pub struct InterRegionDataTransferResponse {
     pub from_region_code: String,
     pub to_region_code: String,
     pub price_per_gb: f64,
+    pub provider: String,
 }
 
 #[Object]
@@ -18,7 +19,19 @@ impl InterRegionDataTransferResponse {
         &self.to_region_code
     }
 
+    pub async fn from_region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.from_region_code)
+    }
+
+    pub async fn to_region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.to_region_code)
+    }
+
     pub async fn price_per_gb(&self) -> f64 {
         self.price_per_gb
     }
+
+    pub async fn provider(&self) -> &str {
+        &self.provider
+    }
 }
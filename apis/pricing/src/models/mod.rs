@@ -1,10 +1,20 @@
 pub mod block_storage_request;
 pub mod block_storage_response;
+pub mod cloud_provider;
+pub mod database_instance_request;
+pub mod database_instance_response;
+pub mod database_storage_request;
+pub mod database_storage_response;
 pub mod external_data_transfer_request;
 pub mod external_data_transfer_response;
 pub mod inter_region_data_transfer_request;
 pub mod inter_region_data_transfer_response;
+pub mod network_services_request;
+pub mod network_services_response;
+pub mod object_storage_request;
+pub mod object_storage_response;
 pub mod on_demand_request;
 pub mod on_demand_response;
+pub mod region_id;
 pub mod spot_request;
 pub mod spot_response;
@@ -0,0 +1,13 @@
+use async_graphql::InputObject;
+use serde::{Deserialize, Serialize};
+
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
+#[derive(InputObject, Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkServicesRequest {
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
+    pub services: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
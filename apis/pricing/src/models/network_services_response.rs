@@ -0,0 +1,43 @@
+use async_graphql::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkServicesResponse {
+    pub region: String,
+    pub service: String,
+    pub usage: String,
+    pub unit: String,
+    pub price_per_unit: f64,
+    pub provider: String,
+}
+
+#[Object]
+impl NetworkServicesResponse {
+    async fn region(&self) -> &str {
+        &self.region
+    }
+
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
+    async fn service(&self) -> &str {
+        &self.service
+    }
+
+    async fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    async fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    async fn price_per_unit(&self) -> f64 {
+        self.price_per_unit
+    }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
+}
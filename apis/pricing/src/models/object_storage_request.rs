@@ -0,0 +1,13 @@
+use async_graphql::InputObject;
+use serde::{Deserialize, Serialize};
+
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
+#[derive(InputObject, Serialize, Deserialize, Debug, Clone)]
+pub struct ObjectStorageRequest {
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
+    pub storage_classes: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
@@ -0,0 +1,50 @@
+use async_graphql::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct ObjectStorageResponse {
+    pub region: String,
+    pub storage_class: String,
+    pub price_per_gb_month: f64,
+    pub price_per_1k_put_requests: f64,
+    pub price_per_1k_get_requests: f64,
+    pub retrieval_price_per_gb: f64,
+    pub provider: String,
+}
+
+#[Object]
+impl ObjectStorageResponse {
+    async fn region(&self) -> &str {
+        &self.region
+    }
+
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
+    async fn storage_class(&self) -> &str {
+        &self.storage_class
+    }
+
+    async fn price_per_gb_month(&self) -> f64 {
+        self.price_per_gb_month
+    }
+
+    #[graphql(name = "pricePer1kPutRequests")]
+    async fn price_per_1k_put_requests(&self) -> f64 {
+        self.price_per_1k_put_requests
+    }
+
+    #[graphql(name = "pricePer1kGetRequests")]
+    async fn price_per_1k_get_requests(&self) -> f64 {
+        self.price_per_1k_get_requests
+    }
+
+    async fn retrieval_price_per_gb(&self) -> f64 {
+        self.retrieval_price_per_gb
+    }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
+}
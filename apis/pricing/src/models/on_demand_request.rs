@@ -1,9 +1,12 @@
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
 #[derive(Deserialize, Serialize, Debug, Clone, InputObject)]
 pub struct OnDemandRequest {
-    pub regions: Option<Vec<String>>,
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
     pub instance_types: Option<Vec<String>>,
     pub min_vcpu: Option<f64>,
     pub max_vcpu: Option<f64>,
@@ -9,6 +9,7 @@ pub struct OnDemandResponse {
     pub vcpu_count: f64,
     pub memory: f64,
     pub price_per_hour: f64,
+    pub provider: String,
 }
 
 #[Object]
@@ -17,6 +18,10 @@ impl OnDemandResponse {
         &self.region
     }
 
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
     async fn instance_type(&self) -> &str {
         &self.instance_type
     }
@@ -36,4 +41,8 @@ impl OnDemandResponse {
     async fn price_per_hour(&self) -> f64 {
         self.price_per_hour
     }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
 }
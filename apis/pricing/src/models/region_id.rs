@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+use serde::{Deserialize, Serialize};
+use types::region::Region;
+
+// A region identifier, either provider-qualified (aws:us-east-1) or a bare region code
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegionId(pub Region);
+
+#[Scalar]
+impl ScalarType for RegionId {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => Region::from_str(&s)
+                .map(RegionId)
+                .map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.qualified_code().unwrap_or_default())
+    }
+}
+
+impl RegionId {
+    pub fn provider(&self) -> String {
+        self.0.provider().to_string()
+    }
+
+    pub fn code(&self) -> String {
+        self.0.code().unwrap_or_default()
+    }
+}
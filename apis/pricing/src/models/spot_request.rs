@@ -1,9 +1,12 @@
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 
+use super::{cloud_provider::CloudProvider, region_id::RegionId};
+
 #[derive(InputObject, Deserialize, Serialize, Debug, Clone)]
 pub struct SpotRequest {
-    pub regions: Option<Vec<String>>,
+    pub providers: Option<Vec<CloudProvider>>,
+    pub regions: Option<Vec<RegionId>>,
     pub availability_zones: Option<Vec<String>>,
     pub instance_types: Option<Vec<String>>,
     pub min_price_per_hour: Option<f64>,
@@ -7,6 +7,7 @@ pub struct SpotResponse {
     pub region: String,
     pub instance_type: String,
     pub price_per_hour: f64,
+    pub provider: String,
 }
 
 #[Object]
@@ -19,6 +20,10 @@ impl SpotResponse {
         &self.region
     }
 
+    async fn region_id(&self) -> String {
+        format!("{}:{}", self.provider, self.region)
+    }
+
     async fn instance_type(&self) -> &str {
         &self.instance_type
     }
@@ -26,4 +31,8 @@ impl SpotResponse {
     async fn price_per_hour(&self) -> f64 {
         self.price_per_hour
     }
+
+    async fn provider(&self) -> &str {
+        &self.provider
+    }
 }
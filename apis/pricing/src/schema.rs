@@ -7,12 +7,20 @@ use crate::{
     db,
     models::{
         block_storage_request::BlockStorageRequest, block_storage_response::BlockStorageResponse,
+        database_instance_request::DatabaseInstanceRequest,
+        database_instance_response::DatabaseInstanceResponse,
+        database_storage_request::DatabaseStorageRequest,
+        database_storage_response::DatabaseStorageResponse,
         external_data_transfer_request::ExternalDataTransferRequest,
         external_data_transfer_response::ExternalDataTransferResponse,
         inter_region_data_transfer_request::InterRegionDataTransferRequest,
         inter_region_data_transfer_response::InterRegionDataTransferResponse,
-        on_demand_request::OnDemandRequest, on_demand_response::OnDemandResponse,
-        spot_request::SpotRequest, spot_response::SpotResponse,
+        network_services_request::NetworkServicesRequest,
+        network_services_response::NetworkServicesResponse,
+        object_storage_request::ObjectStorageRequest,
+        object_storage_response::ObjectStorageResponse, on_demand_request::OnDemandRequest,
+        on_demand_response::OnDemandResponse, spot_request::SpotRequest,
+        spot_response::SpotResponse,
     },
 };
 
@@ -210,4 +218,140 @@ impl Query {
 
         Ok(results)
     }
+
+    async fn object_storage(
+        &self,
+        ctx: &Context<'_>,
+        request: ObjectStorageRequest,
+    ) -> Result<Vec<ObjectStorageResponse>> {
+        let pool = ctx
+            .data::<web::Data<Pool<Postgres>>>()
+            .expect("Failed to get the pool");
+
+        let headers = ctx.data::<HeaderMap>().expect("Failed to get the headers");
+
+        let key_validation = extract_and_validate_key(pool.clone(), headers).await;
+
+        if key_validation.is_err() {
+            return Err(anyhow::anyhow!(
+                "Failed to validate API key. Make sure you have a valid API key. This could also be a problem with the API service. If you're sure you have a valid API key, please contact support."
+            ));
+        }
+
+        let results = match db::fetch_object_storage(pool, request).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Failed to fetch object storage data: {}", e);
+                return Err(anyhow::anyhow!("Failed to fetch object storage data"));
+            }
+        };
+
+        if results.is_empty() {
+            return Err(anyhow::anyhow!("No data found"));
+        }
+
+        Ok(results)
+    }
+
+    async fn database_instance(
+        &self,
+        ctx: &Context<'_>,
+        request: DatabaseInstanceRequest,
+    ) -> Result<Vec<DatabaseInstanceResponse>> {
+        let pool = ctx
+            .data::<web::Data<Pool<Postgres>>>()
+            .expect("Failed to get the pool");
+
+        let headers = ctx.data::<HeaderMap>().expect("Failed to get the headers");
+
+        let key_validation = extract_and_validate_key(pool.clone(), headers).await;
+
+        if key_validation.is_err() {
+            return Err(anyhow::anyhow!(
+                "Failed to validate API key. Make sure you have a valid API key. This could also be a problem with the API service. If you're sure you have a valid API key, please contact support."
+            ));
+        }
+
+        let results = match db::fetch_database_instances(pool, request).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Failed to fetch database instance data: {}", e);
+                return Err(anyhow::anyhow!("Failed to fetch database instance data"));
+            }
+        };
+
+        if results.is_empty() {
+            return Err(anyhow::anyhow!("No data found"));
+        }
+
+        Ok(results)
+    }
+
+    async fn database_storage(
+        &self,
+        ctx: &Context<'_>,
+        request: DatabaseStorageRequest,
+    ) -> Result<Vec<DatabaseStorageResponse>> {
+        let pool = ctx
+            .data::<web::Data<Pool<Postgres>>>()
+            .expect("Failed to get the pool");
+
+        let headers = ctx.data::<HeaderMap>().expect("Failed to get the headers");
+
+        let key_validation = extract_and_validate_key(pool.clone(), headers).await;
+
+        if key_validation.is_err() {
+            return Err(anyhow::anyhow!(
+                "Failed to validate API key. Make sure you have a valid API key. This could also be a problem with the API service. If you're sure you have a valid API key, please contact support."
+            ));
+        }
+
+        let results = match db::fetch_database_storage(pool, request).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Failed to fetch database storage data: {}", e);
+                return Err(anyhow::anyhow!("Failed to fetch database storage data"));
+            }
+        };
+
+        if results.is_empty() {
+            return Err(anyhow::anyhow!("No data found"));
+        }
+
+        Ok(results)
+    }
+
+    async fn network_services(
+        &self,
+        ctx: &Context<'_>,
+        request: NetworkServicesRequest,
+    ) -> Result<Vec<NetworkServicesResponse>> {
+        let pool = ctx
+            .data::<web::Data<Pool<Postgres>>>()
+            .expect("Failed to get the pool");
+
+        let headers = ctx.data::<HeaderMap>().expect("Failed to get the headers");
+
+        let key_validation = extract_and_validate_key(pool.clone(), headers).await;
+
+        if key_validation.is_err() {
+            return Err(anyhow::anyhow!(
+                "Failed to validate API key. Make sure you have a valid API key. This could also be a problem with the API service. If you're sure you have a valid API key, please contact support."
+            ));
+        }
+
+        let results = match db::fetch_network_services(pool, request).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Failed to fetch network services data: {}", e);
+                return Err(anyhow::anyhow!("Failed to fetch network services data"));
+            }
+        };
+
+        if results.is_empty() {
+            return Err(anyhow::anyhow!("No data found"));
+        }
+
+        Ok(results)
+    }
 }
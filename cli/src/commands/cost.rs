@@ -0,0 +1,637 @@
+use colored::Colorize;
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Cell, Color, Table};
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use indexmap::{IndexMap, IndexSet};
+use miette::Result;
+use serde_json::{json, Value};
+use strum::{EnumString, IntoEnumIterator};
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::core::{error::CommandError, settings};
+use constants::regions::UNSUPPORTED_REGIONS;
+use math::budget::Severity;
+use math::CostRange;
+use types::adjustments::Adjustments;
+use types::config::InfrastructureConfiguration;
+use types::currency::{Currency, ExchangeRate};
+use types::deployment_configuration::DeploymentConfiguration;
+use types::discovery::{self, Discovery};
+use types::region::{AwsRegion, Region};
+
+// How the estimate is printed
+#[derive(EnumString, Debug, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Markdown,
+}
+
+// Cost of the configuration in a single region
+struct Estimate {
+    region: Region,
+    breakdown: IndexMap<String, f64>,
+    // only set when there are adjustments to apply
+    effective: Option<IndexMap<String, f64>>,
+    range: CostRange,
+}
+
+pub async fn execute(
+    config: Option<String>,
+    region: Option<String>,
+    format: Option<String>,
+    currency: Option<String>,
+    compare_regions: bool,
+) -> Result<()> {
+    let format = format
+        .map(|format| {
+            OutputFormat::from_str(&format).map_err(|_| CommandError::UnsupportedFormat(format))
+        })
+        .transpose()?
+        .unwrap_or(OutputFormat::Table);
+
+    let currency = parse_currency(currency)?;
+
+    let path = config.unwrap_or_else(|| String::from("./infra.toml"));
+
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        CommandError::InvalidConfiguration(format!("Failed to read {}: {}", path, e))
+    })?;
+
+    let configuration = InfrastructureConfiguration::from_str(&contents).map_err(|e| {
+        CommandError::InvalidConfiguration(format!("Failed to parse {}: {}", path, e))
+    })?;
+
+    let regions: Vec<Region> = if compare_regions {
+        AwsRegion::iter()
+            .filter(|region| !UNSUPPORTED_REGIONS.contains(&region.code().as_str()))
+            .map(Region::Aws)
+            .collect()
+    } else {
+        let region =
+            match region {
+                Some(region) => Region::from_str(&region)
+                    .map_err(|_| CommandError::UnsupportedRegion(region))?,
+                None => Region::from_provider(
+                    &configuration.app.cloud_provider,
+                    &configuration.app.region,
+                )
+                .map_err(|_| CommandError::UnsupportedRegion(configuration.app.region.clone()))?,
+            };
+
+        vec![region]
+    };
+
+    let deployment = DeploymentConfiguration::from_infrastructure(&configuration, regions.clone())
+        .map_err(CommandError::InvalidConfiguration)?;
+
+    // --currency wins over the currency of the infra.toml
+    let rate =
+        settings::exchange_rate(currency.or(configuration.currency).unwrap_or(Currency::Usd))?;
+    let adjustments = settings::adjustments()?;
+
+    let mut costs = math::calculate_deployment_range(deployment)
+        .await
+        .map_err(CommandError::PricesUnavailable)?;
+
+    let region_codes = regions
+        .iter()
+        .filter_map(|region| region.qualified_code())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let mut estimates: Vec<Estimate> = regions
+        .into_iter()
+        .filter_map(|region| {
+            let (breakdown, range) = costs.remove(&region)?;
+
+            let effective = (!adjustments.is_empty())
+                .then(|| math::apply_adjustments(&breakdown, &region, &adjustments));
+
+            Some(Estimate {
+                region,
+                breakdown,
+                effective,
+                range,
+            })
+        })
+        .collect();
+
+    // cheapest region first when comparing
+    estimates.sort_by(|a, b| {
+        a.breakdown["Total"]
+            .partial_cmp(&b.breakdown["Total"])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // regions without prices for the configuration are left out of the estimates
+    let Some(estimate) = estimates.first() else {
+        return Err(CommandError::PricesUnavailable(if compare_regions {
+            String::from("No prices found for any region")
+        } else {
+            format!("No prices found for {}", region_codes)
+        })
+        .into());
+    };
+
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "💰 Estimated monthly cost of {}",
+                configuration.app.name.bright_cyan()
+            );
+
+            if compare_regions {
+                render_region_comparison(&estimates, &rate);
+            } else {
+                render_breakdown(estimate, &rate);
+            }
+
+            // prices are in USD, note the rate they were converted at
+            if rate.currency != Currency::Usd {
+                println!("Prices converted at {}.", rate.describe());
+            }
+
+            if !adjustments.is_empty() {
+                println!(
+                    "Effective prices include the discounts, uplifts and tax in your adjustments."
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let output = if compare_regions {
+                Value::Array(
+                    estimates
+                        .iter()
+                        .map(|estimate| estimate_json(estimate, &rate))
+                        .collect(),
+                )
+            } else {
+                estimate_json(estimate, &rate)
+            };
+
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        }
+        OutputFormat::Markdown => {
+            println!(
+                "{}",
+                estimates_markdown(
+                    &configuration.app.name,
+                    &estimates,
+                    &rate,
+                    &adjustments,
+                    compare_regions
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Currency passed to --currency, if any
+fn parse_currency(currency: Option<String>) -> Result<Option<Currency>> {
+    Ok(currency
+        .map(|currency| {
+            Currency::from_str(&currency).map_err(|_| CommandError::UnsupportedCurrency(currency))
+        })
+        .transpose()?)
+}
+
+// Every line of the breakdown in one region
+fn render_breakdown(estimate: &Estimate, rate: &ExchangeRate) {
+    let mut table = Table::new();
+
+    let mut header = vec!["service", "cost"];
+
+    if estimate.effective.is_some() {
+        header.push("effective");
+    }
+
+    table.set_header(header);
+
+    for (service, cost) in &estimate.breakdown {
+        let color = if service == "Total" {
+            Color::Green
+        } else {
+            Color::Reset
+        };
+
+        let mut row = vec![
+            Cell::new(service).fg(Color::Blue),
+            Cell::new(rate.format(*cost)).fg(color),
+        ];
+
+        if let Some(effective) = &estimate.effective {
+            row.push(Cell::new(rate.format(effective[service])).fg(color));
+        }
+
+        table.add_row(row);
+    }
+
+    // autoscaled deployments cost anywhere between their smallest and largest size
+    if estimate.range.min != estimate.range.max {
+        table.add_row(vec![
+            Cell::new("Range").fg(Color::Blue),
+            Cell::new(format!(
+                "{} – {}",
+                rate.format(estimate.range.min),
+                rate.format(estimate.range.max)
+            ))
+            .fg(Color::Yellow),
+        ]);
+    }
+
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS);
+
+    println!(
+        "🌏 {} ({})",
+        estimate.region.display_name().unwrap_or_default(),
+        estimate.region.qualified_code().unwrap_or_default()
+    );
+
+    println!("{}", table);
+}
+
+// The total of the same configuration in every region
+fn render_region_comparison(estimates: &[Estimate], rate: &ExchangeRate) {
+    let mut table = Table::new();
+
+    let adjusted = estimates
+        .iter()
+        .any(|estimate| estimate.effective.is_some());
+    let autoscaled = estimates
+        .iter()
+        .any(|estimate| estimate.range.min != estimate.range.max);
+
+    let mut header = vec!["name", "code", "total"];
+
+    if adjusted {
+        header.push("effective");
+    }
+
+    if autoscaled {
+        header.push("range");
+    }
+
+    table.set_header(header);
+
+    for estimate in estimates {
+        let mut row = vec![
+            Cell::new(estimate.region.display_name().unwrap_or_default()).fg(Color::Blue),
+            Cell::new(estimate.region.code().unwrap_or_default()).fg(Color::Cyan),
+            Cell::new(rate.format(estimate.breakdown["Total"])).fg(Color::Green),
+        ];
+
+        if let Some(effective) = &estimate.effective {
+            row.push(Cell::new(rate.format(effective["Total"])).fg(Color::Green));
+        }
+
+        if autoscaled {
+            row.push(
+                Cell::new(format!(
+                    "{} – {}",
+                    rate.format(estimate.range.min),
+                    rate.format(estimate.range.max)
+                ))
+                .fg(Color::Yellow),
+            );
+        }
+
+        table.add_row(row);
+    }
+
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS);
+
+    println!("{}", table);
+}
+
+fn estimate_json(estimate: &Estimate, rate: &ExchangeRate) -> Value {
+    let range = estimate.range.convert(rate);
+
+    json!({
+        "region": estimate.region.qualified_code(),
+        "currency": rate.currency,
+        "breakdown": math::convert_breakdown(&estimate.breakdown, rate),
+        "effective": estimate
+            .effective
+            .as_ref()
+            .map(|effective| math::convert_breakdown(effective, rate)),
+        "range": {
+            "min": range.min,
+            "expected": range.expected,
+            "max": range.max,
+        },
+    })
+}
+
+fn estimates_markdown(
+    app_name: &str,
+    estimates: &[Estimate],
+    rate: &ExchangeRate,
+    adjustments: &Adjustments,
+    compare_regions: bool,
+) -> String {
+    let adjusted = !adjustments.is_empty();
+
+    let body = if compare_regions {
+        let header = if adjusted {
+            "| Region | Total | Effective |\n|--------|-------|-----------|"
+        } else {
+            "| Region | Total |\n|--------|-------|"
+        };
+
+        let rows = estimates
+            .iter()
+            .map(|estimate| {
+                let effective = estimate
+                    .effective
+                    .as_ref()
+                    .map(|effective| format!(" {} |", rate.format(effective["Total"])))
+                    .unwrap_or_default();
+
+                format!(
+                    "| {} | {} |{}",
+                    estimate.region.qualified_code().unwrap_or_default(),
+                    rate.format(estimate.breakdown["Total"]),
+                    effective
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("{}\n{}", header, rows)
+    } else {
+        // execute only renders a single region when it has prices
+        let Some(estimate) = estimates.first() else {
+            return String::new();
+        };
+
+        let header = if adjusted {
+            "| Service | List | Effective |\n|---------|------|-----------|"
+        } else {
+            "| Service | Cost |\n|---------|------|"
+        };
+
+        let rows = estimate
+            .breakdown
+            .iter()
+            .map(|(service, cost)| {
+                let effective = estimate
+                    .effective
+                    .as_ref()
+                    .map(|effective| format!(" {} |", rate.format(effective[service])))
+                    .unwrap_or_default();
+
+                if service == "Total" {
+                    format!("| **Total** | **{}** |{}", rate.format(*cost), effective)
+                } else {
+                    format!("| {} | {} |{}", service, rate.format(*cost), effective)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let range = if estimate.range.min != estimate.range.max {
+            format!(
+                "\n| **Range** | {} – {} (expected {}) |",
+                rate.format(estimate.range.min),
+                rate.format(estimate.range.max),
+                rate.format(estimate.range.expected)
+            )
+        } else {
+            String::from("")
+        };
+
+        format!(
+            "**Region**: {}\n\n{}\n{}{}",
+            estimate.region.qualified_code().unwrap_or_default(),
+            header,
+            rows,
+            range
+        )
+    };
+
+    // costs are priced in USD, note the rate they were shown at
+    let rate_line = if rate.currency != Currency::Usd {
+        format!("\n\n_Costs converted from USD at {}._", rate.describe())
+    } else {
+        String::from("")
+    };
+
+    format!("## Cost Estimate: {}\n\n{}{}", app_name, body, rate_line)
+}
+
+// Price the infra.toml files at two refs and print the change, the same comment the cost-bot
+// leaves on a pull request. Fails when an app at the head goes over its budget
+pub async fn diff(
+    range: Option<String>,
+    budget: Option<String>,
+    currency: Option<String>,
+) -> Result<()> {
+    let range = range.unwrap_or_else(|| String::from("main..HEAD"));
+
+    // main..HEAD, main...HEAD or main on its own, compared to HEAD
+    let (base, head, symmetric) = match range.split_once("...") {
+        Some((base, head)) => (base, head, true),
+        None => match range.split_once("..") {
+            Some((base, head)) => (base, head, false),
+            None => (range.as_str(), "", false),
+        },
+    };
+
+    let head = if head.is_empty() { "HEAD" } else { head };
+
+    let budget = budget
+        .map(|budget| {
+            budget
+                .parse::<f64>()
+                .map_err(|_| CommandError::InvalidBudget(budget))
+        })
+        .transpose()?;
+
+    let currency = parse_currency(currency)?;
+
+    let repository = Repository::discover(".")
+        .map_err(|e| CommandError::InvalidReference(e.message().to_string()))?;
+
+    // main...HEAD compares HEAD to the commit it branched off main at, like git diff does
+    let base = if symmetric {
+        let commit = |reference: &str| {
+            repository
+                .revparse_single(reference)
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id())
+                .map_err(|e| {
+                    CommandError::InvalidReference(format!(
+                        "Failed to read {}: {}",
+                        reference,
+                        e.message()
+                    ))
+                })
+        };
+
+        repository
+            .merge_base(commit(base)?, commit(head)?)
+            .map_err(|e| {
+                CommandError::InvalidReference(format!(
+                    "Failed to find a merge base of {} and {}: {}",
+                    base,
+                    head,
+                    e.message()
+                ))
+            })?
+            .to_string()
+    } else {
+        base.to_string()
+    };
+
+    let base_configurations = configurations_at(&repository, &base)?;
+    let head_configurations = configurations_at(&repository, head)?;
+
+    let adjustments = settings::adjustments()?;
+
+    let base_report = math::report::cost_report(&base_configurations, &adjustments)
+        .await
+        .map_err(CommandError::InvalidConfiguration)?;
+
+    let head_report = math::report::cost_report(&head_configurations, &adjustments)
+        .await
+        .map_err(CommandError::InvalidConfiguration)?;
+
+    let rate = settings::exchange_rate(currency.or(head_report.currency).unwrap_or(Currency::Usd))?;
+
+    // --budget is a limit in USD, like the [budget] of an infra.toml, and overrides the limit
+    // it declares for every app
+    let mut budgets = head_report.budgets.clone();
+
+    if let Some(limit) = budget {
+        for app_name in head_report.breakdowns.keys() {
+            budgets.entry(app_name.clone()).or_default().limit = Some(limit);
+        }
+    }
+
+    let violations = math::budget::budget_violations(
+        &head_report,
+        &budgets,
+        Some(&base_report.breakdowns),
+        &rate,
+    );
+
+    println!(
+        "{}",
+        math::report::markdown(Some(base_report.breakdowns), &head_report, &rate)
+    );
+
+    if !violations.is_empty() {
+        println!(
+            "\n### Violations\n\n{}",
+            math::budget::violation_lines(&violations)
+        );
+    }
+
+    let over_budget = violations
+        .iter()
+        .filter(|violation| violation.severity == Severity::Failure)
+        .map(|violation| violation.app_name.as_str())
+        .collect::<IndexSet<_>>();
+
+    if !over_budget.is_empty() {
+        return Err(CommandError::OverBudget(
+            over_budget.into_iter().collect::<Vec<_>>().join(", "),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+// The infra.toml files in the tree of a ref, found the same way the cost-bot finds them
+fn configurations_at(
+    repository: &Repository,
+    reference: &str,
+) -> Result<Vec<InfrastructureConfiguration>> {
+    let tree = repository
+        .revparse_single(reference)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| {
+            CommandError::InvalidReference(format!("Failed to read {}: {}", reference, e.message()))
+        })?;
+
+    // the .infralink.yml of the ref says which files are configurations
+    let settings = match tree.get_path(Path::new(discovery::SETTINGS_FILE)) {
+        Ok(entry) => {
+            let blob = entry
+                .to_object(repository)
+                .and_then(|object| object.peel_to_blob())
+                .map_err(|e| {
+                    CommandError::InvalidConfiguration(format!(
+                        "Failed to read {} at {}: {}",
+                        discovery::SETTINGS_FILE,
+                        reference,
+                        e.message()
+                    ))
+                })?;
+
+            Discovery::from_str(&String::from_utf8_lossy(blob.content())).map_err(|e| {
+                CommandError::InvalidConfiguration(format!(
+                    "Failed to parse {} at {}: {}",
+                    discovery::SETTINGS_FILE,
+                    reference,
+                    e
+                ))
+            })?
+        }
+        Err(_) => Discovery::default(),
+    };
+
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        let path = Path::new(root).join(entry.name().unwrap_or_default());
+
+        if entry.kind() == Some(ObjectType::Blob) && settings.is_configuration_file(&path) {
+            if let Ok(blob) = entry
+                .to_object(repository)
+                .and_then(|object| object.peel_to_blob())
+            {
+                files.push((path, String::from_utf8_lossy(blob.content()).to_string()));
+            }
+        }
+
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| {
+        CommandError::InvalidReference(format!("Failed to read {}: {}", reference, e.message()))
+    })?;
+
+    discovery::sort_by_depth(&mut files);
+
+    files
+        .iter()
+        .map(|(path, contents)| {
+            let mut configuration =
+                InfrastructureConfiguration::from_str(contents).map_err(|e| {
+                    CommandError::InvalidConfiguration(format!(
+                        "Failed to parse {} at {}: {}",
+                        path.display(),
+                        reference,
+                        e
+                    ))
+                })?;
+
+            // named like the cost-bot names apps of an environment
+            if let Some(environment) = settings.environment(path) {
+                configuration.app.name = format!("{} ({})", configuration.app.name, environment);
+            }
+
+            Ok(configuration)
+        })
+        .collect()
+}
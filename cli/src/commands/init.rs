@@ -1,8 +1,11 @@
 use colored::Colorize;
 use miette::Result;
 
+use std::str::FromStr;
+
 use crate::api;
-use crate::core::{prompt, table};
+use crate::core::{error::CommandError, prompt, settings, table};
+use types::currency::Currency;
 use types::region::Region;
 
 use types::{
@@ -10,10 +13,20 @@ use types::{
     config::{InfrastructureConfiguration, InternalAWSConfiguration, InternalConfiguration},
 };
 
-pub async fn execute() -> Result<()> {
+pub async fn execute(currency: Option<String>) -> Result<()> {
     // check if the user is logged in
     prompt::remain_not_logged_in()?;
 
+    // validate the currency before asking anything
+    let currency = currency
+        .map(|currency| {
+            Currency::from_str(&currency).map_err(|_| CommandError::UnsupportedCurrency(currency))
+        })
+        .transpose()?;
+
+    let rate = settings::exchange_rate(currency.unwrap_or(Currency::Usd))?;
+    let adjustments = settings::adjustments()?;
+
     println!(
         "{}",
         "👋 Welcome! Let's get started by setting up your infrastructure configuration."
@@ -74,7 +87,7 @@ pub async fn execute() -> Result<()> {
             if let InternalConfiguration::Aws(aws_config) = internal_configuration {
                 let regions = api::aws::api::list_regions(aws_config).await?;
 
-                table::render_region_pricing(regions.clone()).await;
+                table::render_region_pricing(regions.clone(), &rate, &adjustments).await?;
 
                 region = prompt::region(regions)?;
             }
@@ -84,12 +97,16 @@ pub async fn execute() -> Result<()> {
         CloudProvider::None => panic!("No cloud provider selected."),
     }
 
-    InfrastructureConfiguration::builder()
+    let mut builder = InfrastructureConfiguration::builder()
         .with_app_name(app_name)
         .with_cloud_provider(cloud_provider)
-        .with_region(region)
-        .build()
-        .save::<&str>(None);
+        .with_region(region);
+
+    if let Some(currency) = currency {
+        builder = builder.with_currency(currency);
+    }
+
+    builder.build().save::<&str>(None);
 
     println!(
         "🎉 You're now ready to deploy your app. Run {} {} to start.",
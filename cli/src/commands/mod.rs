@@ -1,4 +1,5 @@
 pub mod build;
+pub mod cost;
 pub mod deploy;
 pub mod help;
 pub mod init;
@@ -9,4 +9,74 @@ pub enum CommandError {
         help("Run `infra help` to see a list of available commands")
     )]
     CommandNotFound,
+
+    #[error("Unsupported currency: {0}")]
+    #[diagnostic(
+        code(infra::cli::UnsupportedCurrency),
+        help("Pass one of USD, EUR or GBP to --currency")
+    )]
+    UnsupportedCurrency(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(infra::cli::ExchangeRatesUnavailable),
+        help("Set INFRALINK_EXCHANGE_RATES to a rates file, or add one at ~/.infralink/exchange_rates.toml")
+    )]
+    ExchangeRatesUnavailable(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(infra::cli::InvalidAdjustments),
+        help("Check the discounts, region_uplifts and tax_rate in ~/.infralink/adjustments.toml")
+    )]
+    InvalidAdjustments(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(infra::cli::InvalidConfiguration),
+        help("Run `infra init` to create an infra.toml, or pass its path with --config")
+    )]
+    InvalidConfiguration(String),
+
+    #[error("Unsupported region: {0}")]
+    #[diagnostic(
+        code(infra::cli::UnsupportedRegion),
+        help("Pass a region code to --region, e.g. us-east-1 or aws:us-east-1")
+    )]
+    UnsupportedRegion(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(infra::cli::InvalidReference),
+        help("Run `infra cost diff` inside a git repository with a range of two refs, e.g. main..HEAD")
+    )]
+    InvalidReference(String),
+
+    #[error("Invalid budget: {0}")]
+    #[diagnostic(
+        code(infra::cli::InvalidBudget),
+        help("Pass the monthly budget of every app in USD to --budget, e.g. --budget 500")
+    )]
+    InvalidBudget(String),
+
+    #[error("Over budget: {0}")]
+    #[diagnostic(
+        code(infra::cli::OverBudget),
+        help("Reduce the cost of the apps in this change, or raise their [budget] in infra.toml")
+    )]
+    OverBudget(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(infra::cli::PricesUnavailable),
+        help("Check your internet connection, and that the services in your infra.toml are priced in the region")
+    )]
+    PricesUnavailable(String),
+
+    #[error("Unsupported format: {0}")]
+    #[diagnostic(
+        code(infra::cli::UnsupportedFormat),
+        help("Pass one of table, json or markdown to --format")
+    )]
+    UnsupportedFormat(String),
 }
@@ -6,7 +6,19 @@ use miette::Result;
 // Execute the command passed in
 pub async fn execute(options: ValidatedOptions) -> Result<()> {
     match options {
-        ValidatedOptions::Init {} => commands::init::execute().await,
+        ValidatedOptions::Init { currency } => commands::init::execute(currency).await,
+        ValidatedOptions::Cost {
+            config,
+            region,
+            format,
+            currency,
+            compare_regions,
+        } => commands::cost::execute(config, region, format, currency, compare_regions).await,
+        ValidatedOptions::CostDiff {
+            range,
+            budget,
+            currency,
+        } => commands::cost::diff(range, budget, currency).await,
         ValidatedOptions::Help {} => commands::help::execute().await,
         ValidatedOptions::Deploy {} => commands::deploy::execute().await,
         ValidatedOptions::None => {
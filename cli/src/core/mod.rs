@@ -3,5 +3,6 @@ pub mod error;
 pub mod executor;
 pub mod parser;
 pub mod prompt;
+pub mod settings;
 pub mod table;
 pub mod validator;
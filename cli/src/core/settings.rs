@@ -0,0 +1,48 @@
+use miette::Result;
+
+use super::error::CommandError;
+use types::adjustments::Adjustments;
+use types::currency::{Currency, ExchangeRate, ExchangeRateSource, StaticFileRates};
+
+// Rate to show costs in, read from INFRALINK_EXCHANGE_RATES or ~/.infralink/exchange_rates.toml
+pub fn exchange_rate(currency: Currency) -> Result<ExchangeRate> {
+    if currency == Currency::Usd {
+        return Ok(ExchangeRate::usd());
+    }
+
+    let path = std::env::var("INFRALINK_EXCHANGE_RATES")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| {
+            dirs::home_dir()
+                .unwrap()
+                .join(".infralink/")
+                .join("exchange_rates.toml")
+        });
+
+    let rate = StaticFileRates::load(path)
+        .and_then(|rates| rates.rate(currency))
+        .map_err(CommandError::ExchangeRatesUnavailable)?;
+
+    Ok(rate)
+}
+
+// Discounts and tax to show next to list prices, read from INFRALINK_ADJUSTMENTS or
+// ~/.infralink/adjustments.toml. List prices are shown alone when there is no file
+pub fn adjustments() -> Result<Adjustments> {
+    let path = std::env::var("INFRALINK_ADJUSTMENTS")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| {
+            dirs::home_dir()
+                .unwrap()
+                .join(".infralink/")
+                .join("adjustments.toml")
+        });
+
+    if !path.exists() {
+        return Ok(Adjustments::default());
+    }
+
+    let adjustments = Adjustments::load(path).map_err(CommandError::InvalidAdjustments)?;
+
+    Ok(adjustments)
+}
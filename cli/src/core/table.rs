@@ -1,10 +1,39 @@
 use aws_sdk_account::types::RegionOptStatus;
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Cell, Color, Table};
+use indexmap::IndexMap;
 use linked_hash_map::LinkedHashMap;
+use miette::Result;
 
-use types::region::AwsRegion;
+use types::adjustments::Adjustments;
+use types::currency::{Currency, ExchangeRate};
+use types::region::{AwsRegion, Region};
+
+use super::error::CommandError;
+
+pub async fn render_region_pricing(
+    regions: LinkedHashMap<AwsRegion, RegionOptStatus>,
+    rate: &ExchangeRate,
+    adjustments: &Adjustments,
+) -> Result<()> {
+    // with adjustments, show the effective price with the list price beside it
+    let price = |region: &AwsRegion, breakdown: Option<&IndexMap<String, f64>>| {
+        // regions without prices for the deployment
+        let Some(breakdown) = breakdown else {
+            return String::from("-");
+        };
+
+        let list = breakdown["Total"];
+
+        if adjustments.is_empty() {
+            return rate.format(list);
+        }
+
+        let effective =
+            math::apply_adjustments(breakdown, &Region::Aws(region.clone()), adjustments)["Total"];
+
+        format!("{} (list {})", rate.format(effective), rate.format(list))
+    };
 
-pub async fn render_region_pricing(regions: LinkedHashMap<AwsRegion, RegionOptStatus>) {
     let mut table = Table::new();
 
     table.set_header(vec![
@@ -12,6 +41,7 @@ pub async fn render_region_pricing(regions: LinkedHashMap<AwsRegion, RegionOptSt
         "code",
         "small deployment",
         "large deployment",
+        "large deployment (eks)",
         "status",
     ]);
 
@@ -20,26 +50,41 @@ pub async fn render_region_pricing(regions: LinkedHashMap<AwsRegion, RegionOptSt
     let small_deployment_prices = math::calculate_cheapest_deployment(
         regions_vec
             .iter()
-            .map(|(region, _)| region.clone())
+            .map(|(region, _)| Region::Aws(region.clone()))
             .collect(),
     )
-    .await;
+    .await
+    .map_err(CommandError::PricesUnavailable)?;
 
     let large_deployment_prices = math::calculate_large_deployment(
         regions_vec
             .iter()
-            .map(|(region, _)| region.clone())
+            .map(|(region, _)| Region::Aws(region.clone()))
+            .collect(),
+    )
+    .await
+    .map_err(CommandError::PricesUnavailable)?;
+
+    let large_eks_deployment_prices = math::calculate_large_eks_deployment(
+        regions_vec
+            .iter()
+            .map(|(region, _)| Region::Aws(region.clone()))
             .collect(),
     )
-    .await;
+    .await
+    .map_err(CommandError::PricesUnavailable)?;
 
-    // sort the regions by the lowest cost spot instance price
+    // sort the regions by the lowest cost spot instance price, regions without prices last
     regions_vec.sort_by(|(region1, _), (region2, _)| {
-        let price1 = small_deployment_prices.get(region1).unwrap()["Total"];
-        let price2 = small_deployment_prices.get(region2).unwrap()["Total"];
+        let price = |region: &AwsRegion| {
+            small_deployment_prices
+                .get(&Region::Aws(region.clone()))
+                .map(|breakdown| breakdown["Total"])
+                .unwrap_or(f64::INFINITY)
+        };
 
-        price1
-            .partial_cmp(&price2)
+        price(region1)
+            .partial_cmp(&price(region2))
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
@@ -47,16 +92,28 @@ pub async fn render_region_pricing(regions: LinkedHashMap<AwsRegion, RegionOptSt
         let display_name = region.display_name();
         let code = region.code();
 
-        let small_deployment_price = small_deployment_prices.get(region).unwrap()["Total"];
+        let small_deployment_price = price(
+            region,
+            small_deployment_prices.get(&Region::Aws(region.clone())),
+        );
 
-        let large_deployment_price = large_deployment_prices.get(region).unwrap()["Total"];
+        let large_deployment_price = price(
+            region,
+            large_deployment_prices.get(&Region::Aws(region.clone())),
+        );
+
+        let large_eks_deployment_price = price(
+            region,
+            large_eks_deployment_prices.get(&Region::Aws(region.clone())),
+        );
 
         table.add_row(
             vec![
                 Cell::new(display_name).fg(Color::Blue),
                 Cell::new(code).fg(Color::Cyan),
-                Cell::new(small_deployment_price.to_string()).fg(Color::Green),
-                Cell::new(large_deployment_price.to_string()).fg(Color::Green),
+                Cell::new(small_deployment_price).fg(Color::Green),
+                Cell::new(large_deployment_price).fg(Color::Green),
+                Cell::new(large_eks_deployment_price).fg(Color::Green),
                 match status {
                     RegionOptStatus::Enabled => Cell::new("enabled").fg(Color::Green),
                     RegionOptStatus::EnabledByDefault => Cell::new("enabled").fg(Color::Green),
@@ -73,4 +130,15 @@ pub async fn render_region_pricing(regions: LinkedHashMap<AwsRegion, RegionOptSt
         .apply_modifier(UTF8_ROUND_CORNERS);
 
     println!("{}", table);
+
+    // prices are in USD, note the rate they were converted at
+    if rate.currency != Currency::Usd {
+        println!("Prices converted at {}.", rate.describe());
+    }
+
+    if !adjustments.is_empty() {
+        println!("Prices include the discounts, uplifts and tax in your adjustments.");
+    }
+
+    Ok(())
 }
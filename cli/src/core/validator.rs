@@ -2,7 +2,21 @@ use linked_hash_map::LinkedHashMap;
 
 #[derive(Debug, Clone)]
 pub enum ValidatedOptions {
-    Init {},
+    Init {
+        currency: Option<String>,
+    },
+    Cost {
+        config: Option<String>,
+        region: Option<String>,
+        format: Option<String>,
+        currency: Option<String>,
+        compare_regions: bool,
+    },
+    CostDiff {
+        range: Option<String>,
+        budget: Option<String>,
+        currency: Option<String>,
+    },
     Help {},
     Deploy {},
     None,
@@ -10,11 +24,38 @@ pub enum ValidatedOptions {
 
 pub fn validate(
     command: &str,
-    _options: LinkedHashMap<String, Option<String>>,
-    _raw_args: Option<Vec<String>>,
+    options: LinkedHashMap<String, Option<String>>,
+    raw_args: Option<Vec<String>>,
 ) -> ValidatedOptions {
     match command {
-        "init" => ValidatedOptions::Init {},
+        "init" => ValidatedOptions::Init {
+            currency: options.get("currency").cloned().flatten(),
+        },
+        // infra cost diff main..HEAD
+        "cost"
+            if raw_args
+                .as_ref()
+                .and_then(|args| args.get(1))
+                .map(String::as_str)
+                == Some("diff") =>
+        {
+            ValidatedOptions::CostDiff {
+                range: raw_args
+                    .as_ref()
+                    .and_then(|args| args.get(2))
+                    .filter(|arg| !arg.starts_with('-'))
+                    .cloned(),
+                budget: options.get("budget").cloned().flatten(),
+                currency: options.get("currency").cloned().flatten(),
+            }
+        }
+        "cost" => ValidatedOptions::Cost {
+            config: options.get("config").cloned().flatten(),
+            region: options.get("region").cloned().flatten(),
+            format: options.get("format").cloned().flatten(),
+            currency: options.get("currency").cloned().flatten(),
+            compare_regions: options.contains_key("compare-regions"),
+        },
         "help" => ValidatedOptions::Help {},
         "deploy" => ValidatedOptions::Deploy {},
         _ => ValidatedOptions::None,
@@ -3,6 +3,7 @@ use once_cell::sync::Lazy;
 pub static COMMANDS_LIST: Lazy<Vec<String>> = Lazy::new(|| {
     vec![
         String::from("init"),
+        String::from("cost"),
         String::from("deploy"),
         String::from("help"),
     ]
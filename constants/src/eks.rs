@@ -0,0 +1,5 @@
+// hourly fee of an eks cluster whose kubernetes version is in standard support
+pub const EKS_CLUSTER_HOURLY_PRICE: f64 = 0.10;
+
+// added to the hourly fee while the kubernetes version is in extended support
+pub const EKS_EXTENDED_SUPPORT_HOURLY_SURCHARGE: f64 = 0.50;
@@ -7,7 +7,11 @@ pub static HELP_COMMAND: Lazy<String> = Lazy::new(|| {
 
   {}    {}            Deploy the latest version of your project with Infralink.
 
-  {}                        Setup a new projeect to deploy with Infralink.
+  {}    {}          Setup a new projeect to deploy with Infralink.
+
+  {}    {}            Estimate the monthly cost of your infra.toml.
+
+  {}    {}     Compare the monthly cost of two git refs.
 
 
   {}    {}            Build a docker image given a directory. 
@@ -25,6 +29,11 @@ Join our Discord community: {}"#,
         "deploy".bright_magenta(),
         "./path".bright_black(),
         "init".bright_cyan(),
+        "--currency".bright_black(),
+        "cost".bright_green(),
+        "--format".bright_black(),
+        "cost diff".bright_green(),
+        "main..HEAD".bright_black(),
         "build".bright_blue(),
         "./path".bright_black(),
         "upgrade".bright_yellow(),
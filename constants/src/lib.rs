@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod eks;
 pub mod help;
 pub mod regions;
 pub mod render_config;
@@ -0,0 +1,313 @@
+use indexmap::IndexMap;
+use types::{
+    budget::{Budget, Threshold},
+    currency::ExchangeRate,
+};
+
+use crate::report::{self, CostReport};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Failure,
+}
+
+// A threshold of a budget an app went over
+#[derive(Debug)]
+pub struct Violation {
+    pub app_name: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+// Check a cost against a hard limit, then a warning threshold
+fn check_threshold(
+    violations: &mut Vec<Violation>,
+    app_name: &str,
+    label: &str,
+    cost: f64,
+    threshold: &Threshold,
+    rate: &ExchangeRate,
+) {
+    let (severity, amount, kind) = match (threshold.limit, threshold.warning) {
+        (Some(limit), _) if cost > limit => (Severity::Failure, limit, "limit"),
+        (_, Some(warning)) if cost > warning => (Severity::Warning, warning, "warning threshold"),
+        _ => return,
+    };
+
+    violations.push(Violation {
+        app_name: app_name.to_string(),
+        severity,
+        message: format!(
+            "{} for {} of {} is above its {} {}.",
+            label,
+            app_name,
+            rate.format(cost),
+            rate.format(amount),
+            kind
+        ),
+    });
+}
+
+// Every threshold an app went over: its monthly cost, its components, and how much it went up
+// from the previous breakdown
+pub fn budget_violations(
+    report: &CostReport,
+    budgets: &IndexMap<String, Budget>,
+    previous_breakdown: Option<&IndexMap<String, IndexMap<String, f64>>>,
+    rate: &ExchangeRate,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (app_name, costs) in &report.breakdowns {
+        let Some(budget) = budgets.get(app_name) else {
+            continue;
+        };
+
+        let total_cost = report::app_total(costs);
+
+        let threshold = Threshold {
+            warning: budget.warning,
+            limit: budget.limit,
+        };
+
+        check_threshold(
+            &mut violations,
+            app_name,
+            "Cost",
+            total_cost,
+            &threshold,
+            rate,
+        );
+
+        // a component the app doesn't use costs nothing
+        for (component, threshold) in &budget.components {
+            let cost = costs.get(component).copied().unwrap_or_default();
+
+            check_threshold(&mut violations, app_name, component, cost, threshold, rate);
+        }
+
+        // a new app has nothing to have gone up from
+        let previous_cost = previous_breakdown
+            .and_then(|previous| previous.get(app_name))
+            .map(report::app_total)
+            .filter(|previous_cost| *previous_cost > 0.0);
+
+        if let (Some(max_increase), Some(previous_cost)) = (budget.max_increase, previous_cost) {
+            let increase = (total_cost - previous_cost) / previous_cost * 100.0;
+
+            if increase > max_increase {
+                violations.push(Violation {
+                    app_name: app_name.clone(),
+                    severity: Severity::Failure,
+                    message: format!(
+                        "Cost for {} went up {:.1}% from {} to {}, more than the {}% allowed.",
+                        app_name,
+                        increase,
+                        rate.format(previous_cost),
+                        rate.format(total_cost),
+                        max_increase
+                    ),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+// One line per violation, failures first
+pub fn violation_lines(violations: &[Violation]) -> String {
+    let mut violations: Vec<&Violation> = violations.iter().collect();
+
+    violations.sort_by_key(|violation| std::cmp::Reverse(violation.severity));
+
+    violations
+        .iter()
+        .map(|violation| match violation.severity {
+            Severity::Failure => format!("- ❌ {}", violation.message),
+            Severity::Warning => format!("- ⚠️ {}", violation.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use types::adjustments::Adjustments;
+
+    use super::*;
+
+    fn breakdown(lines: &[(&str, f64)]) -> IndexMap<String, f64> {
+        lines
+            .iter()
+            .map(|(line, cost)| (line.to_string(), *cost))
+            .collect()
+    }
+
+    // Report of a single app costing $500 a month, $60 of it data transfer
+    fn report() -> CostReport {
+        let mut report = CostReport::new(&Adjustments::default());
+
+        report.breakdowns.insert(
+            String::from("web"),
+            breakdown(&[
+                ("Instances", 440.0),
+                ("Data Transfer", 60.0),
+                ("Total", 500.0),
+            ]),
+        );
+
+        report
+    }
+
+    fn violations(budget: Budget, previous: Option<f64>) -> Vec<Violation> {
+        let budgets = IndexMap::from([(String::from("web"), budget)]);
+        let previous = previous.map(|previous| {
+            IndexMap::from([(
+                String::from("web"),
+                breakdown(&[("Instances", previous), ("Total", previous)]),
+            )])
+        });
+
+        budget_violations(&report(), &budgets, previous.as_ref(), &ExchangeRate::usd())
+    }
+
+    #[test]
+    fn cost_within_budget_has_no_violations() {
+        let budget = Budget {
+            warning: Some(600.0),
+            limit: Some(800.0),
+            ..Budget::default()
+        };
+
+        assert!(violations(budget, None).is_empty());
+    }
+
+    #[test]
+    fn cost_above_the_warning_threshold_warns() {
+        let violations = violations(
+            Budget {
+                warning: Some(400.0),
+                limit: Some(800.0),
+                ..Budget::default()
+            },
+            None,
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(
+            violations[0].message,
+            "Cost for web of $500.00 is above its $400.00 warning threshold."
+        );
+    }
+
+    #[test]
+    fn cost_above_the_limit_fails_rather_than_warns() {
+        let violations = violations(
+            Budget {
+                warning: Some(300.0),
+                limit: Some(450.0),
+                ..Budget::default()
+            },
+            None,
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Failure);
+        assert_eq!(
+            violations[0].message,
+            "Cost for web of $500.00 is above its $450.00 limit."
+        );
+    }
+
+    #[test]
+    fn components_are_checked_against_their_own_thresholds() {
+        let violations = violations(
+            Budget {
+                components: BTreeMap::from([
+                    (
+                        String::from("Data Transfer"),
+                        Threshold {
+                            warning: None,
+                            limit: Some(50.0),
+                        },
+                    ),
+                    // a component the app doesn't use costs nothing
+                    (
+                        String::from("NAT Gateway"),
+                        Threshold {
+                            warning: Some(0.0),
+                            limit: None,
+                        },
+                    ),
+                ]),
+                ..Budget::default()
+            },
+            None,
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Failure);
+        assert_eq!(
+            violations[0].message,
+            "Data Transfer for web of $60.00 is above its $50.00 limit."
+        );
+    }
+
+    #[test]
+    fn increase_above_the_maximum_fails() {
+        let budget = Budget {
+            max_increase: Some(10.0),
+            ..Budget::default()
+        };
+
+        // within the allowed increase, and an app that is new or cost nothing before
+        assert!(violations(budget.clone(), Some(475.0)).is_empty());
+        assert!(violations(budget.clone(), None).is_empty());
+        assert!(violations(budget.clone(), Some(0.0)).is_empty());
+
+        let violations = violations(budget, Some(400.0));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, Severity::Failure);
+        assert_eq!(
+            violations[0].message,
+            "Cost for web went up 25.0% from $400.00 to $500.00, more than the 10% allowed."
+        );
+    }
+
+    #[test]
+    fn apps_without_a_budget_are_not_checked() {
+        let budgets = IndexMap::from([(String::from("api"), Budget::with_limit(1.0))]);
+
+        assert!(budget_violations(&report(), &budgets, None, &ExchangeRate::usd()).is_empty());
+    }
+
+    #[test]
+    fn violations_are_listed_failures_first() {
+        let violations = violations(
+            Budget {
+                warning: Some(400.0),
+                components: BTreeMap::from([(
+                    String::from("Data Transfer"),
+                    Threshold {
+                        warning: None,
+                        limit: Some(50.0),
+                    },
+                )]),
+                ..Budget::default()
+            },
+            None,
+        );
+
+        assert_eq!(
+            violation_lines(&violations),
+            "- ❌ Data Transfer for web of $60.00 is above its $50.00 limit.\n\
+             - ⚠️ Cost for web of $500.00 is above its $400.00 warning threshold."
+        );
+    }
+}
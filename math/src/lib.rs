@@ -1,68 +1,312 @@
+pub mod budget;
+pub mod report;
+
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use constants::eks::{EKS_CLUSTER_HOURLY_PRICE, EKS_EXTENDED_SUPPORT_HOURLY_SURCHARGE};
 use constants::regions::UNSUPPORTED_REGIONS;
 use indexmap::IndexMap;
 use pricing_sdk::BlockStorageQuery;
+use pricing_sdk::BlockStorageResponse;
+use pricing_sdk::DatabaseInstanceQuery;
+use pricing_sdk::DatabaseInstanceResponse;
+use pricing_sdk::DatabaseStorageQuery;
+use pricing_sdk::DatabaseStorageResponse;
 use pricing_sdk::ExternalDataTransferQuery;
+use pricing_sdk::NetworkServicesQuery;
+use pricing_sdk::NetworkServicesResponse;
+use pricing_sdk::ObjectStorageQuery;
+use pricing_sdk::ObjectStorageResponse;
 use pricing_sdk::OnDemandQuery;
 use pricing_sdk::PricingQuery;
 use pricing_sdk::SpotQuery;
-use types::deployment_configuration::DeploymentConfiguration;
-use types::region::AwsRegion;
+use types::adjustments::{Adjustments, ServiceCategory};
+use types::cloud_provider::CloudProvider;
+use types::config::{Database, Network, ObjectStorage, SnapshotRetention};
+use types::currency::ExchangeRate;
+use types::deployment_configuration::{ControlPlane, DeploymentConfiguration, NodeScaling};
+use types::region::Region;
+
+// Monthly total of a deployment at its smallest, expected and largest size
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostRange {
+    pub min: f64,
+    pub expected: f64,
+    pub max: f64,
+}
+
+impl CostRange {
+    pub fn convert(&self, rate: &ExchangeRate) -> CostRange {
+        CostRange {
+            min: (rate.convert(self.min) * 100.0).round() / 100.0,
+            expected: (rate.convert(self.expected) * 100.0).round() / 100.0,
+            max: (rate.convert(self.max) * 100.0).round() / 100.0,
+        }
+    }
+}
+
+// Convert a USD breakdown to the currency of the rate, rounding every line to 2 dp
+pub fn convert_breakdown(
+    breakdown: &IndexMap<String, f64>,
+    rate: &ExchangeRate,
+) -> IndexMap<String, f64> {
+    breakdown
+        .iter()
+        .map(|(line, cost)| (line.clone(), (rate.convert(*cost) * 100.0).round() / 100.0))
+        .collect()
+}
+
+// Category a breakdown line is discounted under, the total has none
+fn line_category(line: &str) -> Option<ServiceCategory> {
+    match line {
+        "Control Plane" | "Instances" => Some(ServiceCategory::Compute),
+        "Control Plane Storage"
+        | "Storage"
+        | "Snapshots"
+        | "Object Storage"
+        | "Object Storage Requests"
+        | "Object Storage Retrieval" => Some(ServiceCategory::Storage),
+        "Database Instance" | "Database Storage" | "Database Backup" => {
+            Some(ServiceCategory::Database)
+        }
+        "Load Balancer" | "NAT Gateway" | "Public IPv4" | "Data Transfer" => {
+            Some(ServiceCategory::Network)
+        }
+        _ => None,
+    }
+}
+
+// Effective price of every line of a list price breakdown, with the total recomputed from
+// the adjusted lines so it includes tax
+pub fn apply_adjustments(
+    breakdown: &IndexMap<String, f64>,
+    region: &Region,
+    adjustments: &Adjustments,
+) -> IndexMap<String, f64> {
+    let mut effective: IndexMap<String, f64> = breakdown
+        .iter()
+        .filter(|(line, _)| line.as_str() != "Total")
+        .map(|(line, cost)| {
+            let factor = adjustments.factor(line_category(line), region);
+
+            (line.clone(), (cost * factor * 100.0).round() / 100.0)
+        })
+        .collect();
+
+    if breakdown.contains_key("Total") {
+        let total = effective.values().sum::<f64>();
+        effective.insert(String::from("Total"), (total * 100.0).round() / 100.0);
+    }
+
+    effective
+}
+
+// Parse the region of a pricing row, given the provider it was ingested from
+fn row_region(provider: &str, region: &str) -> Result<Region, String> {
+    let provider = CloudProvider::from_str(provider).map_err(|e| e.to_string())?;
+
+    Region::from_provider(&provider, region)
+}
+
+// Monthly price of a volume, only charging for the iops and throughput above the baseline
+// that is already included in the price per gb
+pub fn volume_monthly_cost(
+    price: &BlockStorageResponse,
+    size_gb: f64,
+    iops: Option<u64>,
+    throughput: Option<u64>,
+) -> f64 {
+    let billable = |provisioned: Option<u64>, included: Option<u32>| {
+        provisioned
+            .map(|provisioned| provisioned.saturating_sub(included.unwrap_or(0) as u64) as f64)
+            .unwrap_or(0.0)
+    };
+
+    size_gb * price.price_per_gb_month
+        + billable(iops, price.included_iops) * price.price_per_iops_month.unwrap_or(0.0)
+        + billable(throughput, price.included_throughput)
+            * price.price_per_mibps_month.unwrap_or(0.0)
+}
+
+// Monthly price of the snapshots kept for a volume. Snapshots are incremental, so the first one
+// stores the whole volume and every later one only the blocks that changed since
+pub fn snapshot_monthly_cost(
+    price: &BlockStorageResponse,
+    size_gb: f64,
+    retention: &SnapshotRetention,
+) -> f64 {
+    if retention.count == 0 {
+        return 0.0;
+    }
+
+    let stored_gb = size_gb + size_gb * retention.change_rate * (retention.count - 1) as f64;
+
+    stored_gb * price.snapshot_price_per_gb_month.unwrap_or(0.0)
+}
+
+// Monthly price of stored objects, requests and retrievals, in that order
+pub fn object_storage_monthly_cost(
+    price: &ObjectStorageResponse,
+    object_storage: &ObjectStorage,
+) -> (f64, f64, f64) {
+    let storage = object_storage.size * price.price_per_gb_month;
+    let requests = object_storage.put_requests.unwrap_or(0) as f64 / 1000.0
+        * price.price_per_1k_put_requests
+        + object_storage.get_requests.unwrap_or(0) as f64 / 1000.0
+            * price.price_per_1k_get_requests;
+    let retrieval = object_storage.retrieval.unwrap_or(0.0) * price.retrieval_price_per_gb;
+
+    (storage, requests, retrieval)
+}
+
+// Monthly price of a database instance, its storage and backups, in that order.
+// Backups are free up to the allocated storage
+pub fn database_monthly_cost(
+    instance: &DatabaseInstanceResponse,
+    storage: &DatabaseStorageResponse,
+    database: &Database,
+) -> (f64, f64, f64) {
+    let instance_price = instance.price_per_hour * 730.0;
+    let storage_price = database.storage * storage.price_per_gb_month;
+    let backup_price = (database.backup.unwrap_or(0.0) - database.storage).max(0.0)
+        * storage.backup_price_per_gb_month.unwrap_or(0.0);
+
+    (instance_price, storage_price, backup_price)
+}
+
+// Monthly price of the load balancer, the NAT gateway and public IPv4 addresses, in that order.
+// Nodes without a public address reach the internet through a NAT gateway, which has one itself
+pub fn network_monthly_cost(
+    prices: &[NetworkServicesResponse],
+    network: &Network,
+    node_count: f64,
+    outbound_data_gb: u64,
+) -> (f64, f64, f64) {
+    let price = |service: &str, usage: &str| {
+        prices
+            .iter()
+            .find(|price| price.service == service && price.usage == usage)
+            .map(|price| price.price_per_unit)
+            .unwrap_or(0.0)
+    };
+
+    let load_balancer = network
+        .ingress
+        .as_ref()
+        .and_then(|ingress| ingress.service())
+        .map(|service| {
+            (price(service, "hours") + network.lcu.unwrap_or(1.0) * price(service, "lcu")) * 730.0
+        })
+        .unwrap_or(0.0);
+
+    let public_ip = network.public_ip.unwrap_or(true);
+
+    let nat_gateway = if public_ip {
+        0.0
+    } else {
+        price("nat-gateway", "hours") * 730.0
+            + outbound_data_gb as f64 * price("nat-gateway", "data-processed")
+    };
+
+    let addresses = if public_ip { node_count } else { 1.0 };
+    let public_ipv4 = addresses * price("public-ipv4", "hours") * 730.0;
+
+    (load_balancer, nat_gateway, public_ipv4)
+}
 
 pub async fn calculate_deployment(
     deployment_configuration: DeploymentConfiguration,
-) -> HashMap<AwsRegion, IndexMap<String, f64>> {
+) -> Result<HashMap<Region, IndexMap<String, f64>>, String> {
+    Ok(calculate_deployment_range(deployment_configuration)
+        .await?
+        .into_iter()
+        .map(|(region, (breakdown, _))| (region, breakdown))
+        .collect())
+}
+
+// Total at the smallest, expected and largest number of nodes, the expected one throughout
+// without scaling
+fn cost_range(
+    scaling: Option<&NodeScaling>,
+    expected: f64,
+    total_for: impl Fn(f64) -> f64,
+) -> CostRange {
+    match scaling {
+        Some(scaling) => CostRange {
+            min: total_for(scaling.min),
+            expected,
+            max: total_for(scaling.max),
+        },
+        None => CostRange {
+            min: expected,
+            expected,
+            max: expected,
+        },
+    }
+}
+
+// Breakdown at the expected number of nodes, along with the total at the smallest, expected and
+// largest number of nodes the deployment scales between. Regions missing a price the deployment
+// needs are left out
+pub async fn calculate_deployment_range(
+    deployment_configuration: DeploymentConfiguration,
+) -> Result<HashMap<Region, (IndexMap<String, f64>, CostRange)>, String> {
     let mut query = PricingQuery::start();
 
-    // load spot & on-demand options for the control plane
-    if deployment_configuration.spot_control_plane {
-        query.with_spot(
-            None,
-            SpotQuery {
-                availability_zones: None,
-                instance_types: None,
-                max_price_per_hour: None,
-                min_price_per_hour: None,
-                regions: Some(
-                    deployment_configuration
-                        .regions
-                        .clone()
-                        .into_iter()
-                        .map(|r| r.code())
-                        .collect(),
-                ),
-                sort_by: Some(String::from("price_per_hour")),
-                sort_order: Some(String::from("asc")),
-                limit: None,
-            },
-        );
-    } else {
-        query.with_on_demand(
-            Some(String::from("controlPlaneOnDemand")),
-            OnDemandQuery {
-                instance_types: None,
-                max_memory: None,
-                max_price_per_hour: None,
-                max_vcpu: None,
-                min_memory: Some(deployment_configuration.control_plane_specs.unwrap().1),
-                min_price_per_hour: None,
-                min_vcpu: Some(deployment_configuration.control_plane_specs.unwrap().0),
-                regions: Some(
-                    deployment_configuration
-                        .regions
-                        .clone()
-                        .into_iter()
-                        .map(|r| r.code())
-                        .collect(),
-                ),
-                sort_by: Some(String::from("price_per_hour")),
-                sort_order: Some(String::from("asc")),
-                limit: None,
-            },
-        );
-    };
+    // load spot & on-demand options for a self-hosted control plane
+    match deployment_configuration.control_plane {
+        ControlPlane::SelfHosted { spot: true, .. } => {
+            query.with_spot(
+                None,
+                SpotQuery {
+                    availability_zones: None,
+                    instance_types: None,
+                    max_price_per_hour: None,
+                    min_price_per_hour: None,
+                    providers: None,
+                    regions: Some(
+                        deployment_configuration
+                            .regions
+                            .clone()
+                            .into_iter()
+                            .filter_map(|r| r.qualified_code())
+                            .collect(),
+                    ),
+                    sort_by: Some(String::from("price_per_hour")),
+                    sort_order: Some(String::from("asc")),
+                    limit: None,
+                },
+            );
+        }
+        ControlPlane::SelfHosted { specs, spot: false } => {
+            query.with_on_demand(
+                Some(String::from("controlPlaneOnDemand")),
+                OnDemandQuery {
+                    instance_types: None,
+                    max_memory: None,
+                    max_price_per_hour: None,
+                    max_vcpu: None,
+                    min_memory: Some(specs.unwrap().1),
+                    min_price_per_hour: None,
+                    min_vcpu: Some(specs.unwrap().0),
+                    providers: None,
+                    regions: Some(
+                        deployment_configuration
+                            .regions
+                            .clone()
+                            .into_iter()
+                            .filter_map(|r| r.qualified_code())
+                            .collect(),
+                    ),
+                    sort_by: Some(String::from("price_per_hour")),
+                    sort_order: Some(String::from("asc")),
+                    limit: None,
+                },
+            );
+        }
+        ControlPlane::Eks { .. } | ControlPlane::None => {}
+    }
 
     // load options for the nodes
     query.with_on_demand(
@@ -75,12 +319,13 @@ pub async fn calculate_deployment(
             min_memory: Some(deployment_configuration.node_specs.1),
             min_price_per_hour: None,
             min_vcpu: Some(deployment_configuration.node_specs.0),
+            providers: None,
             regions: Some(
                 deployment_configuration
                     .regions
                     .clone()
                     .into_iter()
-                    .map(|r| r.code())
+                    .filter_map(|r| r.qualified_code())
                     .collect(),
             ),
             sort_by: Some(String::from("price_per_hour")),
@@ -91,86 +336,211 @@ pub async fn calculate_deployment(
 
     // load options for the storage
     query.with_block_storage(BlockStorageQuery {
+        providers: None,
         regions: Some(
             deployment_configuration
                 .regions
                 .clone()
                 .into_iter()
-                .map(|r| r.code())
+                .filter_map(|r| r.qualified_code())
                 .collect(),
         ),
         sort_by: Some(String::from("price_per_gb_month")),
         sort_order: Some(String::from("asc")),
-        storage_media: Some(String::from("SSD")),
-        volume_api_name: None,
+        // the volume type decides the media, st1 and sc1 are HDD
+        storage_media: deployment_configuration
+            .volume
+            .is_none()
+            .then(|| String::from("SSD")),
+        volume_api_name: deployment_configuration
+            .volume
+            .as_ref()
+            .map(|volume| volume.volume_api_name.clone()),
     });
 
+    // load options for the object storage
+    if let Some(object_storage) = deployment_configuration.object_storage.as_ref() {
+        query.with_object_storage(ObjectStorageQuery {
+            providers: None,
+            regions: Some(
+                deployment_configuration
+                    .regions
+                    .clone()
+                    .into_iter()
+                    .filter_map(|r| r.qualified_code())
+                    .collect(),
+            ),
+            sort_by: None,
+            sort_order: None,
+            storage_classes: Some(vec![object_storage.class().to_string()]),
+        });
+    }
+
+    // load options for the database
+    if let Some(database) = deployment_configuration.database.as_ref() {
+        query.with_database_instance(DatabaseInstanceQuery {
+            deployment_options: Some(vec![database.deployment_option().to_string()]),
+            engines: Some(vec![database.engine.clone()]),
+            instance_types: database
+                .instance_type
+                .as_ref()
+                .map(|instance_type| vec![instance_type.clone()]),
+            max_memory: None,
+            max_vcpu: None,
+            min_memory: database.memory,
+            min_vcpu: database.vcpu.map(|vcpu| vcpu as f32),
+            providers: None,
+            regions: Some(
+                deployment_configuration
+                    .regions
+                    .clone()
+                    .into_iter()
+                    .filter_map(|r| r.qualified_code())
+                    .collect(),
+            ),
+            sort_by: Some(String::from("price_per_hour")),
+            sort_order: Some(String::from("asc")),
+        });
+
+        query.with_database_storage(DatabaseStorageQuery {
+            deployment_options: Some(vec![database.deployment_option().to_string()]),
+            providers: None,
+            regions: Some(
+                deployment_configuration
+                    .regions
+                    .clone()
+                    .into_iter()
+                    .filter_map(|r| r.qualified_code())
+                    .collect(),
+            ),
+            sort_by: None,
+            sort_order: None,
+            volume_types: Some(vec![database.volume_type()]),
+        });
+    }
+
+    // load options for the load balancer, nat gateway and public addresses
+    if let Some(network) = deployment_configuration.network.as_ref() {
+        let mut services = vec![String::from("nat-gateway"), String::from("public-ipv4")];
+
+        if let Some(service) = network
+            .ingress
+            .as_ref()
+            .and_then(|ingress| ingress.service())
+        {
+            services.push(service.to_string());
+        }
+
+        query.with_network_services(NetworkServicesQuery {
+            providers: None,
+            regions: Some(
+                deployment_configuration
+                    .regions
+                    .clone()
+                    .into_iter()
+                    .filter_map(|r| r.qualified_code())
+                    .collect(),
+            ),
+            services: Some(services),
+            sort_by: None,
+            sort_order: None,
+        });
+    }
+
     // load options for the outbound data transfer
     query.with_external_data_transfer(ExternalDataTransferQuery {
         from_region_code: None,
+        providers: None,
         sort_by: None,
         sort_order: None,
         start_range: Some(deployment_configuration.storage_size_gb as i32),
     });
 
-    let mut total_deployment_cost: HashMap<AwsRegion, IndexMap<String, f64>> = HashMap::new();
+    let mut total_deployment_cost: HashMap<Region, (IndexMap<String, f64>, CostRange)> =
+        HashMap::new();
 
     query.end();
 
-    let result = query.execute().await.unwrap();
+    let result = query
+        .execute()
+        .await
+        .map_err(|e| format!("Failed to fetch prices: {}", e))?;
 
-    let mut control_plane_cost: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut control_plane_cost: HashMap<Region, f64> = HashMap::new();
 
     // calculate and populate the cost of the control plane
-    if deployment_configuration.spot_control_plane {
-        let spot_options = result.data.spot.unwrap();
+    match deployment_configuration.control_plane {
+        ControlPlane::SelfHosted { spot: true, .. } => {
+            let spot_options = result.data.spot.as_deref().unwrap_or_default();
 
-        let mut cheapest_spot_in_region: HashMap<AwsRegion, f64> = HashMap::new();
+            let mut cheapest_spot_in_region: HashMap<Region, f64> = HashMap::new();
 
-        for spot in spot_options {
-            let region = AwsRegion::from_str(&spot.region).unwrap();
-            let current_price = cheapest_spot_in_region.get(&region);
+            for spot in spot_options {
+                let region = row_region(&spot.provider, &spot.region)?;
+                let current_price = cheapest_spot_in_region.get(&region);
 
-            if current_price.is_none() || *current_price.unwrap() > spot.price_per_hour {
-                cheapest_spot_in_region.insert(region, spot.price_per_hour);
+                if current_price.is_none() || *current_price.unwrap() > spot.price_per_hour {
+                    cheapest_spot_in_region.insert(region, spot.price_per_hour);
+                }
             }
-        }
 
-        for (region, price) in cheapest_spot_in_region {
-            control_plane_cost.insert(region, price);
+            for (region, price) in cheapest_spot_in_region {
+                control_plane_cost.insert(region, price);
+            }
         }
-    } else {
-        let on_demand_options = result.data.control_plane_on_demand.as_ref().unwrap();
+        ControlPlane::SelfHosted { spot: false, .. } => {
+            let on_demand_options = result
+                .data
+                .control_plane_on_demand
+                .as_deref()
+                .unwrap_or_default();
 
-        let mut cheapest_on_demand_in_region: HashMap<AwsRegion, f64> = HashMap::new();
+            let mut cheapest_on_demand_in_region: HashMap<Region, f64> = HashMap::new();
 
-        for on_demand in on_demand_options {
-            if on_demand.price_per_hour == 0.0 {
-                continue;
-            }
+            for on_demand in on_demand_options {
+                if on_demand.price_per_hour == 0.0 {
+                    continue;
+                }
 
-            let region = AwsRegion::from_str(&on_demand.region).unwrap();
-            let current_price = cheapest_on_demand_in_region.get(&region);
+                let region = row_region(&on_demand.provider, &on_demand.region)?;
+                let current_price = cheapest_on_demand_in_region.get(&region);
 
-            if current_price.is_none() || *current_price.unwrap() > on_demand.price_per_hour {
-                cheapest_on_demand_in_region.insert(region, on_demand.price_per_hour);
+                if current_price.is_none() || *current_price.unwrap() > on_demand.price_per_hour {
+                    cheapest_on_demand_in_region.insert(region, on_demand.price_per_hour);
+                }
+            }
+
+            for (region, price) in cheapest_on_demand_in_region {
+                control_plane_cost.insert(region, price);
             }
         }
+        ControlPlane::Eks { extended_support } => {
+            let price = if extended_support {
+                EKS_CLUSTER_HOURLY_PRICE + EKS_EXTENDED_SUPPORT_HOURLY_SURCHARGE
+            } else {
+                EKS_CLUSTER_HOURLY_PRICE
+            };
 
-        for (region, price) in cheapest_on_demand_in_region {
-            control_plane_cost.insert(region, price);
+            for region in deployment_configuration.regions.iter() {
+                control_plane_cost.insert(region.clone(), price);
+            }
+        }
+        ControlPlane::None => {
+            for region in deployment_configuration.regions.iter() {
+                control_plane_cost.insert(region.clone(), 0.0);
+            }
         }
     }
 
-    let mut node_cost: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut node_cost: HashMap<Region, f64> = HashMap::new();
 
     // calculate and populate the cost of the nodes per-region
-    let on_demand_options = result.data.node_on_demand.as_ref().unwrap();
+    let on_demand_options = result.data.node_on_demand.as_deref().unwrap_or_default();
 
-    let mut cheapest_node_in_region: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut cheapest_node_in_region: HashMap<Region, f64> = HashMap::new();
 
     for on_demand in on_demand_options {
-        let region = AwsRegion::from_str(&on_demand.region).unwrap();
+        let region = row_region(&on_demand.provider, &on_demand.region)?;
         let current_price = cheapest_node_in_region.get(&region);
 
         if on_demand.price_per_hour == 0.0 {
@@ -187,35 +557,49 @@ pub async fn calculate_deployment(
     }
 
     // calculate and populate the cost of the storage per-region
-    let block_storage_options = result.data.block_storage.as_ref().unwrap();
+    let block_storage_options = result.data.block_storage.as_deref().unwrap_or_default();
 
-    let mut cheapest_storage_in_region: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut cheapest_storage_in_region: HashMap<Region, BlockStorageResponse> = HashMap::new();
 
     for block_storage in block_storage_options {
-        let region = AwsRegion::from_str(&block_storage.region).unwrap();
-        let current_price = cheapest_storage_in_region.get(&region);
+        let region = row_region(&block_storage.provider, &block_storage.region)?;
+        let current = cheapest_storage_in_region.get(&region);
 
-        if current_price.is_none() || *current_price.unwrap() > block_storage.price_per_gb_month {
-            cheapest_storage_in_region.insert(region, block_storage.price_per_gb_month);
+        if current.is_none()
+            || current.unwrap().price_per_gb_month > block_storage.price_per_gb_month
+        {
+            cheapest_storage_in_region.insert(region, block_storage.clone());
         }
     }
 
-    let mut storage_cost: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut storage_cost: HashMap<Region, BlockStorageResponse> = HashMap::new();
 
     for (region, price) in cheapest_storage_in_region {
         storage_cost.insert(region, price);
     }
 
     // calculate and populate the cost of the outbound data transfer per-region
-    let external_data_transfer_options = result.data.external_data_transfer.as_ref().unwrap();
+    let external_data_transfer_options = result
+        .data
+        .external_data_transfer
+        .as_deref()
+        .unwrap_or_default();
 
-    let mut cheapest_data_transfer_in_region: HashMap<AwsRegion, f64> = HashMap::new();
+    let mut cheapest_data_transfer_in_region: HashMap<Region, f64> = HashMap::new();
 
     for external_data_transfer in external_data_transfer_options {
         if !external_data_transfer.from_region_code.is_empty()
             && !UNSUPPORTED_REGIONS.contains(&external_data_transfer.from_region_code.as_str())
         {
-            let region = AwsRegion::from_str(&external_data_transfer.from_region_code).unwrap();
+            // transfer prices are fetched for every region, skip the ones we can't price
+            let region = match row_region(
+                &external_data_transfer.provider,
+                &external_data_transfer.from_region_code,
+            ) {
+                Ok(region) => region,
+                Err(_) => continue,
+            };
+
             let current_price = cheapest_data_transfer_in_region.get(&region);
 
             if current_price.is_none()
@@ -227,87 +611,430 @@ pub async fn calculate_deployment(
         }
     }
 
-    let mut data_transfer_cost: HashMap<AwsRegion, f64> = HashMap::new();
+    // the object storage class is fixed, so there is one option per region
+    let mut object_storage_cost: HashMap<Region, ObjectStorageResponse> = HashMap::new();
+
+    if let Some(object_storage_options) = result.data.object_storage.as_ref() {
+        for object_storage in object_storage_options {
+            let region = row_region(&object_storage.provider, &object_storage.region)?;
+
+            object_storage_cost.insert(region, object_storage.clone());
+        }
+    }
+
+    let mut cheapest_database_instance_in_region: HashMap<Region, DatabaseInstanceResponse> =
+        HashMap::new();
+
+    if let Some(database_instance_options) = result.data.database_instance.as_ref() {
+        for database_instance in database_instance_options {
+            let region = row_region(&database_instance.provider, &database_instance.region)?;
+            let current = cheapest_database_instance_in_region.get(&region);
+
+            if current.is_none()
+                || current.unwrap().price_per_hour > database_instance.price_per_hour
+            {
+                cheapest_database_instance_in_region.insert(region, database_instance.clone());
+            }
+        }
+    }
+
+    // the storage type and deployment option are fixed, so there is one option per region
+    let mut database_storage_cost: HashMap<Region, DatabaseStorageResponse> = HashMap::new();
+
+    if let Some(database_storage_options) = result.data.database_storage.as_ref() {
+        for database_storage in database_storage_options {
+            let region = row_region(&database_storage.provider, &database_storage.region)?;
+
+            database_storage_cost.insert(region, database_storage.clone());
+        }
+    }
+
+    let mut network_services_cost: HashMap<Region, Vec<NetworkServicesResponse>> = HashMap::new();
+
+    if let Some(network_services_options) = result.data.network_services.as_ref() {
+        for network_service in network_services_options {
+            let region = row_region(&network_service.provider, &network_service.region)?;
+
+            network_services_cost
+                .entry(region)
+                .or_default()
+                .push(network_service.clone());
+        }
+    }
+
+    let mut data_transfer_cost: HashMap<Region, f64> = HashMap::new();
 
     for (region, price) in cheapest_data_transfer_in_region {
         data_transfer_cost.insert(region, price);
     }
 
     for region in deployment_configuration.regions {
-        let control_plane_price = control_plane_cost.get(&region).unwrap();
-        let node_price = node_cost.get(&region).unwrap();
-        let storage_price = storage_cost.get(&region).unwrap();
-        let data_transfer_price = data_transfer_cost.get(&region).unwrap();
-
-        let control_plane_monthly_price = ((control_plane_price * 730.0) * 100.0).round() / 100.0;
-        let control_plane_storage_price = (8.0 * storage_price * 100.0).round() / 100.0;
-        let node_monthly_price =
-            ((deployment_configuration.node_count * node_price * 730.0) * 100.0).round() / 100.0;
-        let node_storage_price = ((deployment_configuration.storage_size_gb
-            * storage_price
-            * deployment_configuration.node_count)
-            * 100.0)
-            .round()
-            / 100.0;
-        let data_transfer_price =
-            (data_transfer_price * deployment_configuration.outbound_data_gb as f64 * 100.0)
+        let (
+            Some(control_plane_price),
+            Some(node_price),
+            Some(storage_price),
+            Some(data_transfer_price),
+        ) = (
+            control_plane_cost.get(&region),
+            node_cost.get(&region),
+            storage_cost.get(&region),
+            data_transfer_cost.get(&region),
+        )
+        else {
+            continue;
+        };
+
+        // an object storage, database or network without a price would be left out of the total
+        if deployment_configuration.object_storage.is_some()
+            && !object_storage_cost.contains_key(&region)
+        {
+            continue;
+        }
+
+        if deployment_configuration.database.is_some()
+            && !(cheapest_database_instance_in_region.contains_key(&region)
+                && database_storage_cost.contains_key(&region))
+        {
+            continue;
+        }
+
+        if deployment_configuration.network.is_some()
+            && !network_services_cost.contains_key(&region)
+        {
+            continue;
+        }
+
+        // the breakdown for a given number of nodes
+        let breakdown_for = |node_count: f64| {
+            let control_plane_monthly_price =
+                ((control_plane_price * 730.0) * 100.0).round() / 100.0;
+            // only a self-hosted control plane has a volume of its own
+            let control_plane_storage_price = match deployment_configuration.control_plane {
+                ControlPlane::SelfHosted { .. } => {
+                    (8.0 * storage_price.price_per_gb_month * 100.0).round() / 100.0
+                }
+                ControlPlane::Eks { .. } | ControlPlane::None => 0.0,
+            };
+            let node_monthly_price = ((node_count * node_price * 730.0) * 100.0).round() / 100.0;
+            let volume = deployment_configuration.volume.as_ref();
+            let node_storage_price = ((volume_monthly_cost(
+                storage_price,
+                deployment_configuration.storage_size_gb,
+                volume.and_then(|volume| volume.iops),
+                volume.and_then(|volume| volume.throughput),
+            ) * node_count)
+                * 100.0)
                 .round()
                 / 100.0;
+            let snapshot_price =
+                volume
+                    .and_then(|volume| volume.snapshots.as_ref())
+                    .map(|retention| {
+                        ((snapshot_monthly_cost(
+                            storage_price,
+                            deployment_configuration.storage_size_gb,
+                            retention,
+                        ) * node_count)
+                            * 100.0)
+                            .round()
+                            / 100.0
+                    });
+            let data_transfer_price =
+                (data_transfer_price * deployment_configuration.outbound_data_gb as f64 * 100.0)
+                    .round()
+                    / 100.0;
 
-        let total_cost = control_plane_monthly_price
-            + control_plane_storage_price
-            + node_monthly_price
-            + node_storage_price
-            + data_transfer_price;
+            let object_storage_price = deployment_configuration
+                .object_storage
+                .as_ref()
+                .zip(object_storage_cost.get(&region))
+                .map(|(object_storage, price)| {
+                    let (storage, requests, retrieval) =
+                        object_storage_monthly_cost(price, object_storage);
 
-        // round to 2 dp
-        let total_cost = (total_cost * 100.0).round() / 100.0;
+                    (
+                        (storage * 100.0).round() / 100.0,
+                        (requests * 100.0).round() / 100.0,
+                        (retrieval * 100.0).round() / 100.0,
+                    )
+                });
 
-        let mut breakdown = IndexMap::new();
+            let database_price = deployment_configuration
+                .database
+                .as_ref()
+                .zip(cheapest_database_instance_in_region.get(&region))
+                .zip(database_storage_cost.get(&region))
+                .map(|((database, instance), storage)| {
+                    let (instance, storage, backup) =
+                        database_monthly_cost(instance, storage, database);
 
-        breakdown.insert(String::from("Control Plane"), control_plane_monthly_price);
-        breakdown.insert(
-            String::from("Control Plane Storage"),
-            control_plane_storage_price,
-        );
+                    (
+                        (instance * 100.0).round() / 100.0,
+                        (storage * 100.0).round() / 100.0,
+                        (backup * 100.0).round() / 100.0,
+                    )
+                });
+
+            let network_price = deployment_configuration.network.as_ref().map(|network| {
+                let (load_balancer, nat_gateway, public_ipv4) = network_monthly_cost(
+                    &network_services_cost[&region],
+                    network,
+                    node_count,
+                    deployment_configuration.outbound_data_gb,
+                );
+
+                (
+                    (load_balancer * 100.0).round() / 100.0,
+                    (nat_gateway * 100.0).round() / 100.0,
+                    (public_ipv4 * 100.0).round() / 100.0,
+                )
+            });
+
+            let total_cost = control_plane_monthly_price
+                + control_plane_storage_price
+                + node_monthly_price
+                + node_storage_price
+                + snapshot_price.unwrap_or(0.0)
+                + object_storage_price
+                    .map(|(storage, requests, retrieval)| storage + requests + retrieval)
+                    .unwrap_or(0.0)
+                + database_price
+                    .map(|(instance, storage, backup)| instance + storage + backup)
+                    .unwrap_or(0.0)
+                + network_price
+                    .map(|(load_balancer, nat_gateway, public_ipv4)| {
+                        load_balancer + nat_gateway + public_ipv4
+                    })
+                    .unwrap_or(0.0)
+                + data_transfer_price;
+
+            // round to 2 dp
+            let total_cost = (total_cost * 100.0).round() / 100.0;
+
+            let mut breakdown = IndexMap::new();
+
+            match deployment_configuration.control_plane {
+                ControlPlane::SelfHosted { .. } => {
+                    breakdown.insert(String::from("Control Plane"), control_plane_monthly_price);
+                    breakdown.insert(
+                        String::from("Control Plane Storage"),
+                        control_plane_storage_price,
+                    );
+                }
+                ControlPlane::Eks { .. } => {
+                    breakdown.insert(String::from("Control Plane"), control_plane_monthly_price);
+                }
+                ControlPlane::None => {}
+            }
 
-        breakdown.insert(String::from("Instances"), node_monthly_price);
-        breakdown.insert(String::from("Storage"), node_storage_price);
-        breakdown.insert(String::from("Data Transfer"), data_transfer_price);
-        breakdown.insert(String::from("Total"), total_cost);
+            breakdown.insert(String::from("Instances"), node_monthly_price);
+            breakdown.insert(String::from("Storage"), node_storage_price);
 
-        total_deployment_cost.insert(region, breakdown);
+            if let Some(snapshot_price) = snapshot_price {
+                breakdown.insert(String::from("Snapshots"), snapshot_price);
+            }
+
+            if let Some((storage, requests, retrieval)) = object_storage_price {
+                breakdown.insert(String::from("Object Storage"), storage);
+                breakdown.insert(String::from("Object Storage Requests"), requests);
+
+                if retrieval > 0.0 {
+                    breakdown.insert(String::from("Object Storage Retrieval"), retrieval);
+                }
+            }
+
+            if let Some((instance, storage, backup)) = database_price {
+                breakdown.insert(String::from("Database Instance"), instance);
+                breakdown.insert(String::from("Database Storage"), storage);
+                breakdown.insert(String::from("Database Backup"), backup);
+            }
+
+            if let Some((load_balancer, nat_gateway, public_ipv4)) = network_price {
+                let network = deployment_configuration.network.as_ref().unwrap();
+
+                if network
+                    .ingress
+                    .as_ref()
+                    .is_some_and(|ingress| ingress.service().is_some())
+                {
+                    breakdown.insert(String::from("Load Balancer"), load_balancer);
+                }
+
+                if !network.public_ip.unwrap_or(true) {
+                    breakdown.insert(String::from("NAT Gateway"), nat_gateway);
+                }
+
+                breakdown.insert(String::from("Public IPv4"), public_ipv4);
+            }
+
+            breakdown.insert(String::from("Data Transfer"), data_transfer_price);
+            breakdown.insert(String::from("Total"), total_cost);
+
+            breakdown
+        };
+
+        let breakdown = breakdown_for(deployment_configuration.node_count);
+
+        let range = cost_range(
+            deployment_configuration.scaling.as_ref(),
+            breakdown["Total"],
+            |node_count| breakdown_for(node_count)["Total"],
+        );
+
+        total_deployment_cost.insert(region, (breakdown, range));
     }
 
-    total_deployment_cost
+    Ok(total_deployment_cost)
 }
 
 pub async fn calculate_cheapest_deployment(
-    regions: Vec<AwsRegion>,
-) -> HashMap<AwsRegion, IndexMap<String, f64>> {
+    regions: Vec<Region>,
+) -> Result<HashMap<Region, IndexMap<String, f64>>, String> {
     calculate_deployment(DeploymentConfiguration {
         regions,
-        control_plane_specs: None,
-        spot_control_plane: true,
+        control_plane: ControlPlane::SelfHosted {
+            specs: None,
+            spot: true,
+        },
         node_specs: (1, 1.0),
         storage_size_gb: 8.0,
         node_count: 1.0,
         outbound_data_gb: 0,
+        volume: None,
+        object_storage: None,
+        database: None,
+        network: None,
+        scaling: None,
     })
     .await
 }
 
 pub async fn calculate_large_deployment(
-    regions: Vec<AwsRegion>,
-) -> HashMap<AwsRegion, IndexMap<String, f64>> {
+    regions: Vec<Region>,
+) -> Result<HashMap<Region, IndexMap<String, f64>>, String> {
     calculate_deployment(DeploymentConfiguration {
         regions,
-        control_plane_specs: Some((2, 4.0)),
-        spot_control_plane: false,
+        control_plane: ControlPlane::SelfHosted {
+            specs: Some((2, 4.0)),
+            spot: false,
+        },
         node_specs: (4, 8.0),
         storage_size_gb: 50.0,
         node_count: 3.0,
         outbound_data_gb: 1000,
+        volume: None,
+        object_storage: None,
+        database: None,
+        network: None,
+        scaling: None,
     })
     .await
 }
+
+pub async fn calculate_large_eks_deployment(
+    regions: Vec<Region>,
+) -> Result<HashMap<Region, IndexMap<String, f64>>, String> {
+    calculate_deployment(DeploymentConfiguration {
+        regions,
+        control_plane: ControlPlane::Eks {
+            extended_support: false,
+        },
+        node_specs: (4, 8.0),
+        storage_size_gb: 50.0,
+        node_count: 3.0,
+        outbound_data_gb: 1000,
+        volume: None,
+        object_storage: None,
+        database: None,
+        network: None,
+        scaling: None,
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use types::config::{Scaling, Utilisation};
+
+    use super::*;
+
+    // a control plane and nodes at $0.05 an hour, rounded like a breakdown
+    fn total_for(node_count: f64) -> f64 {
+        ((73.0 + node_count * 0.05 * 730.0) * 100.0).round() / 100.0
+    }
+
+    fn range(scaling: &Scaling) -> CostRange {
+        let node_scaling = NodeScaling {
+            min: scaling.min_replicas as f64,
+            max: scaling.max_replicas as f64,
+        };
+
+        cost_range(
+            Some(&node_scaling),
+            total_for(scaling.expected_replicas()),
+            total_for,
+        )
+    }
+
+    #[test]
+    fn range_is_ordered_for_every_profile() {
+        let profiles = [
+            None,
+            Some(Utilisation::Daily(vec![0.0; 24])),
+            Some(Utilisation::Daily(vec![1.0; 24])),
+            Some(Utilisation::Daily(
+                (0..24).map(|hour| hour as f64 / 23.0).collect(),
+            )),
+            Some(Utilisation::Weekly {
+                weekday: vec![0.9; 24],
+                weekend: vec![0.1; 24],
+            }),
+        ];
+
+        for utilisation in profiles {
+            let scaling = Scaling {
+                min_replicas: 2,
+                max_replicas: 12,
+                utilisation: utilisation.clone(),
+            };
+
+            let range = range(&scaling);
+
+            assert!(
+                range.min <= range.expected && range.expected <= range.max,
+                "{:?} is out of order for {:?}",
+                range,
+                utilisation
+            );
+        }
+    }
+
+    #[test]
+    fn range_spans_the_replica_bounds() {
+        let range = range(&Scaling {
+            min_replicas: 2,
+            max_replicas: 12,
+            utilisation: Some(Utilisation::Weekly {
+                weekday: vec![0.5; 24],
+                weekend: vec![0.5; 24],
+            }),
+        });
+
+        assert_eq!(
+            range,
+            CostRange {
+                min: total_for(2.0),
+                expected: total_for(7.0),
+                max: total_for(12.0),
+            }
+        );
+    }
+
+    #[test]
+    fn range_without_scaling_is_the_expected_total() {
+        let range = cost_range(None, total_for(3.0), total_for);
+
+        assert_eq!(range.min, range.expected);
+        assert_eq!(range.max, range.expected);
+    }
+}
@@ -0,0 +1,354 @@
+use indexmap::IndexMap;
+use types::{
+    adjustments::Adjustments,
+    budget::Budget,
+    config::InfrastructureConfiguration,
+    currency::{Currency, ExchangeRate},
+    deployment_configuration::DeploymentConfiguration,
+    region::Region,
+};
+
+use crate::CostRange;
+
+// Cost of every app found in a commit, in USD
+pub struct CostReport {
+    pub breakdowns: IndexMap<String, IndexMap<String, f64>>,
+    pub ranges: IndexMap<String, CostRange>,
+    // breakdowns after discounts, uplifts and tax, same lines as the list price ones
+    pub effective: IndexMap<String, IndexMap<String, f64>>,
+    pub adjusted: bool,
+    // first currency declared by an infra.toml
+    pub currency: Option<Currency>,
+    // configuration files left out of the report, with why, by path
+    pub errors: IndexMap<String, String>,
+    // budgets declared by the infra.toml of each app
+    pub budgets: IndexMap<String, Budget>,
+}
+
+impl CostReport {
+    pub fn new(adjustments: &Adjustments) -> Self {
+        Self {
+            breakdowns: IndexMap::new(),
+            ranges: IndexMap::new(),
+            effective: IndexMap::new(),
+            adjusted: !adjustments.is_empty(),
+            currency: None,
+            errors: IndexMap::new(),
+            budgets: IndexMap::new(),
+        }
+    }
+
+    // Price an app and add it to the report under name
+    pub async fn add_app(
+        &mut self,
+        name: String,
+        configuration: &InfrastructureConfiguration,
+        adjustments: &Adjustments,
+    ) -> Result<(), String> {
+        let (breakdown, range, effective) = app_cost(configuration, adjustments).await?;
+
+        self.currency = self.currency.or(configuration.currency);
+
+        if let Some(budget) = &configuration.budget {
+            self.budgets.insert(name.clone(), budget.clone());
+        }
+        self.ranges.insert(name.clone(), range);
+        self.effective.insert(name.clone(), effective);
+        self.breakdowns.insert(name, breakdown);
+
+        Ok(())
+    }
+
+    // Total monthly cost of every app, in USD
+    pub fn total(&self) -> f64 {
+        self.breakdowns.values().map(app_total).sum()
+    }
+}
+
+// Sum of the lines of a breakdown, leaving out the total line it may already carry
+pub fn app_total(costs: &IndexMap<String, f64>) -> f64 {
+    costs
+        .iter()
+        .filter(|(line, _)| line.as_str() != "Total")
+        .map(|(_, cost)| cost)
+        .sum()
+}
+
+// Breakdown at the expected number of replicas, with the range of the total when autoscaled
+// and the breakdown after adjustments
+pub async fn app_cost(
+    configuration: &InfrastructureConfiguration,
+    adjustments: &Adjustments,
+) -> Result<(IndexMap<String, f64>, CostRange, IndexMap<String, f64>), String> {
+    let region =
+        Region::from_provider(&configuration.app.cloud_provider, &configuration.app.region)?;
+
+    let deployment =
+        DeploymentConfiguration::from_infrastructure(configuration, vec![region.clone()])?;
+
+    let result = crate::calculate_deployment_range(deployment).await?;
+
+    let (breakdown, range) = result
+        .get(&region)
+        .cloned()
+        .ok_or_else(|| format!("No prices found for {}", configuration.app.region))?;
+
+    let effective = crate::apply_adjustments(&breakdown, &region, adjustments);
+
+    Ok((breakdown, range, effective))
+}
+
+// Price every app, keyed by the app name
+pub async fn cost_report(
+    configurations: &[InfrastructureConfiguration],
+    adjustments: &Adjustments,
+) -> Result<CostReport, String> {
+    let mut report = CostReport::new(adjustments);
+
+    for configuration in configurations {
+        report
+            .add_app(configuration.app.name.clone(), configuration, adjustments)
+            .await?;
+    }
+
+    Ok(report)
+}
+
+// Whether any app or line of the breakdowns differs from the previous breakdowns, a missing
+// previous breakdown counts as a change
+pub fn has_changes(
+    previous_breakdown: Option<&IndexMap<String, IndexMap<String, f64>>>,
+    breakdowns: &IndexMap<String, IndexMap<String, f64>>,
+) -> bool {
+    let Some(previous) = previous_breakdown else {
+        return true;
+    };
+
+    if previous.len() != breakdowns.len() {
+        return true;
+    }
+
+    breakdowns
+        .iter()
+        .any(|(app, costs)| match previous.get(app) {
+            Some(prev_costs) => {
+                prev_costs.len() != costs.len()
+                    || costs.iter().any(|(service, cost)| {
+                        prev_costs
+                            .get(service)
+                            .is_none_or(|prev_cost| (cost - prev_cost).abs() > 1e-9)
+                    })
+            }
+            None => true,
+        })
+}
+
+// Markdown of every app's breakdown, with the change from the previous breakdown if any
+pub fn markdown(
+    previous_breakdown: Option<IndexMap<String, IndexMap<String, f64>>>,
+    report: &CostReport,
+    rate: &ExchangeRate,
+) -> String {
+    let breakdowns = &report.breakdowns;
+    let ranges = &report.ranges;
+
+    // Calculate the total monthly cost for all apps
+    let total_cost: f64 = breakdowns.iter().map(|(_, costs)| app_total(costs)).sum();
+
+    // Calculate the previous total cost, if available
+    let prev_total_cost: Option<f64> = previous_breakdown
+        .as_ref()
+        .map(|prev| prev.iter().map(|(_, costs)| app_total(costs)).sum());
+
+    // Calculate the total cost change and percentage, if previous data is available
+    let (total_cost_diff, _total_percent_diff) = if let Some(prev_total) = prev_total_cost {
+        let diff = total_cost - prev_total;
+        let percent_diff = (diff / prev_total) * 100.0;
+        let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
+        (
+            format!(" (Change: {} {:.1}%)", sign, percent_diff.abs()),
+            true,
+        )
+    } else {
+        (String::from(""), false)
+    };
+
+    // Show the range of the total when any of the apps autoscales
+    let range_line = if ranges.values().any(|range| range.min != range.max) {
+        let min: f64 = ranges.values().map(|range| range.min).sum();
+        let max: f64 = ranges.values().map(|range| range.max).sum();
+
+        format!(
+            "\n- **Monthly Cost Range**: {} – {}",
+            rate.format(min),
+            rate.format(max)
+        )
+    } else {
+        String::from("")
+    };
+
+    // Show what is actually paid once discounts, uplifts and tax are applied
+    let effective_line = if report.adjusted {
+        let effective: f64 = report
+            .effective
+            .values()
+            .map(|costs| costs.get("Total").copied().unwrap_or_default())
+            .sum();
+
+        format!("\n- **Effective Monthly Cost**: {}", rate.format(effective))
+    } else {
+        String::from("")
+    };
+
+    // Create the header and summary
+    let header = format!(
+      "## Cost Breakdown 📊\n\n## Summary\n\n- **Total Monthly Cost**: {}{}{}{}\n\n---\n\n## Detailed Breakdown",
+      rate.format(total_cost), total_cost_diff, effective_line, range_line
+    );
+
+    // Generate the markdown for each app
+    let markdown = breakdowns
+      .iter()
+      .map(|(app, costs)| {
+          let prev_costs = previous_breakdown.as_ref().and_then(|prev| prev.get(app));
+          let has_change_for_app = costs.iter().any(|(service, cost)| {
+              if service != "Total" && service != "Data Transfer" {
+                  prev_costs.is_some_and(|prev| {
+                      prev.get(service).is_some_and(|prev_cost| (cost - prev_cost).abs() > 1e-9)
+                  })
+              } else {
+                  false
+              }
+          });
+
+          let effective_costs = report.effective.get(app).filter(|_| report.adjusted);
+
+          let table_header = match (has_change_for_app, effective_costs.is_some()) {
+              (true, true) => "| Service            | List  | Effective | Change (%) |\n|--------------------|-------|-----------|------------|",
+              (true, false) => "| Service            | Cost  | Change (%) |\n|--------------------|-------|------------|",
+              (false, true) => "| Service            | List  | Effective |\n|--------------------|-------|-----------|",
+              (false, false) => "| Service            | Cost  |\n|--------------------|-------|",
+          };
+
+          // effective price next to the list price, when the repository has adjustments
+          let effective_column = |service: &str| {
+              effective_costs
+                  .map(|effective| {
+                      format!("| {} ", rate.format(effective.get(service).copied().unwrap_or_default()))
+                  })
+                  .unwrap_or_default()
+          };
+
+          let costs_table = costs
+              .iter()
+              .filter_map(|(service, cost)| {
+                  if service != "Total" && service != "Data Transfer" {
+                      let change_str = if let Some(prev_cost) = prev_costs.and_then(|prev| prev.get(service)) {
+                          let diff = cost - prev_cost;
+                          let percent = (diff / prev_cost) * 100.0;
+                          if diff.abs() > 1e-9 {
+                              let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
+                              Some(format!("{} {:.1}%", sign, percent.abs()))
+                          } else {
+                              None
+                          }
+                      } else {
+                          None
+                      };
+
+                      let change_column = if has_change_for_app {
+                          format!("| {}", change_str.unwrap_or_default())
+                      } else {
+                          "".to_string()
+                      };
+
+                      Some(format!("| {} | {} {}{}", service, rate.format(*cost), effective_column(service), change_column))
+                  } else {
+                      None
+                  }
+              })
+              .collect::<Vec<_>>()
+              .join("\n");
+
+          let total_cost = app_total(costs);
+          let total_cost_diff = if let Some(prev_costs) = prev_costs {
+              let prev_total = app_total(prev_costs);
+              let diff = total_cost - prev_total;
+              if diff.abs() > 1e-9 {
+                  let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
+                  format!("| {} {:.1}%", sign, ((diff / prev_total) * 100.0).abs())
+              } else {
+                  "| -".to_string()
+              }
+          } else {
+              "| -".to_string()
+          };
+
+          let total_effective = effective_costs
+              .map(|effective| {
+                  format!("| **{}** ", rate.format(effective.get("Total").copied().unwrap_or_default()))
+              })
+              .unwrap_or_default();
+
+          let total_line = format!("| **Total** | **{}** {}{}", rate.format(total_cost), total_effective, total_cost_diff);
+
+          // autoscaled apps cost anywhere between their smallest and largest size
+          let range_line = match ranges.get(app) {
+              Some(range) if range.min != range.max => format!(
+                  "\n| **Range** | {} – {} (expected {})",
+                  rate.format(range.min), rate.format(range.max), rate.format(range.expected)
+              ),
+              _ => String::from(""),
+          };
+
+          format!(
+              "### {}\n\n{}\n{}\n{}{}",
+              app, table_header, costs_table, total_line, range_line
+          )
+      })
+      .collect::<Vec<_>>()
+      .join("\n\n");
+
+    // costs are stored in USD, note the rate they were shown at
+    let rate_line = if rate.currency != Currency::Usd {
+        format!("\n\n_Costs converted from USD at {}._", rate.describe())
+    } else {
+        String::from("")
+    };
+
+    let adjustments_line = if report.adjusted {
+        String::from(
+            "\n\n_Effective costs include the repository's discounts, regional uplifts and tax._",
+        )
+    } else {
+        String::from("")
+    };
+
+    // files that could not be priced, so the costs above are known to be incomplete
+    let errors_section = if report.errors.is_empty() {
+        String::from("")
+    } else {
+        let errors = report
+            .errors
+            .iter()
+            .map(|(path, error)| {
+                format!(
+                    "- `{}`\n\n  ```\n  {}\n  ```",
+                    path,
+                    error.trim().replace('\n', "\n  ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        format!(
+            "\n\n## Configuration Errors ⚠️\n\nThese files were left out of the costs above:\n\n{}",
+            errors
+        )
+    };
+
+    format!(
+        "{}\n\n{}{}{}{}",
+        header, markdown, errors_section, rate_line, adjustments_line
+    )
+}
@@ -1,11 +1,18 @@
-use miette::{IntoDiagnostic, Result};
+use miette::{miette, IntoDiagnostic, Result};
 use reqwest::Client;
 use serde_json::json;
 
 use crate::models::{
-    block_storage_query::BlockStorageQuery, external_transfer_query::ExternalDataTransferQuery,
-    inter_region_transfer_query::InterRegionDataTransferQuery, on_demand_query::OnDemandQuery,
-    pricing_response::PricingResponse, spot_query::SpotQuery,
+    block_storage_query::BlockStorageQuery,
+    database_instance_query::DatabaseInstanceQuery,
+    database_storage_query::DatabaseStorageQuery,
+    external_transfer_query::ExternalDataTransferQuery,
+    inter_region_transfer_query::InterRegionDataTransferQuery,
+    network_services_query::NetworkServicesQuery,
+    object_storage_query::ObjectStorageQuery,
+    on_demand_query::OnDemandQuery,
+    pricing_response::{GraphQLResponse, PricingResponse},
+    spot_query::SpotQuery,
 };
 
 pub struct PricingQuery {
@@ -74,6 +81,15 @@ impl PricingQuery {
             self.query.push_str(&format!("minVcpu: {},", min_vcpu));
         }
 
+        if let Some(providers) = on_demand_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
         if let Some(regions) = on_demand_options.regions {
             self.query.push_str("regions: [");
             for region in regions {
@@ -101,6 +117,7 @@ impl PricingQuery {
         self.query.push_str(" instanceType");
         self.query.push_str(" memory");
         self.query.push_str(" pricePerHour");
+        self.query.push_str(" provider");
         self.query.push_str(" region");
         self.query.push_str(" vcpuCount");
 
@@ -142,6 +159,15 @@ impl PricingQuery {
                 .push_str(&format!("minPricePerHour: {},", min_price_per_hour));
         }
 
+        if let Some(providers) = spot_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
         if let Some(regions) = spot_options.regions {
             self.query.push_str("regions: [");
             for region in regions {
@@ -167,6 +193,7 @@ impl PricingQuery {
 
         self.query.push_str("instanceType");
         self.query.push_str(" pricePerHour");
+        self.query.push_str(" provider");
         self.query.push_str(" region");
         self.query.push_str(" availabilityZone");
 
@@ -191,6 +218,15 @@ impl PricingQuery {
                 .push_str(&format!("toRegionCode: \"{}\",", to_region_code));
         }
 
+        if let Some(providers) = inter_region_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
         if let Some(sort_by) = inter_region_options.sort_by {
             self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
         }
@@ -204,6 +240,7 @@ impl PricingQuery {
 
         self.query.push_str("fromRegionCode");
         self.query.push_str(" pricePerGb");
+        self.query.push_str(" provider");
         self.query.push_str(" toRegionCode");
 
         self.query.push('}');
@@ -222,6 +259,15 @@ impl PricingQuery {
                 .push_str(&format!("fromRegionCode: \"{}\",", from_region_code));
         }
 
+        if let Some(providers) = external_data_transfer_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
         if let Some(sort_by) = external_data_transfer_options.sort_by {
             self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
         }
@@ -241,6 +287,7 @@ impl PricingQuery {
         self.query.push_str("endRange");
         self.query.push_str(" fromRegionCode");
         self.query.push_str(" pricePerGb");
+        self.query.push_str(" provider");
         self.query.push_str(" startRange");
 
         self.query.push('}');
@@ -251,6 +298,15 @@ impl PricingQuery {
     pub fn with_block_storage(&mut self, block_storage_options: BlockStorageQuery) -> &mut Self {
         self.query.push_str(" blockStorage(request: {");
 
+        if let Some(providers) = block_storage_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
         if let Some(regions) = block_storage_options.regions {
             self.query.push_str("regions: [");
             for region in regions {
@@ -280,8 +336,14 @@ impl PricingQuery {
 
         self.query.push_str("}) {");
 
-        self.query.push_str("pricePerGbMonth");
+        self.query.push_str("includedIops");
+        self.query.push_str(" includedThroughput");
+        self.query.push_str(" pricePerGbMonth");
+        self.query.push_str(" pricePerIopsMonth");
+        self.query.push_str(" pricePerMibpsMonth");
+        self.query.push_str(" provider");
         self.query.push_str(" region");
+        self.query.push_str(" snapshotPricePerGbMonth");
         self.query.push_str(" storageMedia");
         self.query.push_str(" volumeApiName");
 
@@ -290,6 +352,262 @@ impl PricingQuery {
         self
     }
 
+    pub fn with_object_storage(&mut self, object_storage_options: ObjectStorageQuery) -> &mut Self {
+        self.query.push_str(" objectStorage(request: {");
+
+        if let Some(providers) = object_storage_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(regions) = object_storage_options.regions {
+            self.query.push_str("regions: [");
+            for region in regions {
+                self.query.push_str(&format!("\"{}\",", region));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(sort_by) = object_storage_options.sort_by {
+            self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
+        }
+
+        if let Some(sort_order) = object_storage_options.sort_order {
+            self.query
+                .push_str(&format!("sortOrder: \"{}\",", sort_order));
+        }
+
+        if let Some(storage_classes) = object_storage_options.storage_classes {
+            self.query.push_str("storageClasses: [");
+            for storage_class in storage_classes {
+                self.query.push_str(&format!("\"{}\",", storage_class));
+            }
+            self.query.push_str("],");
+        }
+
+        self.query.push_str("}) {");
+
+        self.query.push_str("pricePer1kGetRequests");
+        self.query.push_str(" pricePer1kPutRequests");
+        self.query.push_str(" pricePerGbMonth");
+        self.query.push_str(" provider");
+        self.query.push_str(" region");
+        self.query.push_str(" retrievalPricePerGb");
+        self.query.push_str(" storageClass");
+
+        self.query.push('}');
+
+        self
+    }
+
+    pub fn with_database_instance(
+        &mut self,
+        database_instance_options: DatabaseInstanceQuery,
+    ) -> &mut Self {
+        self.query.push_str(" databaseInstance(request: {");
+
+        if let Some(deployment_options) = database_instance_options.deployment_options {
+            self.query.push_str("deploymentOptions: [");
+            for deployment_option in deployment_options {
+                self.query.push_str(&format!("\"{}\",", deployment_option));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(engines) = database_instance_options.engines {
+            self.query.push_str("engines: [");
+            for engine in engines {
+                self.query.push_str(&format!("\"{}\",", engine));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(instance_types) = database_instance_options.instance_types {
+            self.query.push_str("instanceTypes: [");
+            for instance_type in instance_types {
+                self.query.push_str(&format!("\"{}\",", instance_type));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(max_memory) = database_instance_options.max_memory {
+            self.query.push_str(&format!("maxMemory: {},", max_memory));
+        }
+
+        if let Some(max_vcpu) = database_instance_options.max_vcpu {
+            self.query.push_str(&format!("maxVcpu: {},", max_vcpu));
+        }
+
+        if let Some(min_memory) = database_instance_options.min_memory {
+            self.query.push_str(&format!("minMemory: {},", min_memory));
+        }
+
+        if let Some(min_vcpu) = database_instance_options.min_vcpu {
+            self.query.push_str(&format!("minVcpu: {},", min_vcpu));
+        }
+
+        if let Some(providers) = database_instance_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(regions) = database_instance_options.regions {
+            self.query.push_str("regions: [");
+            for region in regions {
+                self.query.push_str(&format!("\"{}\",", region));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(sort_by) = database_instance_options.sort_by {
+            self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
+        }
+
+        if let Some(sort_order) = database_instance_options.sort_order {
+            self.query
+                .push_str(&format!("sortOrder: \"{}\",", sort_order));
+        }
+
+        self.query.push_str("}) {");
+
+        self.query.push_str("deploymentOption");
+        self.query.push_str(" engine");
+        self.query.push_str(" instanceType");
+        self.query.push_str(" memory");
+        self.query.push_str(" pricePerHour");
+        self.query.push_str(" provider");
+        self.query.push_str(" region");
+        self.query.push_str(" vcpuCount");
+
+        self.query.push('}');
+
+        self
+    }
+
+    pub fn with_database_storage(
+        &mut self,
+        database_storage_options: DatabaseStorageQuery,
+    ) -> &mut Self {
+        self.query.push_str(" databaseStorage(request: {");
+
+        if let Some(deployment_options) = database_storage_options.deployment_options {
+            self.query.push_str("deploymentOptions: [");
+            for deployment_option in deployment_options {
+                self.query.push_str(&format!("\"{}\",", deployment_option));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(providers) = database_storage_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(regions) = database_storage_options.regions {
+            self.query.push_str("regions: [");
+            for region in regions {
+                self.query.push_str(&format!("\"{}\",", region));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(sort_by) = database_storage_options.sort_by {
+            self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
+        }
+
+        if let Some(sort_order) = database_storage_options.sort_order {
+            self.query
+                .push_str(&format!("sortOrder: \"{}\",", sort_order));
+        }
+
+        if let Some(volume_types) = database_storage_options.volume_types {
+            self.query.push_str("volumeTypes: [");
+            for volume_type in volume_types {
+                self.query.push_str(&format!("\"{}\",", volume_type));
+            }
+            self.query.push_str("],");
+        }
+
+        self.query.push_str("}) {");
+
+        self.query.push_str("backupPricePerGbMonth");
+        self.query.push_str(" deploymentOption");
+        self.query.push_str(" pricePerGbMonth");
+        self.query.push_str(" provider");
+        self.query.push_str(" region");
+        self.query.push_str(" volumeType");
+
+        self.query.push('}');
+
+        self
+    }
+
+    pub fn with_network_services(
+        &mut self,
+        network_services_options: NetworkServicesQuery,
+    ) -> &mut Self {
+        self.query.push_str(" networkServices(request: {");
+
+        if let Some(providers) = network_services_options.providers {
+            self.query.push_str("providers: [");
+            for provider in providers {
+                self.query
+                    .push_str(&format!("{},", provider.to_uppercase()));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(regions) = network_services_options.regions {
+            self.query.push_str("regions: [");
+            for region in regions {
+                self.query.push_str(&format!("\"{}\",", region));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(services) = network_services_options.services {
+            self.query.push_str("services: [");
+            for service in services {
+                self.query.push_str(&format!("\"{}\",", service));
+            }
+            self.query.push_str("],");
+        }
+
+        if let Some(sort_by) = network_services_options.sort_by {
+            self.query.push_str(&format!("sortBy: \"{}\",", sort_by));
+        }
+
+        if let Some(sort_order) = network_services_options.sort_order {
+            self.query
+                .push_str(&format!("sortOrder: \"{}\",", sort_order));
+        }
+
+        self.query.push_str("}) {");
+
+        self.query.push_str("pricePerUnit");
+        self.query.push_str(" provider");
+        self.query.push_str(" region");
+        self.query.push_str(" service");
+        self.query.push_str(" unit");
+        self.query.push_str(" usage");
+
+        self.query.push('}');
+
+        self
+    }
+
     pub async fn execute(&self) -> Result<PricingResponse> {
         let client = client()?;
 
@@ -301,11 +619,25 @@ impl PricingQuery {
             .send()
             .await
             .into_diagnostic()?
-            .json::<PricingResponse>()
+            .json::<GraphQLResponse>()
             .await
             .into_diagnostic()?;
 
-        Ok(response)
+        // a field without prices fails the whole query, report which one instead of the missing data
+        match response {
+            GraphQLResponse {
+                data: Some(data), ..
+            } => Ok(PricingResponse { data }),
+            GraphQLResponse { errors, .. } => Err(miette!(
+                "{}",
+                errors
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        }
     }
 
     pub fn end(&mut self) -> &mut Self {
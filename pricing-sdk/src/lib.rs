@@ -5,10 +5,16 @@ pub mod graphql;
 pub use graphql::PricingQuery;
 pub use models::{
     block_storage_query::BlockStorageQuery, block_storage_response::BlockStorageResponse,
+    database_instance_query::DatabaseInstanceQuery,
+    database_instance_response::DatabaseInstanceResponse,
+    database_storage_query::DatabaseStorageQuery,
+    database_storage_response::DatabaseStorageResponse,
     external_transfer_query::ExternalDataTransferQuery,
     external_transfer_response::ExternalDataTransferResponse,
     inter_region_transfer_query::InterRegionDataTransferQuery,
     inter_region_transfer_response::InterRegionDataTransferResponse,
-    on_demand_query::OnDemandQuery, on_demand_response::OnDemandResponse,
-    pricing_response::PricingResponse, spot_query::SpotQuery,
+    network_services_query::NetworkServicesQuery,
+    network_services_response::NetworkServicesResponse, object_storage_query::ObjectStorageQuery,
+    object_storage_response::ObjectStorageResponse, on_demand_query::OnDemandQuery,
+    on_demand_response::OnDemandResponse, pricing_response::PricingResponse, spot_query::SpotQuery,
 };
@@ -1,4 +1,5 @@
 pub struct BlockStorageQuery {
+    pub providers: Option<Vec<String>>,
     pub regions: Option<Vec<String>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
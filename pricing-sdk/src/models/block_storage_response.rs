@@ -3,8 +3,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockStorageResponse {
+    pub included_iops: Option<u32>,
+    pub included_throughput: Option<u32>,
     pub price_per_gb_month: f64,
+    pub price_per_iops_month: Option<f64>,
+    pub price_per_mibps_month: Option<f64>,
+    pub provider: String,
     pub region: String,
+    pub snapshot_price_per_gb_month: Option<f64>,
     pub storage_media: String,
     pub volume_api_name: String,
 }
@@ -0,0 +1,13 @@
+pub struct DatabaseInstanceQuery {
+    pub deployment_options: Option<Vec<String>>,
+    pub engines: Option<Vec<String>>,
+    pub instance_types: Option<Vec<String>>,
+    pub max_memory: Option<f32>,
+    pub max_vcpu: Option<f32>,
+    pub min_memory: Option<f32>,
+    pub min_vcpu: Option<f32>,
+    pub providers: Option<Vec<String>>,
+    pub regions: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
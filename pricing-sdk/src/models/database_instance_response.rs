@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseInstanceResponse {
+    pub deployment_option: String,
+    pub engine: String,
+    pub instance_type: String,
+    pub memory: f32,
+    pub price_per_hour: f64,
+    pub provider: String,
+    pub region: String,
+    pub vcpu_count: f32,
+}
@@ -0,0 +1,8 @@
+pub struct DatabaseStorageQuery {
+    pub deployment_options: Option<Vec<String>>,
+    pub providers: Option<Vec<String>>,
+    pub regions: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    pub volume_types: Option<Vec<String>>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStorageResponse {
+    pub backup_price_per_gb_month: Option<f64>,
+    pub deployment_option: String,
+    pub price_per_gb_month: f64,
+    pub provider: String,
+    pub region: String,
+    pub volume_type: String,
+}
@@ -6,5 +6,6 @@ pub struct ExternalDataTransferResponse {
     pub end_range: i64,
     pub from_region_code: String,
     pub price_per_gb: f64,
+    pub provider: String,
     pub start_range: i64,
 }
@@ -1,5 +1,6 @@
 pub struct InterRegionDataTransferQuery {
     pub from_region_code: Option<String>,
+    pub providers: Option<Vec<String>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub to_region_code: Option<String>,
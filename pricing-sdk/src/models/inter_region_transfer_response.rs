@@ -5,5 +5,6 @@ use serde::{Deserialize, Serialize};
 pub struct InterRegionDataTransferResponse {
     pub from_region_code: String,
     pub price_per_gb: f64,
+    pub provider: String,
     pub to_region_code: String,
 }
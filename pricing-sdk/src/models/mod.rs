@@ -1,9 +1,17 @@
 pub mod block_storage_query;
 pub mod block_storage_response;
+pub mod database_instance_query;
+pub mod database_instance_response;
+pub mod database_storage_query;
+pub mod database_storage_response;
 pub mod external_transfer_query;
 pub mod external_transfer_response;
 pub mod inter_region_transfer_query;
 pub mod inter_region_transfer_response;
+pub mod network_services_query;
+pub mod network_services_response;
+pub mod object_storage_query;
+pub mod object_storage_response;
 pub mod on_demand_query;
 pub mod on_demand_response;
 pub mod pricing_response;
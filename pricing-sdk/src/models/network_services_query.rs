@@ -0,0 +1,7 @@
+pub struct NetworkServicesQuery {
+    pub providers: Option<Vec<String>>,
+    pub regions: Option<Vec<String>>,
+    pub services: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkServicesResponse {
+    pub price_per_unit: f64,
+    pub provider: String,
+    pub region: String,
+    pub service: String,
+    pub unit: String,
+    pub usage: String,
+}
@@ -0,0 +1,7 @@
+pub struct ObjectStorageQuery {
+    pub providers: Option<Vec<String>>,
+    pub regions: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    pub storage_classes: Option<Vec<String>>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStorageResponse {
+    #[serde(rename = "pricePer1kGetRequests")]
+    pub price_per_1k_get_requests: f64,
+    #[serde(rename = "pricePer1kPutRequests")]
+    pub price_per_1k_put_requests: f64,
+    pub price_per_gb_month: f64,
+    pub provider: String,
+    pub region: String,
+    pub retrieval_price_per_gb: f64,
+    pub storage_class: String,
+}
@@ -7,6 +7,7 @@ pub struct OnDemandQuery {
     pub min_memory: Option<f32>,
     pub min_price_per_hour: Option<f32>,
     pub min_vcpu: Option<u32>,
+    pub providers: Option<Vec<String>>,
     pub regions: Option<Vec<String>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
@@ -7,6 +7,7 @@ pub struct OnDemandResponse {
     pub instance_type: String,
     pub memory: f64,
     pub price_per_hour: f64,
+    pub provider: String,
     pub region: String,
     pub vcpu_count: f64,
 }
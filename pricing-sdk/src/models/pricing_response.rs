@@ -2,9 +2,13 @@ use serde::Deserialize;
 
 use super::{
     block_storage_response::BlockStorageResponse,
+    database_instance_response::DatabaseInstanceResponse,
+    database_storage_response::DatabaseStorageResponse,
     external_transfer_response::ExternalDataTransferResponse,
     inter_region_transfer_response::InterRegionDataTransferResponse,
-    on_demand_response::OnDemandResponse, spot_response::SpotResponse,
+    network_services_response::NetworkServicesResponse,
+    object_storage_response::ObjectStorageResponse, on_demand_response::OnDemandResponse,
+    spot_response::SpotResponse,
 };
 
 #[derive(Clone, Debug, Deserialize)]
@@ -22,4 +26,42 @@ pub struct Data {
     pub inter_region_data_transfer: Option<Vec<InterRegionDataTransferResponse>>,
     pub external_data_transfer: Option<Vec<ExternalDataTransferResponse>>,
     pub block_storage: Option<Vec<BlockStorageResponse>>,
+    pub object_storage: Option<Vec<ObjectStorageResponse>>,
+    pub database_instance: Option<Vec<DatabaseInstanceResponse>>,
+    pub database_storage: Option<Vec<DatabaseStorageResponse>>,
+    pub network_services: Option<Vec<NetworkServicesResponse>>,
+}
+
+// Response as sent by the api, data is null when any field failed
+#[derive(Clone, Debug, Deserialize)]
+pub struct GraphQLResponse {
+    pub data: Option<Data>,
+    pub errors: Option<Vec<GraphQLError>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GraphQLError {
+    pub message: String,
+    pub path: Option<Vec<serde_json::Value>>,
+}
+
+impl std::fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .flatten()
+            .map(|segment| match segment {
+                serde_json::Value::String(segment) => segment.clone(),
+                segment => segment.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(".");
+
+        if path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", path, self.message)
+        }
+    }
 }
@@ -3,6 +3,7 @@ pub struct SpotQuery {
     pub instance_types: Option<Vec<String>>,
     pub max_price_per_hour: Option<f32>,
     pub min_price_per_hour: Option<f32>,
+    pub providers: Option<Vec<String>>,
     pub regions: Option<Vec<String>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
@@ -6,5 +6,6 @@ pub struct SpotResponse {
     pub availability_zone: String,
     pub instance_type: String,
     pub price_per_hour: f64,
+    pub provider: String,
     pub region: String,
 }
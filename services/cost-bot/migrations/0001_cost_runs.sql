@@ -0,0 +1,14 @@
+-- Breakdowns of priced commits and the cost limits of each repository, as the bot first
+-- stored them for GitHub repositories only.
+
+CREATE TABLE IF NOT EXISTS cost_runs (
+    repository_id BIGINT NOT NULL,
+    commit_ref TEXT NOT NULL,
+    cost_breakdown JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS cost_limits (
+    repository_id BIGINT PRIMARY KEY,
+    cost_limit JSONB NOT NULL
+);
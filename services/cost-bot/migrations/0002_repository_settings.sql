@@ -0,0 +1,6 @@
+-- Settings of a repository, such as the currency its costs are shown in.
+
+CREATE TABLE IF NOT EXISTS repository_settings (
+    repository_id BIGINT PRIMARY KEY,
+    currency TEXT
+);
@@ -0,0 +1,3 @@
+-- Discounts and tax a repository pays on top of list prices, as json.
+
+ALTER TABLE repository_settings ADD COLUMN IF NOT EXISTS adjustments JSONB;
@@ -0,0 +1,7 @@
+-- Ids of the webhook deliveries received, so a delivery replayed by GitHub is not priced twice.
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id TEXT PRIMARY KEY,
+    event TEXT NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
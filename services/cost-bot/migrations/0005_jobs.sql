@@ -0,0 +1,20 @@
+-- Webhook deliveries queued for a worker. status is one of queued, running, succeeded or dead.
+-- A delivery redelivered by GitHub has the same delivery_id and is not queued twice, so the
+-- deliveries recorded before no longer need a table of their own.
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id BIGSERIAL PRIMARY KEY,
+    delivery_id TEXT NOT NULL UNIQUE,
+    event TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS jobs_due_idx ON jobs (status, run_at);
+
+DROP TABLE IF EXISTS webhook_deliveries;
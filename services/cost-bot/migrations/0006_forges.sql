@@ -0,0 +1,24 @@
+-- Key repositories by their forge and their id on it. Ids are text since Bitbucket
+-- repositories only have a uuid. Existing rows were all GitHub repositories, and so were the
+-- deliveries queued as jobs.
+
+ALTER TABLE cost_runs ADD COLUMN IF NOT EXISTS forge TEXT NOT NULL DEFAULT 'github';
+ALTER TABLE cost_runs ALTER COLUMN repository_id TYPE TEXT;
+
+ALTER TABLE cost_limits ADD COLUMN IF NOT EXISTS forge TEXT NOT NULL DEFAULT 'github';
+ALTER TABLE cost_limits ALTER COLUMN repository_id TYPE TEXT;
+ALTER TABLE cost_limits DROP CONSTRAINT IF EXISTS cost_limits_pkey;
+ALTER TABLE cost_limits ADD PRIMARY KEY (forge, repository_id);
+
+ALTER TABLE repository_settings ADD COLUMN IF NOT EXISTS forge TEXT NOT NULL DEFAULT 'github';
+ALTER TABLE repository_settings ALTER COLUMN repository_id TYPE TEXT;
+ALTER TABLE repository_settings DROP CONSTRAINT IF EXISTS repository_settings_pkey;
+ALTER TABLE repository_settings ADD PRIMARY KEY (forge, repository_id);
+
+-- deliveries of different forges may share an id
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS forge TEXT NOT NULL DEFAULT 'github';
+ALTER TABLE jobs DROP CONSTRAINT IF EXISTS jobs_delivery_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS jobs_forge_delivery_id_key ON jobs (forge, delivery_id);
+
+CREATE INDEX IF NOT EXISTS cost_runs_repository_idx
+    ON cost_runs (forge, repository_id, commit_ref);
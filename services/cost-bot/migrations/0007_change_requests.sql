@@ -0,0 +1,16 @@
+-- Cost of every priced change request before and after it, updated on every push to it.
+-- merged_at is set when it is merged, so its costs count as merged.
+
+CREATE TABLE IF NOT EXISTS change_requests (
+    forge TEXT NOT NULL,
+    repository_id TEXT NOT NULL,
+    number BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    base_ref TEXT NOT NULL,
+    head_ref TEXT NOT NULL,
+    cost_before DOUBLE PRECISION,
+    cost_after DOUBLE PRECISION NOT NULL,
+    merged_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (forge, repository_id, number)
+);
@@ -0,0 +1,5 @@
+-- Where and by whom each priced commit was made, for the cost history.
+
+ALTER TABLE cost_runs ADD COLUMN IF NOT EXISTS branch TEXT;
+ALTER TABLE cost_runs ADD COLUMN IF NOT EXISTS author TEXT;
+ALTER TABLE cost_runs ADD COLUMN IF NOT EXISTS committed_at TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+-- One run per commit, so a retried or redelivered push replaces its run rather than adding
+-- another to the history. The latest of any runs already stored twice is kept.
+
+DELETE FROM cost_runs a
+    USING cost_runs b
+    WHERE a.forge = b.forge
+        AND a.repository_id = b.repository_id
+        AND a.commit_ref = b.commit_ref
+        AND (a.created_at, a.ctid) < (b.created_at, b.ctid);
+
+DROP INDEX IF EXISTS cost_runs_repository_idx;
+
+CREATE UNIQUE INDEX IF NOT EXISTS cost_runs_commit_key
+    ON cost_runs (forge, repository_id, commit_ref);
@@ -1,3 +1,286 @@
+use std::str::FromStr;
+
+use actix_web::{
+    get,
+    http::{header::AUTHORIZATION, StatusCode},
+    web, HttpRequest, HttpResponse, ResponseError,
+};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use math::report::app_total;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::{db, forge, forge::Forge};
+
+// Most runs or change requests a single request returns
+const MAX_LIMIT: i64 = 1000;
+
+// Runs compared when looking for the largest increases
+const INCREASE_WINDOW: i64 = 1000;
+
 pub fn find_infra_toml_files() -> Vec<String> {
     vec![]
 }
+
+// Costs of a pushed commit, in USD
+#[derive(Serialize, Debug)]
+pub struct CostRun {
+    pub commit_ref: String,
+    pub branch: Option<String>,
+    pub author: Option<String>,
+    // when the commit was made, or priced when the forge didn't say
+    pub committed_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub breakdowns: IndexMap<String, IndexMap<String, f64>>,
+}
+
+impl CostRun {
+    // Monthly cost of every app
+    fn apps(&self) -> IndexMap<&str, f64> {
+        self.breakdowns
+            .iter()
+            .map(|(app_name, costs)| (app_name.as_str(), app_total(costs)))
+            .collect()
+    }
+}
+
+// A merged change request with the monthly cost of the repository before and after it, in USD
+#[derive(Serialize, Debug)]
+pub struct MergedChangeRequest {
+    pub number: u64,
+    pub title: String,
+    pub base_ref: String,
+    pub head_ref: String,
+    // None when the base was never priced
+    pub cost_before: Option<f64>,
+    pub cost_after: f64,
+    pub change: f64,
+    pub merged_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HistoryQuery {
+    pub branch: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+impl HistoryQuery {
+    fn limit(&self, default: i64) -> i64 {
+        self.limit.unwrap_or(default).clamp(1, MAX_LIMIT)
+    }
+}
+
+// Why a request for the cost history was rejected
+#[derive(Debug)]
+pub enum ApiError {
+    Unauthorized,
+    UnknownForge(String),
+    Database(String),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Unauthorized => write!(f, "A valid bearer token is required"),
+            ApiError::UnknownForge(forge) => write!(f, "No forge called {}", forge),
+            ApiError::Database(e) => write!(f, "Failed to fetch cost history: {}", e),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::UnknownForge(_) => StatusCode::NOT_FOUND,
+            ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let code = match self {
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::UnknownForge(_) => "unknown_forge",
+            ApiError::Database(_) => "database",
+        };
+
+        HttpResponse::build(self.status_code()).json(json!({
+            "error": code,
+            "message": self.to_string(),
+        }))
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        ApiError::Database(e.to_string())
+    }
+}
+
+// Register the cost history API, only once API_TOKEN is set as the costs of private
+// repositories are never served without it
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(app_costs)
+        .service(largest_increases)
+        .service(merged_change_requests);
+}
+
+// Requests need API_TOKEN as a bearer token
+pub fn authorize(req: &HttpRequest) -> Result<(), ApiError> {
+    // refused rather than open should API_TOKEN be unset after the routes were registered
+    let token = std::env::var("API_TOKEN").map_err(|_| ApiError::Unauthorized)?;
+
+    let authorized = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| forge::verify_token(&token, provided).is_ok());
+
+    if !authorized {
+        return Err(ApiError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+// The forge and id of the repository asked for, once the request is authorized
+fn repository(req: &HttpRequest, path: &(String, String)) -> Result<(Forge, String), ApiError> {
+    authorize(req)?;
+
+    let (forge, repository_id) = path;
+
+    let forge = Forge::from_str(forge).map_err(|_| ApiError::UnknownForge(forge.clone()))?;
+
+    Ok((forge, repository_id.clone()))
+}
+
+// Monthly cost of every app over time, oldest first
+#[get("/api/repositories/{forge}/{repository_id}/costs")]
+pub async fn app_costs(
+    pool: web::Data<Pool<Postgres>>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (forge, repository_id) = repository(&req, &path)?;
+
+    let runs = db::fetch_cost_runs(
+        &pool,
+        forge,
+        &repository_id,
+        query.branch.as_deref(),
+        query.since,
+        query.limit(100),
+    )
+    .await?;
+
+    let runs = runs
+        .iter()
+        .rev()
+        .map(|run| {
+            let apps = run.apps();
+
+            json!({
+                "commit_ref": run.commit_ref,
+                "branch": run.branch,
+                "author": run.author,
+                "committed_at": run.committed_at,
+                "total": apps.values().sum::<f64>(),
+                "apps": apps,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(json!({ "runs": runs })))
+}
+
+// Largest increases of the monthly cost of an app from one run to the next on the same
+// branch, largest first
+#[get("/api/repositories/{forge}/{repository_id}/increases")]
+pub async fn largest_increases(
+    pool: web::Data<Pool<Postgres>>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (forge, repository_id) = repository(&req, &path)?;
+
+    let runs = db::fetch_cost_runs(
+        &pool,
+        forge,
+        &repository_id,
+        query.branch.as_deref(),
+        query.since,
+        INCREASE_WINDOW,
+    )
+    .await?;
+
+    // the latest run seen on each branch, walking from the oldest run
+    let mut previous_runs: IndexMap<Option<&str>, &CostRun> = IndexMap::new();
+    let mut increases = Vec::new();
+
+    for run in runs.iter().rev() {
+        if let Some(previous_run) = previous_runs.insert(run.branch.as_deref(), run) {
+            let previous_apps = previous_run.apps();
+
+            for (app_name, cost) in run.apps() {
+                // an app added by the run went up from nothing
+                let previous_cost = previous_apps.get(app_name).copied().unwrap_or_default();
+
+                if cost > previous_cost {
+                    increases.push((run, previous_run, app_name, previous_cost, cost));
+                }
+            }
+        }
+    }
+
+    increases.sort_by(|a, b| (b.4 - b.3).total_cmp(&(a.4 - a.3)));
+    increases.truncate(query.limit(10) as usize);
+
+    let increases = increases
+        .iter()
+        .map(|(run, previous_run, app_name, previous_cost, cost)| {
+            json!({
+                "app": app_name,
+                "commit_ref": run.commit_ref,
+                "previous_commit_ref": previous_run.commit_ref,
+                "branch": run.branch,
+                "author": run.author,
+                "committed_at": run.committed_at,
+                "previous_cost": previous_cost,
+                "cost": cost,
+                "increase": cost - previous_cost,
+                "percent": (*previous_cost > 0.0)
+                    .then(|| (cost - previous_cost) / previous_cost * 100.0),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(json!({ "increases": increases })))
+}
+
+// Change in monthly cost of every merged change request, latest first
+#[get("/api/repositories/{forge}/{repository_id}/merged")]
+pub async fn merged_change_requests(
+    pool: web::Data<Pool<Postgres>>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (forge, repository_id) = repository(&req, &path)?;
+
+    let change_requests = db::fetch_merged_change_requests(
+        &pool,
+        forge,
+        &repository_id,
+        query.since,
+        query.limit(50),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "change_requests": change_requests })))
+}
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{
+    header::{ACCEPT, USER_AGENT},
+    Client,
+};
+use ring::{
+    rand::SystemRandom,
+    signature::{RsaKeyPair, RSA_PKCS1_SHA256},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::github;
+
+// How the bot authenticates with GitHub: as a GitHub App, with a token per installation, or
+// with a single token for every repository
+pub enum GitHubAuth {
+    App(Box<GitHubApp>),
+    Token(String),
+}
+
+impl GitHubAuth {
+    // A GitHub App when GITHUB_APP_ID is set, otherwise the GITHUB_TOKEN, if either is
+    pub fn from_env() -> Option<Self> {
+        match std::env::var("GITHUB_APP_ID") {
+            Ok(app_id) => Some(GitHubAuth::App(Box::new(GitHubApp::from_env(app_id)))),
+            Err(_) => std::env::var("GITHUB_TOKEN").ok().map(GitHubAuth::Token),
+        }
+    }
+
+    // Token to call the API and clone with, for the installation a webhook was delivered to
+    pub async fn token(
+        &self,
+        client: &Client,
+        installation_id: Option<u64>,
+    ) -> Result<String, String> {
+        match self {
+            GitHubAuth::App(app) => {
+                let installation_id = installation_id
+                    .ok_or(String::from("Webhook was not delivered to an installation"))?;
+
+                app.installation_token(client, installation_id).await
+            }
+            GitHubAuth::Token(token) => Ok(token.clone()),
+        }
+    }
+
+    // Function to get the login the bot comments as, the app's bot user or the owner of the
+    // token
+    pub async fn login(&self, client: &Client) -> Result<String, String> {
+        let (url, token) = match self {
+            GitHubAuth::App(app) => (format!("{}/app", github::api_url()), app.jwt()),
+            GitHubAuth::Token(token) => (format!("{}/user", github::api_url()), token.clone()),
+        };
+
+        let response = client
+            .get(url)
+            .header(USER_AGENT, "Infralink Cost Bot")
+            .header(ACCEPT, "application/vnd.github+json")
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to fetch the bot login: {}",
+                response.status()
+            ));
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+
+        match self {
+            GitHubAuth::App(_) => json["slug"].as_str().map(|slug| format!("{}[bot]", slug)),
+            GitHubAuth::Token(_) => json["login"].as_str().map(String::from),
+        }
+        .ok_or(String::from("Failed to get the bot login"))
+    }
+}
+
+// Access token of an installation, only valid for the repositories it was installed on
+#[derive(Deserialize, Clone)]
+struct InstallationToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+pub struct GitHubApp {
+    app_id: String,
+    key: RsaKeyPair,
+    // installation tokens, reused until shortly before they expire
+    tokens: Mutex<HashMap<u64, InstallationToken>>,
+}
+
+impl GitHubApp {
+    // The private key is read from GITHUB_APP_PRIVATE_KEY, or the file at
+    // GITHUB_APP_PRIVATE_KEY_PATH, as downloaded from the app settings
+    pub fn from_env(app_id: String) -> Self {
+        let pem = std::env::var("GITHUB_APP_PRIVATE_KEY").unwrap_or_else(|_| {
+            let path = std::env::var("GITHUB_APP_PRIVATE_KEY_PATH")
+                .expect("GITHUB_APP_PRIVATE_KEY or GITHUB_APP_PRIVATE_KEY_PATH must be set");
+
+            std::fs::read_to_string(path).expect("Failed to read GitHub App private key")
+        });
+
+        Self::new(app_id, &pem)
+    }
+
+    pub fn new(app_id: String, pem: &str) -> Self {
+        // the body of the pem is the base64 of the der encoded key
+        let der = STANDARD
+            .decode(
+                pem.lines()
+                    .filter(|line| !line.starts_with("-----"))
+                    .collect::<String>(),
+            )
+            .expect("GitHub App private key is not valid pem");
+
+        // keys are PKCS#1 when downloaded from GitHub, PKCS#8 once converted with openssl
+        let key = RsaKeyPair::from_der(&der)
+            .or_else(|_| RsaKeyPair::from_pkcs8(&der))
+            .expect("GitHub App private key is not an RSA key");
+
+        Self {
+            app_id,
+            key,
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // JWT the app authenticates as itself with, valid for the 10 minutes GitHub allows at most
+    fn jwt(&self) -> String {
+        let now = Utc::now().timestamp();
+
+        let header = URL_SAFE_NO_PAD.encode(json!({ "alg": "RS256", "typ": "JWT" }).to_string());
+        let claims = URL_SAFE_NO_PAD.encode(
+            json!({
+                // backdated to allow for clock drift
+                "iat": now - 60,
+                "exp": now + 9 * 60,
+                "iss": self.app_id,
+            })
+            .to_string(),
+        );
+
+        let message = format!("{}.{}", header, claims);
+
+        let mut signature = vec![0; self.key.public().modulus_len()];
+
+        self.key
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message.as_bytes(),
+                &mut signature,
+            )
+            .expect("Failed to sign GitHub App JWT");
+
+        format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    // Function to get an access token for an installation, reusing it until it nearly expires
+    pub async fn installation_token(
+        &self,
+        client: &Client,
+        installation_id: u64,
+    ) -> Result<String, String> {
+        let cached = self
+            .tokens
+            .lock()
+            .unwrap()
+            .get(&installation_id)
+            .filter(|token| token.expires_at - Duration::minutes(5) > Utc::now())
+            .map(|token| token.token.clone());
+
+        if let Some(token) = cached {
+            return Ok(token);
+        }
+
+        // Send a POST request to the GitHub API to create an installation token
+        let response = client
+            .post(format!(
+                "{}/app/installations/{}/access_tokens",
+                github::api_url(),
+                installation_id
+            ))
+            .header(USER_AGENT, "Infralink Cost Bot")
+            .header(ACCEPT, "application/vnd.github+json")
+            .bearer_auth(self.jwt())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 201 {
+            return Err(format!(
+                "Failed to create installation token: {}",
+                response.status()
+            ));
+        }
+
+        let token: InstallationToken = response.json().await.map_err(|e| e.to_string())?;
+
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(installation_id, token.clone());
+
+        Ok(token.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+    use serde_json::Value;
+    use wiremock::{
+        matchers::{header_regex, method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::mock;
+
+    fn app() -> GitHubApp {
+        GitHubApp::new(String::from("12345"), &mock::fixture("github/app-key.pem"))
+    }
+
+    fn decode(part: &str) -> Value {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap()
+    }
+
+    // Token response of GitHub, expiring minutes from now
+    fn token_response(token: &str, minutes: i64) -> ResponseTemplate {
+        ResponseTemplate::new(201).set_body_json(json!({
+            "token": token,
+            "expires_at": Utc::now() + Duration::minutes(minutes),
+        }))
+    }
+
+    #[test]
+    fn jwt_is_signed_by_the_app_key() {
+        let app = app();
+        let jwt = app.jwt();
+
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        assert_eq!(decode(parts[0]), json!({ "alg": "RS256", "typ": "JWT" }));
+
+        // backdated a minute, and valid for less than the 10 minutes GitHub allows
+        let claims = decode(parts[1]);
+        let now = Utc::now().timestamp();
+        let iat = claims["iat"].as_i64().unwrap();
+        let exp = claims["exp"].as_i64().unwrap();
+
+        assert_eq!(claims["iss"], "12345");
+        assert!((now - 61..=now - 59).contains(&iat));
+        assert_eq!(exp - iat, 10 * 60);
+
+        let signature = URL_SAFE_NO_PAD.decode(parts[2]).unwrap();
+        let public_key = UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, app.key.public());
+
+        public_key
+            .verify(format!("{}.{}", parts[0], parts[1]).as_bytes(), &signature)
+            .expect("JWT signature does not match the app key");
+    }
+
+    #[actix_web::test]
+    async fn installation_token_is_reused_until_it_nearly_expires() {
+        let server = mock::server();
+
+        let _guard = Mock::given(method("POST"))
+            .and(path("/app/installations/101/access_tokens"))
+            .and(header_regex(
+                "authorization",
+                r"^Bearer [\w-]+\.[\w-]+\.[\w-]+$",
+            ))
+            .respond_with(token_response("ghs_cached", 60))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let app = app();
+        let client = Client::new();
+
+        assert_eq!(
+            app.installation_token(&client, 101).await.unwrap(),
+            "ghs_cached"
+        );
+        assert_eq!(
+            app.installation_token(&client, 101).await.unwrap(),
+            "ghs_cached"
+        );
+    }
+
+    #[actix_web::test]
+    async fn installation_token_is_refreshed_within_five_minutes_of_expiry() {
+        let server = mock::server();
+
+        let _guard = Mock::given(method("POST"))
+            .and(path("/app/installations/102/access_tokens"))
+            .respond_with(token_response("ghs_expiring", 4))
+            .expect(2)
+            .mount_as_scoped(server)
+            .await;
+
+        let app = app();
+        let client = Client::new();
+
+        app.installation_token(&client, 102).await.unwrap();
+        app.installation_token(&client, 102).await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn installation_tokens_are_cached_per_installation() {
+        let server = mock::server();
+
+        let _first = Mock::given(method("POST"))
+            .and(path("/app/installations/103/access_tokens"))
+            .respond_with(token_response("ghs_first", 60))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _second = Mock::given(method("POST"))
+            .and(path("/app/installations/104/access_tokens"))
+            .respond_with(token_response("ghs_second", 60))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let app = app();
+        let client = Client::new();
+
+        assert_eq!(
+            app.installation_token(&client, 103).await.unwrap(),
+            "ghs_first"
+        );
+        assert_eq!(
+            app.installation_token(&client, 104).await.unwrap(),
+            "ghs_second"
+        );
+    }
+
+    #[actix_web::test]
+    async fn failed_installation_token_is_an_error() {
+        let server = mock::server();
+
+        let _guard = Mock::given(method("POST"))
+            .and(path("/app/installations/105/access_tokens"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount_as_scoped(server)
+            .await;
+
+        let error = app()
+            .installation_token(&Client::new(), 105)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error, "Failed to create installation token: 404 Not Found");
+    }
+
+    #[actix_web::test]
+    async fn token_auth_needs_no_installation() {
+        let auth = GitHubAuth::Token(String::from("ghp_token"));
+
+        assert_eq!(auth.token(&Client::new(), None).await.unwrap(), "ghp_token");
+    }
+
+    #[actix_web::test]
+    async fn app_auth_needs_an_installation() {
+        let auth = GitHubAuth::App(Box::new(app()));
+
+        assert!(auth.token(&Client::new(), None).await.is_err());
+    }
+}
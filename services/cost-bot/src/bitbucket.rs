@@ -0,0 +1,763 @@
+use actix_web::HttpRequest;
+use reqwest::{header::USER_AGENT, Client, Method, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::{
+    forge::{
+        self, CheckOutcome, CommitDetails, Delivery, Forge, ForgeEvent, ForgeProvider,
+        ForgeRepository, CHECK_NAME, COMMENT_MARKER,
+    },
+    models::bitbucket::{Comment, Page, PullRequestEvent, PushEvent, Repository},
+    source::Configurations,
+    webhook::WebhookError,
+};
+
+// Key the build status of the cost check is updated by
+const STATUS_KEY: &str = "infralink-cost-analysis";
+
+// Base url of the Bitbucket Cloud API, overridable to point the bot at a mock server
+pub fn api_url() -> String {
+    std::env::var("BITBUCKET_API_URL").unwrap_or(String::from("https://api.bitbucket.org/2.0"))
+}
+
+// Base url of Bitbucket itself, used for clones and links
+pub fn web_url() -> String {
+    std::env::var("BITBUCKET_URL").unwrap_or(String::from("https://bitbucket.org"))
+}
+
+fn request(client: &Client, method: Method, url: &str, token: &str) -> RequestBuilder {
+    client
+        .request(method, url)
+        .header(USER_AGENT, "Infralink Cost Bot")
+        .bearer_auth(token)
+}
+
+fn forge_repository(repository: &Repository) -> ForgeRepository {
+    ForgeRepository {
+        forge: Forge::Bitbucket,
+        id: repository.uuid.clone(),
+        full_name: repository.full_name.clone(),
+        size: None,
+        installation_id: None,
+    }
+}
+
+// Repositories on Bitbucket Cloud, through a repository or workspace access token
+pub struct Bitbucket {
+    token: String,
+    // secret the webhook payloads are signed with
+    secret: String,
+}
+
+impl Bitbucket {
+    // Set up when BITBUCKET_TOKEN is set
+    pub fn from_env() -> Option<Self> {
+        let token = std::env::var("BITBUCKET_TOKEN").ok()?;
+
+        Some(Self {
+            token,
+            secret: forge::required_env("BITBUCKET_WEBHOOK_SECRET"),
+        })
+    }
+
+    // Function to set the build status of the cost check on a commit
+    async fn set_status(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        body: Value,
+    ) -> Result<(), String> {
+        let response = request(
+            client,
+            Method::POST,
+            &format!(
+                "{}/repositories/{}/commit/{}/statuses/build",
+                api_url(),
+                repository.full_name,
+                sha
+            ),
+            &self.token,
+        )
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // created the first time, replaced after that
+        if !response.status().is_success() {
+            return Err(format!("Failed to set build status: {}", response.status()));
+        }
+
+        Ok(())
+    }
+
+    // Function to find a comment the bot left, following the pages from url
+    async fn find_bot_comment(
+        &self,
+        client: &Client,
+        url: String,
+        token: &str,
+    ) -> Result<Option<Comment>, String> {
+        let mut url = Some(url);
+
+        // every page links to the next one
+        while let Some(page_url) = url {
+            let response = request(client, Method::GET, &page_url, token)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            // Check the response status
+            if response.status() != 200 {
+                return Err(format!("Failed to list comments: {}", response.status()));
+            }
+
+            let page: Page<Comment> = response.json().await.map_err(|e| e.to_string())?;
+
+            if let Some(comment) = page
+                .values
+                .into_iter()
+                .find(|comment| comment.content.raw.starts_with(COMMENT_MARKER))
+            {
+                return Ok(Some(comment));
+            }
+
+            url = page.next;
+        }
+
+        Ok(None)
+    }
+
+    // Function to replace the comment with the id at url, or write a new one
+    async fn write_bot_comment(
+        &self,
+        client: &Client,
+        url: &str,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<Comment, String> {
+        let (request, expected_status) = match comment_id {
+            Some(id) => (
+                request(client, Method::PUT, &format!("{}/{}", url, id), token),
+                200,
+            ),
+            None => (request(client, Method::POST, url, token), 201),
+        };
+
+        let response = request
+            .json(&json!({ "content": { "raw": body } }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != expected_status {
+            return Err(format!("Failed to write comment: {}", response.status()));
+        }
+
+        response.json().await.map_err(|e| e.to_string())
+    }
+}
+
+impl ForgeProvider for Bitbucket {
+    const FORGE: Forge = Forge::Bitbucket;
+
+    fn verify(&self, req: &HttpRequest, body: &[u8]) -> Result<Delivery, WebhookError> {
+        // only trust payloads signed with our secret
+        forge::verify_signature(&self.secret, forge::header(req, "X-Hub-Signature")?, body)?;
+
+        Ok(Delivery {
+            event: forge::header(req, "X-Event-Key")?.to_string(),
+            id: forge::header(req, "X-Request-UUID")?.to_string(),
+        })
+    }
+
+    fn parse_event(
+        &self,
+        event: &str,
+        payload: &Value,
+    ) -> Result<Option<ForgeEvent>, WebhookError> {
+        match event {
+            "repo:push" => {
+                let event: PushEvent = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                // the latest branch pushed to, tags and deleted branches have nothing to price
+                let Some((old, new)) = event.push.changes.into_iter().rev().find_map(|change| {
+                    change
+                        .new
+                        .filter(|new| new.r#type == "branch")
+                        .map(|new| (change.old, new))
+                }) else {
+                    return Ok(None);
+                };
+
+                Ok(Some(ForgeEvent::Push {
+                    repository: forge_repository(&event.repository),
+                    // a new branch has no commit before the push
+                    before: old.map(|old| old.target.hash).unwrap_or_default(),
+                    after: new.target.hash,
+                    commit: CommitDetails {
+                        branch: Some(new.name),
+                        author: new.target.author.map(|author| author.raw),
+                        committed_at: new.target.date,
+                    },
+                }))
+            }
+            "pullrequest:created" | "pullrequest:updated" => {
+                let event: PullRequestEvent = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                Ok(Some(ForgeEvent::ChangeRequest {
+                    repository: forge_repository(&event.repository),
+                    number: event.pullrequest.id,
+                    title: event.pullrequest.title,
+                    base: event.pullrequest.destination.commit.hash,
+                    head: event.pullrequest.source.commit.hash,
+                }))
+            }
+            "pullrequest:fulfilled" => {
+                let event: PullRequestEvent = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                Ok(Some(ForgeEvent::Merged {
+                    repository: forge_repository(&event.repository),
+                    number: event.pullrequest.id,
+                    merged_at: event.pullrequest.updated_on,
+                }))
+            }
+            // a webhook subscribes to several events at once, the others are acknowledged
+            _ => Ok(None),
+        }
+    }
+
+    async fn token(
+        &self,
+        _client: &Client,
+        _repository: &ForgeRepository,
+    ) -> Result<String, String> {
+        Ok(self.token.clone())
+    }
+
+    async fn resolve_commit(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        reference: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        // pull request events only carry short shas
+        let response = request(
+            client,
+            Method::GET,
+            &format!(
+                "{}/repositories/{}/commit/{}",
+                api_url(),
+                repository.full_name,
+                forge::encode(reference)
+            ),
+            token,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to resolve {}: {}",
+                reference,
+                response.status()
+            ));
+        }
+
+        let json: Value = response.json().await.map_err(|e| e.to_string())?;
+
+        json["hash"]
+            .as_str()
+            .map(String::from)
+            .ok_or(String::from("Failed to get commit sha"))
+    }
+
+    fn clone_url(&self, repository: &ForgeRepository, token: &str) -> String {
+        let web_url = web_url();
+
+        match web_url.split_once("://") {
+            Some((scheme, host)) if !token.is_empty() => format!(
+                "{}://x-token-auth:{}@{}/{}.git",
+                scheme, token, host, repository.full_name
+            ),
+            _ => format!("{}/{}.git", web_url, repository.full_name),
+        }
+    }
+
+    async fn fetch_configuration_files(
+        &self,
+        _client: &Client,
+        _repository: &ForgeRepository,
+        _sha: &str,
+        _token: &str,
+    ) -> Result<Option<Configurations>, String> {
+        // Bitbucket can't list a tree recursively, so repositories are always cloned
+        Ok(None)
+    }
+
+    async fn start_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        _token: &str,
+    ) -> Result<Option<u64>, String> {
+        self.set_status(
+            client,
+            repository,
+            sha,
+            json!({
+                "key": STATUS_KEY,
+                "state": "INPROGRESS",
+                "name": CHECK_NAME,
+                "url": self.commit_url(repository, sha),
+            }),
+        )
+        .await?;
+
+        // statuses are found by commit and key
+        Ok(None)
+    }
+
+    async fn complete_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        _check_id: Option<u64>,
+        outcome: &CheckOutcome,
+        _token: &str,
+    ) -> Result<(), String> {
+        self.set_status(
+            client,
+            repository,
+            sha,
+            json!({
+                "key": STATUS_KEY,
+                "state": if outcome.success { "SUCCESSFUL" } else { "FAILED" },
+                "name": CHECK_NAME,
+                "description": outcome.summary.lines().next().unwrap_or_default(),
+                "url": outcome.details_url,
+            }),
+        )
+        .await
+    }
+
+    async fn find_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String> {
+        let url = format!(
+            "{}/repositories/{}/commit/{}/comments?pagelen=100",
+            api_url(),
+            repository.full_name,
+            sha
+        );
+
+        Ok(self
+            .find_bot_comment(client, url, token)
+            .await?
+            .map(|comment| comment.id))
+    }
+
+    async fn write_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let url = format!(
+            "{}/repositories/{}/commit/{}/comments",
+            api_url(),
+            repository.full_name,
+            sha
+        );
+
+        let comment = self
+            .write_bot_comment(client, &url, comment_id, body, token)
+            .await?;
+
+        Ok(format!(
+            "{}#comment-{}",
+            self.commit_url(repository, sha),
+            comment.id
+        ))
+    }
+
+    fn commit_url(&self, repository: &ForgeRepository, sha: &str) -> String {
+        format!("{}/{}/commits/{}", web_url(), repository.full_name, sha)
+    }
+
+    fn change_request_url(&self, repository: &ForgeRepository, number: u64) -> String {
+        format!(
+            "{}/{}/pull-requests/{}",
+            web_url(),
+            repository.full_name,
+            number
+        )
+    }
+
+    async fn find_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        token: &str,
+    ) -> Result<Option<(u64, String)>, String> {
+        let url = format!(
+            "{}/repositories/{}/pullrequests/{}/comments?pagelen=100",
+            api_url(),
+            repository.full_name,
+            number
+        );
+
+        Ok(self
+            .find_bot_comment(client, url, token)
+            .await?
+            .map(|comment| (comment.id, comment.content.raw)))
+    }
+
+    async fn write_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let url = format!(
+            "{}/repositories/{}/pullrequests/{}/comments",
+            api_url(),
+            repository.full_name,
+            number
+        );
+
+        let comment = self
+            .write_bot_comment(client, &url, comment_id, body, token)
+            .await?;
+
+        Ok(format!(
+            "{}#comment-{}",
+            self.change_request_url(repository, number),
+            comment.id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use wiremock::{
+        matchers::{body_partial_json, header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::mock;
+
+    fn bitbucket() -> Bitbucket {
+        Bitbucket {
+            token: String::from("bb_token"),
+            secret: String::from("secret"),
+        }
+    }
+
+    fn repository(full_name: &str) -> ForgeRepository {
+        ForgeRepository {
+            forge: Forge::Bitbucket,
+            id: String::from("{2c3e5a6b-7d8e-4f90-a1b2-c3d4e5f60718}"),
+            full_name: full_name.to_string(),
+            size: None,
+            installation_id: None,
+        }
+    }
+
+    fn event(event: &str, fixture: &str) -> Option<ForgeEvent> {
+        let payload = serde_json::from_str(&mock::fixture(fixture)).unwrap();
+
+        bitbucket().parse_event(event, &payload).unwrap()
+    }
+
+    fn comment(id: u64, body: &str) -> Value {
+        json!({ "id": id, "content": { "raw": body, "markup": "markdown" } })
+    }
+
+    #[test]
+    fn push_is_priced_against_the_commit_before_it() {
+        let Some(ForgeEvent::Push {
+            repository,
+            before,
+            after,
+            commit,
+        }) = event("repo:push", "bitbucket/push.json")
+        else {
+            panic!("push was not parsed as a push");
+        };
+
+        assert_eq!(repository.forge, Forge::Bitbucket);
+        assert_eq!(repository.id, "{2c3e5a6b-7d8e-4f90-a1b2-c3d4e5f60718}");
+        assert_eq!(repository.full_name, "acme/infra");
+        assert_eq!(before, "1e65c05c1d5171631d92438a13901ca7dae9618c");
+        assert_eq!(after, "7f5ac4a2b6e9cd2d8f1e0b3a4c5d6e7f8091a2b3");
+        assert_eq!(commit.branch.as_deref(), Some("main"));
+        assert_eq!(
+            commit.author.as_deref(),
+            Some("Erica Jones <erica@example.com>")
+        );
+        assert_eq!(
+            commit.committed_at,
+            Some(Utc.with_ymd_and_hms(2024, 3, 11, 9, 48, 3).unwrap())
+        );
+    }
+
+    #[test]
+    fn deleted_branch_is_not_priced() {
+        assert!(event("repo:push", "bitbucket/push_branch_deleted.json").is_none());
+    }
+
+    #[test]
+    fn created_pull_request_is_priced_against_its_destination() {
+        let Some(ForgeEvent::ChangeRequest {
+            repository,
+            number,
+            title,
+            base,
+            head,
+        }) = event("pullrequest:created", "bitbucket/pullrequest_created.json")
+        else {
+            panic!("pull request was not parsed as a change request");
+        };
+
+        assert_eq!(repository.full_name, "acme/infra");
+        assert_eq!(number, 17);
+        assert_eq!(title, "Add a read replica to the database");
+        // short shas, resolved before the check
+        assert_eq!(base, "7f5ac4a2b6e9");
+        assert_eq!(head, "c4e8b1d2a3f5");
+    }
+
+    #[test]
+    fn fulfilled_pull_request_is_merged() {
+        let Some(ForgeEvent::Merged {
+            repository,
+            number,
+            merged_at,
+        }) = event(
+            "pullrequest:fulfilled",
+            "bitbucket/pullrequest_fulfilled.json",
+        )
+        else {
+            panic!("pull request was not parsed as merged");
+        };
+
+        assert_eq!(repository.full_name, "acme/infra");
+        assert_eq!(number, 17);
+        assert_eq!(
+            merged_at.map(|merged_at| merged_at.timestamp()),
+            Some(
+                Utc.with_ymd_and_hms(2024, 3, 12, 14, 31, 9)
+                    .unwrap()
+                    .timestamp()
+            )
+        );
+    }
+
+    #[test]
+    fn other_events_are_acknowledged() {
+        assert!(bitbucket()
+            .parse_event("pullrequest:comment_created", &json!({}))
+            .unwrap()
+            .is_none());
+    }
+
+    #[actix_web::test]
+    async fn pull_request_comment_is_written_once() {
+        let server = mock::server();
+
+        let _list = Mock::given(method("GET"))
+            .and(path(
+                "/repositories/acme/new-comment/pullrequests/1/comments",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [comment(1, "Can we use a smaller instance?")],
+                "pagelen": 100,
+            })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path(
+                "/repositories/acme/new-comment/pullrequests/1/comments",
+            ))
+            .and(header("authorization", "Bearer bb_token"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(comment(2, "")))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let url = bitbucket()
+            .comment_on_change_request(
+                &Client::new(),
+                &repository("acme/new-comment"),
+                1,
+                "c4e8b1d2a3f5",
+                Some("## Cost Breakdown"),
+                "bb_token",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            url,
+            Some(format!(
+                "{}/acme/new-comment/pull-requests/1#comment-2",
+                server.uri()
+            ))
+        );
+    }
+
+    #[actix_web::test]
+    async fn pull_request_comment_of_the_bot_is_found_on_a_later_page() {
+        let server = mock::server();
+        let comments = "/repositories/acme/update-comment/pullrequests/2/comments";
+
+        let _first = Mock::given(method("GET"))
+            .and(path(comments))
+            .and(query_param("pagelen", "100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [comment(3, "Looks good")],
+                "next": format!("{}{}?page=2", server.uri(), comments),
+            })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _second = Mock::given(method("GET"))
+            .and(path(comments))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [comment(4, &format!("{}\n## Cost Breakdown\n\nold", COMMENT_MARKER))],
+            })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _update = Mock::given(method("PUT"))
+            .and(path(format!("{}/4", comments)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(comment(4, "")))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path(comments))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount_as_scoped(server)
+            .await;
+
+        bitbucket()
+            .comment_on_change_request(
+                &Client::new(),
+                &repository("acme/update-comment"),
+                2,
+                "c4e8b1d2a3f5",
+                Some("## Cost Breakdown\n\nnew"),
+                "bb_token",
+            )
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn build_statuses_are_set_on_the_commit() {
+        let server = mock::server();
+        let sha = "7f5ac4a2b6e9cd2d8f1e0b3a4c5d6e7f8091a2b3";
+        let statuses = format!("/repositories/acme/statuses/commit/{}/statuses/build", sha);
+
+        let _running = Mock::given(method("POST"))
+            .and(path(statuses.as_str()))
+            .and(body_partial_json(json!({
+                "key": STATUS_KEY,
+                "state": "INPROGRESS",
+                "name": CHECK_NAME,
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        // replacing a status is answered with 200
+        let _failed = Mock::given(method("POST"))
+            .and(path(statuses.as_str()))
+            .and(body_partial_json(json!({
+                "key": STATUS_KEY,
+                "state": "FAILED",
+                "description": "api is above its limit",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let bitbucket = bitbucket();
+        let client = Client::new();
+        let repository = repository("acme/statuses");
+
+        let check_id = bitbucket
+            .start_check(&client, &repository, sha, "bb_token")
+            .await
+            .unwrap();
+
+        let outcome = CheckOutcome {
+            success: false,
+            summary: String::from("api is above its limit\nworker is above its limit"),
+            text: String::new(),
+            details_url: bitbucket.commit_url(&repository, sha),
+        };
+
+        bitbucket
+            .complete_check(&client, &repository, sha, check_id, &outcome, "bb_token")
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn refused_build_status_is_an_error() {
+        let server = mock::server();
+        let sha = "1e65c05c1d5171631d92438a13901ca7dae9618c";
+
+        let _running = Mock::given(method("POST"))
+            .and(path(format!(
+                "/repositories/acme/forbidden/commit/{}/statuses/build",
+                sha
+            )))
+            .respond_with(ResponseTemplate::new(403))
+            .mount_as_scoped(server)
+            .await;
+
+        let error = bitbucket()
+            .start_check(
+                &Client::new(),
+                &repository("acme/forbidden"),
+                sha,
+                "bb_token",
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(error, "Failed to set build status: 403 Forbidden");
+    }
+}
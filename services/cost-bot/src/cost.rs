@@ -1,28 +1,174 @@
-use std::str::FromStr;
-
 use indexmap::IndexMap;
+pub use math::budget::{budget_violations, violation_lines, Severity, Violation};
+pub use math::report::CostReport;
 use types::{
-    config::InfrastructureConfiguration, deployment_configuration::DeploymentConfiguration,
-    region::AwsRegion,
+    budget::Budget,
+    currency::{Currency, ExchangeRate, ExchangeRateSource, StaticFileRates},
 };
 
-pub async fn calculate_cost(configuration: &InfrastructureConfiguration) -> IndexMap<String, f64> {
-    let result = math::calculate_deployment(DeploymentConfiguration {
-        regions: vec![AwsRegion::from_str(&configuration.app.region).unwrap()],
-        control_plane_specs: Some((1, 1.0)),
-        spot_control_plane: false,
-        node_specs: (
-            configuration.shape.as_ref().unwrap().vcpu,
-            configuration.shape.as_ref().unwrap().memory,
-        ),
-        storage_size_gb: configuration.storage.as_ref().unwrap().size,
-        node_count: 1.0,
-        outbound_data_gb: 0,
-    })
-    .await;
-
-    return result
-        .get(&AwsRegion::from_str(&configuration.app.region).unwrap())
-        .unwrap()
-        .clone();
+// Rate to show costs in, read from the file at EXCHANGE_RATES_PATH. Falls back to USD when
+// the rate isn't available, so a missing rate never blocks the comment
+pub fn exchange_rate(currency: Currency) -> ExchangeRate {
+    if currency == Currency::Usd {
+        return ExchangeRate::usd();
+    }
+
+    let path = std::env::var("EXCHANGE_RATES_PATH").unwrap_or(String::from("exchange_rates.toml"));
+
+    match StaticFileRates::load(path).and_then(|rates| rates.rate(currency)) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("Failed to load exchange rate, showing USD: {}", e);
+            ExchangeRate::usd()
+        }
+    }
+}
+
+// Budget of every app: the one stored for the repository, else the [budget] of its infra.toml
+// at the base of a change request, else the one the change request declares for an app that
+// had none, so a change can't loosen a budget the repository was given
+pub fn budgets(
+    report: &CostReport,
+    base: IndexMap<String, Budget>,
+    stored: IndexMap<String, Budget>,
+) -> IndexMap<String, Budget> {
+    let mut budgets = report.budgets.clone();
+
+    budgets.extend(base);
+    budgets.extend(stored);
+
+    budgets
+}
+
+// Every app against its budget, then every violation, for the output of the check
+pub fn budgets_markdown(
+    report: &CostReport,
+    budgets: &IndexMap<String, Budget>,
+    violations: &[Violation],
+    rate: &ExchangeRate,
+) -> String {
+    let format_amount = |amount: Option<f64>| match amount {
+        Some(amount) => rate.format(amount),
+        None => String::from("-"),
+    };
+
+    let rows = report
+        .breakdowns
+        .iter()
+        .map(|(app_name, costs)| {
+            let total_cost = math::report::app_total(costs);
+
+            let worst = violations
+                .iter()
+                .filter(|violation| &violation.app_name == app_name)
+                .map(|violation| violation.severity)
+                .max();
+
+            let (warning, limit, status) = match budgets.get(app_name) {
+                Some(budget) => (
+                    format_amount(budget.warning),
+                    format_amount(budget.limit),
+                    match worst {
+                        Some(Severity::Failure) => "❌ Over budget",
+                        Some(Severity::Warning) => "⚠️ Warning",
+                        None => "✅ Within budget",
+                    },
+                ),
+                None => (String::from("-"), String::from("-"), "No budget"),
+            };
+
+            format!(
+                "| {} | {} | {} | {} | {} |",
+                app_name,
+                rate.format(total_cost),
+                warning,
+                limit,
+                status
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let table = format!(
+        "| App | Monthly Cost | Warning | Limit | Status |\n|-----|--------------|---------|-------|--------|\n{}",
+        rows
+    );
+
+    if violations.is_empty() {
+        return table;
+    }
+
+    format!(
+        "{}\n\n### Violations\n\n{}",
+        table,
+        violation_lines(violations)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use types::adjustments::Adjustments;
+
+    use super::*;
+
+    // Report of an app costing $500 a month that declares a $450 limit, and one costing $100
+    fn report() -> CostReport {
+        let mut report = CostReport::new(&Adjustments::default());
+
+        report.breakdowns.insert(
+            String::from("web"),
+            IndexMap::from([(String::from("Instances"), 500.0)]),
+        );
+        report.breakdowns.insert(
+            String::from("worker"),
+            IndexMap::from([(String::from("Instances"), 100.0)]),
+        );
+        report
+            .budgets
+            .insert(String::from("web"), Budget::with_limit(450.0));
+
+        report
+    }
+
+    #[test]
+    fn stored_budgets_win_over_the_base_and_the_change() {
+        let base = IndexMap::from([
+            (String::from("web"), Budget::with_limit(400.0)),
+            (String::from("worker"), Budget::with_limit(200.0)),
+        ]);
+        let stored = IndexMap::from([(String::from("worker"), Budget::with_limit(50.0))]);
+
+        let budgets = budgets(&report(), base, stored);
+
+        assert_eq!(budgets["web"], Budget::with_limit(400.0));
+        assert_eq!(budgets["worker"], Budget::with_limit(50.0));
+    }
+
+    #[test]
+    fn budgets_declared_by_the_change_apply_to_apps_that_had_none() {
+        let budgets = budgets(&report(), IndexMap::new(), IndexMap::new());
+
+        assert_eq!(budgets["web"], Budget::with_limit(450.0));
+        assert!(!budgets.contains_key("worker"));
+    }
+
+    #[test]
+    fn markdown_lists_every_app_then_the_violations() {
+        let report = report();
+        let rate = ExchangeRate::usd();
+        let budgets = budgets(&report, IndexMap::new(), IndexMap::new());
+        let violations = budget_violations(&report, &budgets, None, &rate);
+
+        assert_eq!(
+            budgets_markdown(&report, &budgets, &violations, &rate),
+            "| App | Monthly Cost | Warning | Limit | Status |\n\
+             |-----|--------------|---------|-------|--------|\n\
+             | web | $500.00 | - | $450.00 | ❌ Over budget |\n\
+             | worker | $100.00 | - | - | No budget |\n\
+             \n\
+             ### Violations\n\
+             \n\
+             - ❌ Cost for web of $500.00 is above its $450.00 limit."
+        );
+    }
 }
@@ -1,36 +1,247 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
+use types::{adjustments::Adjustments, budget::Budget, currency::Currency};
 
 pub async fn create_pool(database_url: &str) -> PgPool {
     PgPool::connect(database_url).await.unwrap()
 }
 
+use crate::{
+    api::{CostRun, MergedChangeRequest},
+    forge::{CommitDetails, Forge, ForgeRepository},
+    jobs::Job,
+};
 use serde_json::Value;
-use sqlx::{Error, PgPool, Row};
+use sqlx::{postgres::PgRow, types::Json, Error, PgPool, Row};
 
-pub async fn fetch_repository_cost_limits(
+// Repositories are keyed by their forge and their id on it, as text since Bitbucket
+// repositories only have a uuid. The tables are created by the migrations, run on startup
+
+// Budgets of the apps of a repository, by app name. Limits stored before budgets are plain
+// numbers and read as a hard limit. A budget that isn't valid is kept with why, to be reported
+// on the check
+pub async fn fetch_repository_budgets(
     pool: &PgPool,
-    repository_id: i64,
-) -> Result<IndexMap<String, f64>, Error> {
+    repository: &ForgeRepository,
+) -> Result<IndexMap<String, Result<Budget, String>>, Error> {
+    let row =
+        sqlx::query("SELECT cost_limit FROM cost_limits WHERE forge = $1 AND repository_id = $2")
+            .bind(repository.forge.to_string())
+            .bind(&repository.id)
+            .fetch_optional(pool)
+            .await?;
+
+    // repositories without budgets only have the ones in their infra.toml files
+    let Some(row) = row else {
+        return Ok(IndexMap::new());
+    };
+
+    let cost_limit: IndexMap<String, Value> =
+        serde_json::from_value(row.get("cost_limit")).map_err(|e| Error::Decode(Box::new(e)))?;
+
+    Ok(cost_limit
+        .into_iter()
+        .map(|(app_name, budget)| {
+            let budget = match budget.as_f64() {
+                Some(limit) => Ok(Budget::with_limit(limit)),
+                None => serde_json::from_value(budget).map_err(|e| e.to_string()),
+            };
+
+            (app_name, budget)
+        })
+        .collect())
+}
+
+// Currency the repository wants its costs shown in, if it has set one. A currency that isn't
+// supported is logged and left out, so the costs are still shown
+pub async fn fetch_repository_currency(
+    pool: &PgPool,
+    repository: &ForgeRepository,
+) -> Result<Option<Currency>, Error> {
     let row = sqlx::query(
-        "SELECT cost_limit FROM cost_limits WHERE repository_id = $1 ORDER BY cost_limit DESC",
+        "SELECT currency FROM repository_settings WHERE forge = $1 AND repository_id = $2",
     )
-    .bind(repository_id)
-    .fetch_one(pool)
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(currency) = row.and_then(|row| row.get::<Option<String>, _>("currency")) else {
+        return Ok(None);
+    };
+
+    match Currency::from_str(&currency) {
+        Ok(currency) => Ok(Some(currency)),
+        Err(e) => {
+            eprintln!(
+                "Ignoring the currency setting of {}, {}: {}",
+                repository.full_name, currency, e
+            );
+            Ok(None)
+        }
+    }
+}
+
+// Discounts and tax the repository pays on top of list prices, stored as json. Adjustments
+// that can't be read are logged and left out, so list prices are shown
+pub async fn fetch_repository_adjustments(
+    pool: &PgPool,
+    repository: &ForgeRepository,
+) -> Result<Option<Adjustments>, Error> {
+    let row = sqlx::query(
+        "SELECT adjustments FROM repository_settings WHERE forge = $1 AND repository_id = $2",
+    )
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(adjustments) = row.and_then(|row| row.get::<Option<Value>, _>("adjustments")) else {
+        return Ok(None);
+    };
+
+    match serde_json::from_value(adjustments) {
+        Ok(adjustments) => Ok(Some(adjustments)),
+        Err(e) => {
+            eprintln!(
+                "Ignoring the adjustments setting of {}: {}",
+                repository.full_name, e
+            );
+            Ok(None)
+        }
+    }
+}
+
+// Queue a webhook delivery as a job, None when the delivery was already queued and so is a
+// replay
+pub async fn enqueue_job(
+    pool: &PgPool,
+    forge: Forge,
+    delivery_id: &str,
+    event: &str,
+    payload: &Value,
+) -> Result<Option<i64>, Error> {
+    let row = sqlx::query(
+        "INSERT INTO jobs (forge, delivery_id, event, payload) VALUES ($1, $2, $3, $4) ON CONFLICT (forge, delivery_id) DO NOTHING RETURNING id",
+    )
+    .bind(forge.to_string())
+    .bind(delivery_id)
+    .bind(event)
+    .bind(payload)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| row.get("id")))
+}
+
+fn job_from_row(row: &PgRow) -> Job {
+    Job {
+        id: row.get("id"),
+        forge: row.get("forge"),
+        delivery_id: row.get("delivery_id"),
+        event: row.get("event"),
+        payload: row.get("payload"),
+        status: row.get("status"),
+        attempts: row.get("attempts"),
+        last_error: row.get("last_error"),
+        run_at: row.get("run_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn fetch_job(pool: &PgPool, id: i64) -> Result<Option<Job>, Error> {
+    let row = sqlx::query("SELECT * FROM jobs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(job_from_row))
+}
+
+// Take the next job that is due and mark it running. Rows locked by another worker are skipped
+// so several workers never claim the same job
+pub async fn claim_job(pool: &PgPool) -> Result<Option<Job>, Error> {
+    let row = sqlx::query(
+        "UPDATE jobs SET status = 'running', attempts = attempts + 1, updated_at = now()
+        WHERE id = (
+            SELECT id FROM jobs WHERE status = 'queued' AND run_at <= now()
+            ORDER BY run_at LIMIT 1 FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *",
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(job_from_row))
+}
+
+pub async fn complete_job(pool: &PgPool, id: i64) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE jobs SET status = 'succeeded', last_error = NULL, updated_at = now() WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Put a failed job back in the queue to run again at run_at
+pub async fn retry_job(
+    pool: &PgPool,
+    id: i64,
+    error: &str,
+    run_at: DateTime<Utc>,
+) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE jobs SET status = 'queued', last_error = $2, run_at = $3, updated_at = now() WHERE id = $1",
+    )
+    .bind(id)
+    .bind(error)
+    .bind(run_at)
+    .execute(pool)
     .await?;
 
-    let cost_limit: Value = row.get("cost_limit");
+    Ok(())
+}
 
-    let parsed: IndexMap<String, f64> = serde_json::from_value(cost_limit).unwrap();
+// Give up on a job that failed every attempt, it stays in the table for inspection
+pub async fn dead_letter_job(pool: &PgPool, id: i64, error: &str) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE jobs SET status = 'dead', last_error = $2, updated_at = now() WHERE id = $1",
+    )
+    .bind(id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Queue again jobs left running by a worker that stopped part way through
+pub async fn requeue_stale_jobs(pool: &PgPool, timeout_secs: i64) -> Result<u64, Error> {
+    let result = sqlx::query(
+        "UPDATE jobs SET status = 'queued', run_at = now(), updated_at = now()
+        WHERE status = 'running' AND updated_at < now() - make_interval(secs => $1)",
+    )
+    .bind(timeout_secs as f64)
+    .execute(pool)
+    .await?;
 
-    Ok(parsed)
+    Ok(result.rows_affected())
 }
 
+// Keep the breakdown of a pushed commit for the history. A commit priced again, by a retried
+// job or a push of the same commit to another branch, replaces its run
 pub async fn store_breakdown(
     pool: &PgPool,
-    repository_id: i64,
+    repository: &ForgeRepository,
     commit_ref: &str,
+    commit: &CommitDetails,
     breakdowns: IndexMap<String, IndexMap<String, f64>>,
 ) -> Result<(), Error> {
     // Convert the nested IndexMap to a nested HashMap
@@ -39,16 +250,24 @@ pub async fn store_breakdown(
         .map(|(k, v)| (k, v.into_iter().collect::<HashMap<_, _>>()))
         .collect();
 
-    // Serialize the HashMap to a JSON string
-    let breakdowns_json = serde_json::to_string(&breakdowns_hash_map).unwrap();
-
     // Prepare and execute the SQL INSERT statement
     sqlx::query(
-        "INSERT INTO cost_runs (repository_id, commit_ref, cost_breakdown) VALUES ($1, $2, $3)",
+        "INSERT INTO cost_runs (forge, repository_id, commit_ref, cost_breakdown, branch, author, committed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (forge, repository_id, commit_ref) DO UPDATE SET
+            cost_breakdown = EXCLUDED.cost_breakdown,
+            branch = EXCLUDED.branch,
+            author = EXCLUDED.author,
+            committed_at = EXCLUDED.committed_at,
+            created_at = now()",
     )
-    .bind(repository_id)
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
     .bind(commit_ref)
-    .bind(breakdowns_json)
+    .bind(Json(breakdowns_hash_map))
+    .bind(&commit.branch)
+    .bind(&commit.author)
+    .bind(commit.committed_at)
     .execute(pool)
     .await?;
 
@@ -56,29 +275,16 @@ pub async fn store_breakdown(
     Ok(())
 }
 
-pub async fn remove_breakdown(
-    pool: &PgPool,
-    repository_id: i64,
-    commit_ref: &str,
-) -> Result<(), Error> {
-    sqlx::query("DELETE FROM cost_runs WHERE repository_id = $1 AND commit_ref = $2")
-        .bind(repository_id)
-        .bind(commit_ref)
-        .execute(pool)
-        .await?;
-
-    Ok(())
-}
-
 pub async fn fetch_previous_breakdown(
     pool: &PgPool,
-    repository_id: i64,
+    repository: &ForgeRepository,
     commit_ref: &str,
 ) -> Result<Option<IndexMap<String, IndexMap<String, f64>>>, Error> {
     let row = sqlx::query(
-        "SELECT cost_breakdown FROM cost_runs WHERE repository_id = $1 AND commit_ref = $2 LIMIT 1",
+        "SELECT cost_breakdown FROM cost_runs WHERE forge = $1 AND repository_id = $2 AND commit_ref = $3",
     )
-    .bind(repository_id)
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
     .bind(commit_ref)
     .fetch_optional(pool)
     .await?;
@@ -88,10 +294,151 @@ pub async fn fetch_previous_breakdown(
 
         // Convert Value to your desired IndexMap type
         let parsed: IndexMap<String, IndexMap<String, f64>> =
-            serde_json::from_value(cost_breakdown).unwrap();
+            serde_json::from_value(cost_breakdown).map_err(|e| Error::Decode(Box::new(e)))?;
 
         Ok(Some(parsed))
     } else {
         Ok(None)
     }
 }
+
+// Runs of a repository, latest first, optionally on a branch and since a time
+pub async fn fetch_cost_runs(
+    pool: &PgPool,
+    forge: Forge,
+    repository_id: &str,
+    branch: Option<&str>,
+    since: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<Vec<CostRun>, Error> {
+    let rows = sqlx::query(
+        "SELECT commit_ref, cost_breakdown, branch, author, COALESCE(committed_at, created_at) AS committed_at
+        FROM cost_runs
+        WHERE forge = $1 AND repository_id = $2
+            AND ($3::TEXT IS NULL OR branch = $3)
+            AND ($4::TIMESTAMPTZ IS NULL OR COALESCE(committed_at, created_at) >= $4)
+        ORDER BY COALESCE(committed_at, created_at) DESC
+        LIMIT $5",
+    )
+    .bind(forge.to_string())
+    .bind(repository_id)
+    .bind(branch)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(CostRun {
+                commit_ref: row.get("commit_ref"),
+                branch: row.get("branch"),
+                author: row.get("author"),
+                committed_at: row.get("committed_at"),
+                breakdowns: serde_json::from_value(row.get("cost_breakdown"))
+                    .map_err(|e| Error::Decode(Box::new(e)))?,
+            })
+        })
+        .collect()
+}
+
+// Keep the cost of a change request before and after it, updated on every push to it
+#[allow(clippy::too_many_arguments)]
+pub async fn store_change_request(
+    pool: &PgPool,
+    repository: &ForgeRepository,
+    number: u64,
+    title: &str,
+    base_ref: &str,
+    head_ref: &str,
+    cost_before: Option<f64>,
+    cost_after: f64,
+) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT INTO change_requests (forge, repository_id, number, title, base_ref, head_ref, cost_before, cost_after)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (forge, repository_id, number) DO UPDATE SET
+            title = EXCLUDED.title,
+            base_ref = EXCLUDED.base_ref,
+            head_ref = EXCLUDED.head_ref,
+            cost_before = EXCLUDED.cost_before,
+            cost_after = EXCLUDED.cost_after,
+            updated_at = now()",
+    )
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
+    .bind(number as i64)
+    .bind(title)
+    .bind(base_ref)
+    .bind(head_ref)
+    .bind(cost_before)
+    .bind(cost_after)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Mark a change request merged, false when the bot never priced it
+pub async fn mark_change_request_merged(
+    pool: &PgPool,
+    repository: &ForgeRepository,
+    number: u64,
+    merged_at: Option<DateTime<Utc>>,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        "UPDATE change_requests SET merged_at = COALESCE($4, now()), updated_at = now()
+        WHERE forge = $1 AND repository_id = $2 AND number = $3",
+    )
+    .bind(repository.forge.to_string())
+    .bind(&repository.id)
+    .bind(number as i64)
+    .bind(merged_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Merged change requests of a repository, latest first
+pub async fn fetch_merged_change_requests(
+    pool: &PgPool,
+    forge: Forge,
+    repository_id: &str,
+    since: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<Vec<MergedChangeRequest>, Error> {
+    let rows = sqlx::query(
+        "SELECT number, title, base_ref, head_ref, cost_before, cost_after, merged_at
+        FROM change_requests
+        WHERE forge = $1 AND repository_id = $2 AND merged_at IS NOT NULL
+            AND ($3::TIMESTAMPTZ IS NULL OR merged_at >= $3)
+        ORDER BY merged_at DESC
+        LIMIT $4",
+    )
+    .bind(forge.to_string())
+    .bind(repository_id)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let cost_before: Option<f64> = row.get("cost_before");
+            let cost_after: f64 = row.get("cost_after");
+
+            MergedChangeRequest {
+                number: row.get::<i64, _>("number") as u64,
+                title: row.get("title"),
+                base_ref: row.get("base_ref"),
+                head_ref: row.get("head_ref"),
+                cost_before,
+                cost_after,
+                change: cost_after - cost_before.unwrap_or_default(),
+                merged_at: row.get("merged_at"),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,477 @@
+use std::{fmt, str::FromStr};
+
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use ring::hmac;
+use serde_json::Value;
+
+use crate::{
+    bitbucket::Bitbucket, github::GitHub, gitlab::GitLab, source::Configurations,
+    webhook::WebhookError,
+};
+
+// Name of the status check, which can be made a required check
+pub const CHECK_NAME: &str = "Cost Analysis";
+
+// Hidden marker the bot finds its own change request comment by
+pub const COMMENT_MARKER: &str = "<!-- infralink-cost-bot -->";
+// Hidden marker between the latest breakdown and the history of earlier pushes
+const HISTORY_MARKER: &str = "<!-- infralink-cost-bot:history -->";
+// Separator between the breakdowns of earlier pushes
+const HISTORY_SEPARATOR: &str = "\n\n---\n\n";
+// Start of the line under every breakdown saying the commit it is as of
+const COSTS_AS_OF: &str = "_Costs as of ";
+// Earlier pushes kept in the comment, older ones are dropped to stay under the size limit
+const MAX_HISTORY: usize = 10;
+
+// Where a repository is hosted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl fmt::Display for Forge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Forge::GitHub => write!(f, "github"),
+            Forge::GitLab => write!(f, "gitlab"),
+            Forge::Bitbucket => write!(f, "bitbucket"),
+        }
+    }
+}
+
+impl FromStr for Forge {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(Forge::GitHub),
+            "gitlab" => Ok(Forge::GitLab),
+            "bitbucket" => Ok(Forge::Bitbucket),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForgeRepository {
+    pub forge: Forge,
+    // id of the repository on its forge, a uuid on Bitbucket
+    pub id: String,
+    // owner/name, or the path with namespace on GitLab
+    pub full_name: String,
+    // size of the repository in kilobytes, when the webhook says
+    pub size: Option<u64>,
+    // installation of the GitHub App the webhook was delivered to, if any
+    pub installation_id: Option<u64>,
+}
+
+// Where and by whom a pushed commit was made, kept with its costs in the history
+#[derive(Debug, Clone, Default)]
+pub struct CommitDetails {
+    pub branch: Option<String>,
+    pub author: Option<String>,
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+// What a webhook asks the bot to do, whichever forge it came from
+#[derive(Debug)]
+pub enum ForgeEvent {
+    // commits pushed to a branch, priced against the commit before the push
+    Push {
+        repository: ForgeRepository,
+        before: String,
+        after: String,
+        commit: CommitDetails,
+    },
+    // a pull request or merge request opened or pushed to, priced against its base. base and
+    // head are commits or branch names, resolved with resolve_commit
+    ChangeRequest {
+        repository: ForgeRepository,
+        number: u64,
+        title: String,
+        base: String,
+        head: String,
+    },
+    // a change request was merged, nothing is priced but its costs count as merged
+    Merged {
+        repository: ForgeRepository,
+        number: u64,
+        merged_at: Option<DateTime<Utc>>,
+    },
+}
+
+// A verified webhook delivery
+pub struct Delivery {
+    // event name as the forge sends it
+    pub event: String,
+    // id the forge redelivers with, so a delivery seen before is a replay
+    pub id: String,
+}
+
+// Result of the cost check on a commit
+pub struct CheckOutcome {
+    pub success: bool,
+    // one line per app above its limit, or the total when all are within
+    pub summary: String,
+    // markdown of the limits and breakdown, on forges that show it
+    pub text: String,
+    pub details_url: String,
+}
+
+// Everything the bot needs from a forge: reading its webhooks, its repositories, and
+// reporting back with comments and status checks. Jobs run on the actix runtime of their
+// worker, so the futures need not be Send
+#[allow(async_fn_in_trait)]
+pub trait ForgeProvider {
+    const FORGE: Forge;
+
+    // Check a delivery came from the forge, with the event name and delivery id
+    fn verify(&self, req: &HttpRequest, body: &[u8]) -> Result<Delivery, WebhookError>;
+
+    // What a delivery asks the bot to price, None for events the bot has nothing to do for
+    fn parse_event(&self, event: &str, payload: &Value)
+        -> Result<Option<ForgeEvent>, WebhookError>;
+
+    // Token to call the API and clone the repository with
+    async fn token(&self, client: &Client, repository: &ForgeRepository) -> Result<String, String>;
+
+    // Full sha of a commit or branch, as webhooks of some forges only carry short shas
+    async fn resolve_commit(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        reference: &str,
+        token: &str,
+    ) -> Result<String, String>;
+
+    fn clone_url(&self, repository: &ForgeRepository, token: &str) -> String;
+
+    // Configuration files read through the API, None when the forge can't list them all and
+    // the repository has to be cloned instead
+    async fn fetch_configuration_files(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<Configurations>, String>;
+
+    // Show the cost check as running on a commit, with the id of the check if the forge
+    // gives one. A check already on the commit from an earlier attempt is reused
+    async fn start_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String>;
+
+    async fn complete_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        check_id: Option<u64>,
+        outcome: &CheckOutcome,
+        token: &str,
+    ) -> Result<(), String>;
+
+    // The comment the bot left on a commit, with its id
+    async fn find_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String>;
+
+    // Replace the commit comment with the id, or write a new one, returning a link to it
+    async fn write_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String>;
+
+    // Link to a commit
+    fn commit_url(&self, repository: &ForgeRepository, sha: &str) -> String;
+
+    // Link to a change request
+    fn change_request_url(&self, repository: &ForgeRepository, number: u64) -> String;
+
+    // The comment the bot left on a change request, with its id and body
+    async fn find_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        token: &str,
+    ) -> Result<Option<(u64, String)>, String>;
+
+    // Replace the comment with the id, or write a new one, returning a link to it
+    async fn write_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String>;
+
+    // Comment on a commit, returning a link to the comment. A job that is retried updates
+    // the comment of its earlier attempt rather than posting another
+    async fn comment_on_commit(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let existing = self
+            .find_commit_comment(client, repository, sha, token)
+            .await?;
+
+        let url = self
+            .write_commit_comment(
+                client,
+                repository,
+                sha,
+                existing,
+                &format!("{}\n{}", COMMENT_MARKER, body),
+                token,
+            )
+            .await?;
+
+        // Print a success message
+        println!(
+            "Successfully completed cost analysis for {} @ {}",
+            repository.full_name, sha
+        );
+
+        Ok(url)
+    }
+
+    // The bot keeps a single comment per change request and updates it on every push. With
+    // no markdown the change request no longer changes the cost: the comment is marked as
+    // resolved, and none is posted if there wasn't one. Returns a link to the comment
+    async fn comment_on_change_request(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        head_sha: &str,
+        markdown: Option<&str>,
+        token: &str,
+    ) -> Result<Option<String>, String> {
+        let current = markdown.unwrap_or(
+            "## Cost Breakdown 📊\n\n✅ **Resolved**: this change no longer changes the monthly cost.",
+        );
+
+        let existing = self.find_comment(client, repository, number, token).await?;
+
+        let url = match existing {
+            Some((id, body)) => {
+                let comment = sticky_comment(current, head_sha, Some(&body));
+
+                Some(
+                    self.write_comment(client, repository, number, Some(id), &comment, token)
+                        .await?,
+                )
+            }
+            None if markdown.is_some() => {
+                let comment = sticky_comment(current, head_sha, None);
+
+                Some(
+                    self.write_comment(client, repository, number, None, &comment, token)
+                        .await?,
+                )
+            }
+            None => None,
+        };
+
+        // Print a success message
+        println!(
+            "Successfully completed cost analysis for {} @ {}",
+            repository.full_name, number
+        );
+
+        Ok(url)
+    }
+
+    // Complete the check of a job that will not be retried, so the commit isn't left showing
+    // it as running
+    async fn fail_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        error: &str,
+        token: &str,
+    ) -> Result<(), String> {
+        let check_id = self.start_check(client, repository, sha, token).await?;
+
+        let outcome = CheckOutcome {
+            success: false,
+            summary: String::from("The cost analysis failed, it will not be retried."),
+            text: format!("```\n{}\n```", error),
+            details_url: self.commit_url(repository, sha),
+        };
+
+        self.complete_check(client, repository, sha, check_id, &outcome, token)
+            .await
+    }
+}
+
+// The forges the bot is set up for
+pub struct Forges {
+    pub github: Option<GitHub>,
+    pub gitlab: Option<GitLab>,
+    pub bitbucket: Option<Bitbucket>,
+}
+
+impl Forges {
+    pub fn from_env() -> Self {
+        let forges = Self {
+            github: GitHub::from_env(),
+            gitlab: GitLab::from_env(),
+            bitbucket: Bitbucket::from_env(),
+        };
+
+        if forges.github.is_none() && forges.gitlab.is_none() && forges.bitbucket.is_none() {
+            panic!("GITHUB_APP_ID, GITHUB_TOKEN, GITLAB_TOKEN or BITBUCKET_TOKEN must be set");
+        }
+
+        forges
+    }
+}
+
+// Read a required setting of a forge
+pub fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("{} must be set", name))
+}
+
+// Percent-encode a path segment, e.g. a branch name with slashes in it
+pub fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+pub fn header<'a>(req: &'a HttpRequest, name: &'static str) -> Result<&'a str, WebhookError> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(WebhookError::MissingHeader(name))
+}
+
+// Check a sha256=<hex> signature header is the HMAC of the body, compared in constant time
+pub fn verify_signature(secret: &str, signature: &str, body: &[u8]) -> Result<(), WebhookError> {
+    let hex = signature
+        .strip_prefix("sha256=")
+        .filter(|hex| hex.is_ascii() && hex.len() % 2 == 0)
+        .ok_or(WebhookError::InvalidSignature)?;
+
+    let expected = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(WebhookError::InvalidSignature)?;
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+
+    hmac::verify(&key, body, &expected).map_err(|_| WebhookError::InvalidSignature)
+}
+
+// Check a plain token header matches the secret, compared in constant time by comparing the
+// HMACs of both
+pub fn verify_token(secret: &str, token: &str) -> Result<(), WebhookError> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+
+    hmac::verify(
+        &key,
+        token.as_bytes(),
+        hmac::sign(&key, secret.as_bytes()).as_ref(),
+    )
+    .map_err(|_| WebhookError::InvalidSignature)
+}
+
+// Body of the sticky comment: the latest breakdown, then the breakdowns of earlier pushes
+// folded away. The latest breakdown of the previous body moves to the top of the history
+fn sticky_comment(current: &str, head_sha: &str, previous_body: Option<&str>) -> String {
+    let mut history: Vec<String> = Vec::new();
+
+    if let Some(previous_body) = previous_body {
+        let previous_body = previous_body
+            .trim_start_matches(COMMENT_MARKER)
+            .trim_start();
+
+        let (previous_current, previous_history) = previous_body
+            .split_once(HISTORY_MARKER)
+            .unwrap_or((previous_body, ""));
+
+        // the same breakdown again, e.g. resolved twice, replaces the one before it
+        if costs(previous_current) != current.trim() {
+            history.push(previous_current.trim().to_string());
+        }
+
+        // the entries sit between the summary and the closing tag of the details block
+        let entries = previous_history
+            .split_once("</summary>")
+            .map(|(_, entries)| entries.trim_end().trim_end_matches("</details>").trim())
+            .unwrap_or_default();
+
+        history.extend(
+            entries
+                .split(HISTORY_SEPARATOR)
+                .filter(|entry| !entry.trim().is_empty())
+                .map(|entry| entry.trim().to_string()),
+        );
+    }
+
+    history.dedup_by(|entry, earlier| costs(entry) == costs(earlier));
+    history.truncate(MAX_HISTORY);
+
+    let body = format!(
+        "{}\n{}\n\n{}`{}`._",
+        COMMENT_MARKER,
+        current,
+        COSTS_AS_OF,
+        &head_sha[..head_sha.len().min(7)]
+    );
+
+    if history.is_empty() {
+        return body;
+    }
+
+    format!(
+        "{}\n\n{}\n<details><summary>Earlier pushes ({})</summary>\n\n{}\n\n</details>",
+        body,
+        HISTORY_MARKER,
+        history.len(),
+        history.join(HISTORY_SEPARATOR)
+    )
+}
+
+// A breakdown of the comment without the commit it is as of, to compare it with another
+fn costs(entry: &str) -> &str {
+    let entry = entry.trim();
+
+    entry
+        .rsplit_once(COSTS_AS_OF)
+        .map_or(entry, |(costs, _)| costs.trim_end())
+}
@@ -3,102 +3,137 @@ use std::{
     str::FromStr,
 };
 
-use git2::{build::RepoBuilder, FetchOptions, RemoteCallbacks};
+use git2::{FetchOptions, ObjectType, Oid, Repository, TreeEntry, TreeWalkMode, TreeWalkResult};
 use indexmap::IndexMap;
-use types::config::InfrastructureConfiguration;
-use walkdir::WalkDir;
-
-use crate::cost;
-
-pub fn clone(
-    repository_name: String,
-    _credentials: String,
-    specific_ref: String,
-    mut builder: RepoBuilder,
-) {
-    let callbacks = RemoteCallbacks::new();
+use types::{
+    adjustments::Adjustments, budget::Budget, config::InfrastructureConfiguration, discovery,
+};
+
+use crate::{cost::CostReport, source::Configurations};
+
+// Configuration files in a repository at a commit, from a shallow fetch of just that commit
+// into a temporary directory of its own, removed again once the files are read. Fetching
+// blocks, so it is run off the async runtime with spawn_blocking
+pub fn clone_configuration_files(url: &str, sha: &str) -> Result<Configurations, String> {
+    let directory = tempfile::Builder::new()
+        .prefix("infralink-")
+        .tempdir()
+        .map_err(|e| format!("Failed to create a directory to clone into: {}", e))?;
+
+    let repo = Repository::init_bare(directory.path()).map_err(|e| e.to_string())?;
+
     let mut options = FetchOptions::new();
 
     options.download_tags(git2::AutotagOption::None);
     options.update_fetchhead(false);
     options.depth(1);
 
-    options.remote_callbacks(callbacks);
-
-    builder.fetch_options(options);
-
-    // Construct the URL and local path
-    let url = format!("https://github.com/{}.git", repository_name);
-    let local_path = format!("./{}", repository_name.split('/').last().unwrap());
+    // fetch the commit itself, it need not be on the default branch
+    repo.remote_anonymous(url)
+        .and_then(|mut remote| remote.fetch(&[sha], Some(&mut options), None))
+        .map_err(|e| format!("Failed to fetch {}: {}", sha, e.message()))?;
+
+    let tree = Oid::from_str(sha)
+        .and_then(|oid| repo.find_commit(oid))
+        .and_then(|commit| commit.tree())
+        .map_err(|e| format!("Failed to read the tree of {}: {}", sha, e.message()))?;
+
+    let blob_contents = |entry: &TreeEntry| {
+        entry
+            .to_object(&repo)
+            .and_then(|object| object.peel_to_blob())
+            .map(|blob| String::from_utf8_lossy(blob.content()).to_string())
+            .map_err(|e| e.message().to_string())
+    };
+
+    let settings = match tree.get_path(Path::new(discovery::SETTINGS_FILE)) {
+        Ok(entry) => Some(blob_contents(&entry)?),
+        Err(_) => None,
+    };
+
+    let mut configurations = Configurations::new(settings.as_deref());
+
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    let mut errors: Vec<(PathBuf, String)> = Vec::new();
+
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        let path = Path::new(root).join(entry.name().unwrap_or_default());
+
+        if entry.kind() == Some(ObjectType::Blob) && configurations.is_configuration_file(&path) {
+            match blob_contents(entry) {
+                Ok(contents) => files.push((path, contents)),
+                Err(e) => errors.push((path, e)),
+            }
+        }
 
-    // Clone the repository to a specific path
-    let repo = builder.clone(&url, Path::new(&local_path)).unwrap();
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| format!("Failed to walk the tree of {}: {}", sha, e.message()))?;
 
-    // Checkout to the specific ref
-    let object = repo.revparse_single(&specific_ref).unwrap();
-    let mut checkout_opts = git2::build::CheckoutBuilder::new();
-    checkout_opts.force();
-    repo.checkout_tree(&object, Some(&mut checkout_opts))
-        .unwrap();
+    configurations.errors.extend(errors);
+    configurations.set_files(files);
 
-    // Set HEAD to point to our specific ref
-    repo.set_head(&format!("refs/heads/{}", specific_ref))
-        .unwrap();
+    Ok(configurations)
 }
 
-pub fn delete(repository_name: String) {
-    std::fs::remove_dir_all(Path::new(&format!(
-        "./{}",
-        repository_name.split('/').last().unwrap()
-    )))
-    .unwrap();
-}
-
-pub fn configuration_files(repository_name: String) -> IndexMap<PathBuf, String> {
-    let mut files = IndexMap::new();
-
-    // Search for all infra.toml files recursively using the walkdir crate
-    let walker = WalkDir::new(Path::new(&format!(
-        "./{}",
-        repository_name.split('/').last().unwrap()
-    )));
-
-    for entry in walker.into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("toml") {
-            let contents = std::fs::read_to_string(path).unwrap();
+// Price every configuration file, a file that can't be parsed or priced is reported as an
+// error rather than failing the whole report
+pub async fn cost_breakdowns(
+    configurations: Configurations,
+    adjustments: &Adjustments,
+) -> CostReport {
+    let mut report = CostReport::new(adjustments);
+
+    for (path, error) in &configurations.errors {
+        report
+            .errors
+            .insert(path.display().to_string(), error.clone());
+    }
 
-            files.insert(path.to_path_buf(), contents);
+    for (path, contents) in &configurations.files {
+        let configuration = match InfrastructureConfiguration::from_str(contents) {
+            Ok(configuration) => configuration,
+            Err(e) => {
+                report
+                    .errors
+                    .insert(path.display().to_string(), e.to_string());
+                continue;
+            }
+        };
+
+        let name = app_name(&configurations, path, &configuration);
+
+        if let Err(e) = report.add_app(name, &configuration, adjustments).await {
+            report.errors.insert(path.display().to_string(), e);
         }
     }
 
-    let mut file_vec: Vec<(PathBuf, String)> = files.into_iter().collect();
-
-    // Sort the vector based on the depth of the file tree
-    file_vec.sort_by(|a, b| {
-        let depth_a = a.0.components().count();
-        let depth_b = b.0.components().count();
-        depth_a.cmp(&depth_b)
-    });
-
-    files = file_vec.into_iter().collect();
-
-    files
+    report
 }
 
-pub async fn cost_breakdowns(
-    files: IndexMap<PathBuf, String>,
-) -> IndexMap<String, IndexMap<String, f64>> {
-    let mut breakdowns: IndexMap<String, IndexMap<String, f64>> = IndexMap::new();
-
-    for (_path, contents) in files {
-        let config = InfrastructureConfiguration::from_str(&contents).unwrap();
-
-        // next, parse the contents of the infra.toml file and analyse it.
-        let breakdown = cost::calculate_cost(&config).await;
-
-        breakdowns.insert(config.app.name, breakdown);
+// Name of an app in the report, told apart from the same app in other environments
+fn app_name(
+    configurations: &Configurations,
+    path: &Path,
+    configuration: &InfrastructureConfiguration,
+) -> String {
+    match configurations.discovery.environment(path) {
+        Some(environment) => format!("{} ({})", configuration.app.name, environment),
+        None => configuration.app.name.clone(),
     }
+}
 
-    breakdowns
+// Budgets declared by the configuration files, by app name, without pricing them. Files that
+// can't be parsed declare none
+pub fn declared_budgets(configurations: &Configurations) -> IndexMap<String, Budget> {
+    configurations
+        .files
+        .iter()
+        .filter_map(|(path, contents)| {
+            let configuration = InfrastructureConfiguration::from_str(contents).ok()?;
+            let budget = configuration.budget.clone()?;
+
+            Some((app_name(configurations, path, &configuration), budget))
+        })
+        .collect()
 }
@@ -1,52 +1,166 @@
-use indexmap::IndexMap;
+use std::{path::PathBuf, str::FromStr, sync::Mutex};
+
+use actix_web::HttpRequest;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use reqwest::{header::USER_AGENT, Client};
-use serde_json::json;
+use serde_json::{json, Value};
+use types::discovery;
+
+use crate::{
+    auth::GitHubAuth,
+    forge::{
+        self, CheckOutcome, CommitDetails, Delivery, Forge, ForgeEvent, ForgeProvider,
+        ForgeRepository, CHECK_NAME, COMMENT_MARKER,
+    },
+    models::{
+        events::GitHubEvent,
+        pull_request::PullRequest,
+        push::{Installation, Push, Repository},
+        tree::{Blob, Tree},
+    },
+    source::Configurations,
+    webhook::WebhookError,
+};
+
+// Base url of the GitHub REST API, overridable to point the bot at GitHub Enterprise or a
+// mock server
+pub fn api_url() -> String {
+    std::env::var("GITHUB_API_URL").unwrap_or(String::from("https://api.github.com"))
+}
+
+// Base url of GitHub itself, used for clones and links
+pub fn web_url() -> String {
+    std::env::var("GITHUB_URL").unwrap_or(String::from("https://github.com"))
+}
 
 // Function to write a comment to a specific commit reference
 pub async fn write_comment_to_commit_ref(
-    client: actix_web::web::Data<Client>,
+    client: &Client,
     comment: String,
     commit_ref: &str,
     repository_name: &str,
-) -> u64 {
-    // Fetch the GitHub token from the environment variables
-    let token = std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN not set");
-
+    token: &str,
+) -> Result<u64, String> {
     // Split the repository name into owner and repo
-    let parts: Vec<&str> = repository_name.split('/').collect();
-
-    if parts.len() != 2 {
-        eprintln!("Invalid repository name");
-    }
-
-    let (owner, repo) = (parts[0], parts[1]);
+    let Some((owner, repo)) = repository_name.split_once('/') else {
+        return Err(format!("Invalid repository name {}", repository_name));
+    };
 
     // Send a POST request to the GitHub API to write a comment to the commit
     let response = client
         .post(&format!(
-            "https://api.github.com/repos/{}/{}/commits/{}/comments",
-            owner, repo, commit_ref
+            "{}/repos/{}/{}/commits/{}/comments",
+            api_url(),
+            owner,
+            repo,
+            commit_ref
         ))
         .header(USER_AGENT, "Infralink Cost Bot")
         .bearer_auth(token)
         .json(&serde_json::json!({ "body": comment }))
         .send()
         .await
-        .expect("Failed to send request");
+        .map_err(|e| format!("Failed to send request: {}", e))?;
 
     // Check the response status
     if response.status() != 201 {
-        panic!("Failed to write comment to commit");
+        return Err(format!(
+            "Failed to write comment to commit: {}",
+            response.status()
+        ));
     }
 
     // Parse the response JSON
-    let json: serde_json::Value = response.json().await.unwrap();
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
 
     // Return the comment ID
     json["id"]
         .as_u64()
-        .ok_or("Failed to get comment ID")
-        .unwrap()
+        .ok_or(String::from("Failed to get comment ID"))
+}
+
+// Function to find the comment the bot left on a commit
+async fn find_commit_comment(
+    client: &Client,
+    commit_ref: &str,
+    repository_name: &str,
+    login: &str,
+    token: &str,
+) -> Result<Option<u64>, String> {
+    let mut page = 1;
+
+    loop {
+        let response = client
+            .get(format!(
+                "{}/repos/{}/commits/{}/comments?per_page=100&page={}",
+                api_url(),
+                repository_name,
+                commit_ref,
+                page
+            ))
+            .header(USER_AGENT, "Infralink Cost Bot")
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to list commit comments: {}",
+                response.status()
+            ));
+        }
+
+        let comments: Vec<serde_json::Value> = response.json().await.map_err(|e| e.to_string())?;
+
+        if comments.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(comment) = comments
+            .iter()
+            .find(|comment| is_bot_comment(comment, login))
+        {
+            return Ok(comment["id"].as_u64());
+        }
+
+        page += 1;
+    }
+}
+
+// Function to replace the body of a commit comment
+async fn update_commit_comment(
+    client: &Client,
+    comment_id: u64,
+    comment: String,
+    repository_name: &str,
+    token: &str,
+) -> Result<(), String> {
+    // Send a PATCH request to the GitHub API to update the comment
+    let response = client
+        .patch(format!(
+            "{}/repos/{}/comments/{}",
+            api_url(),
+            repository_name,
+            comment_id
+        ))
+        .header(USER_AGENT, "Infralink Cost Bot")
+        .bearer_auth(token)
+        .json(&json!({ "body": comment }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!(
+            "Failed to update commit comment: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
 }
 
 // Function to write a comment to a pull request
@@ -54,13 +168,12 @@ pub async fn write_comment_to_pull_request(
     comment: String,
     pull_request_number: u64,
     repository_name: &str,
-) -> u64 {
-    // Fetch the GitHub token from the environment variables
-    let token = std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN not set");
+    token: &str,
+) -> Result<u64, String> {
     // Split the repository name into owner and repo
-    let parts: Vec<&str> = repository_name.split('/').collect();
-
-    let (owner, repo) = (parts[0], parts[1]);
+    let Some((owner, repo)) = repository_name.split_once('/') else {
+        return Err(format!("Invalid repository name {}", repository_name));
+    };
 
     // Create a new client
     let client = reqwest::Client::new();
@@ -68,240 +181,1208 @@ pub async fn write_comment_to_pull_request(
     // Send a POST request to the GitHub API to write a comment to the pull request
     let response = client
         .post(&format!(
-            "https://api.github.com/repos/{}/{}/issues/{}/comments", // notice the change from "pulls" to "issues"
-            owner, repo, pull_request_number
+            "{}/repos/{}/{}/issues/{}/comments", // notice the change from "pulls" to "issues"
+            api_url(),
+            owner,
+            repo,
+            pull_request_number
         ))
         .header("User-Agent", "Infralink Cost Bot")
         .bearer_auth(token)
         .json(&serde_json::json!({ "body": comment }))
         .send()
         .await
-        .expect("Failed to send request");
+        .map_err(|e| format!("Failed to send request: {}", e))?;
 
     // Check the response status
     if response.status() != 201 {
-        panic!("Failed to write comment to pull request");
+        return Err(format!(
+            "Failed to write comment to pull request: {}",
+            response.status()
+        ));
     }
 
     // Parse the response JSON
-    let json: serde_json::Value = response.json().await.unwrap();
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
 
     // Return the comment ID
     json["id"]
         .as_u64()
-        .ok_or("Failed to get comment ID")
-        .unwrap()
+        .ok_or(String::from("Failed to get comment ID"))
 }
 
-// Function to generate markdown for the cost breakdown
-fn generate_markdown(
-    previous_breakdown: Option<IndexMap<String, IndexMap<String, f64>>>,
-    breakdowns: &IndexMap<String, IndexMap<String, f64>>,
-) -> String {
-    // Calculate the total monthly cost for all apps
-    let total_cost: f64 = breakdowns
-        .iter()
-        .map(|(_, costs)| costs.values().sum::<f64>())
-        .sum();
-
-    // Calculate the previous total cost, if available
-    let prev_total_cost: Option<f64> = previous_breakdown.as_ref().map(|prev| {
-        prev.iter()
-            .map(|(_, costs)| costs.values().sum::<f64>())
-            .sum()
-    });
-
-    // Calculate the total cost change and percentage, if previous data is available
-    let (total_cost_diff, _total_percent_diff) = if let Some(prev_total) = prev_total_cost {
-        let diff = total_cost - prev_total;
-        let percent_diff = (diff / prev_total) * 100.0;
-        let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
-        (
-            format!(" (Change: {} {:.1}%)", sign, percent_diff.abs()),
-            true,
-        )
-    } else {
-        (String::from(""), false)
-    };
+// Function to find the comment the bot left on a pull request, with its body
+async fn find_pull_request_comment(
+    pull_request_number: u64,
+    repository_name: &str,
+    login: &str,
+    token: &str,
+) -> Result<Option<(u64, String)>, String> {
+    // Create a new client
+    let client = reqwest::Client::new();
+
+    let mut page = 1;
+
+    loop {
+        let response = client
+            .get(format!(
+                "{}/repos/{}/issues/{}/comments?per_page=100&page={}",
+                api_url(),
+                repository_name,
+                pull_request_number,
+                page
+            ))
+            .header(USER_AGENT, "Infralink Cost Bot")
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to list pull request comments: {}",
+                response.status()
+            ));
+        }
+
+        let comments: Vec<serde_json::Value> = response.json().await.map_err(|e| e.to_string())?;
+
+        if comments.is_empty() {
+            return Ok(None);
+        }
+
+        for comment in comments {
+            if !is_bot_comment(&comment, login) {
+                continue;
+            }
+
+            if let Some(id) = comment["id"].as_u64() {
+                let body = comment["body"].as_str().unwrap_or_default();
+
+                return Ok(Some((id, body.to_string())));
+            }
+        }
 
-    // Create the header and summary
-    let header = format!(
-      "## Cost Breakdown 📊\n\n## Summary\n\n- **Total Monthly Cost**: ${:.2}{}\n\n---\n\n## Detailed Breakdown",
-      total_cost, total_cost_diff
-    );
-
-    // Generate the markdown for each app
-    let markdown = breakdowns
-      .iter()
-      .map(|(app, costs)| {
-          let prev_costs = previous_breakdown.as_ref().and_then(|prev| prev.get(app));
-          let has_change_for_app = costs.iter().any(|(service, cost)| {
-              if service != "Total" && service != "Data Transfer" {
-                  prev_costs.map_or(false, |prev| {
-                      prev.get(service).map_or(false, |prev_cost| (cost - prev_cost).abs() > 1e-9)
-                  })
-              } else {
-                  false
-              }
-          });
-
-          let table_header = if has_change_for_app {
-              "| Service            | Cost  | Change (%) |\n|--------------------|-------|------------|"
-          } else {
-              "| Service            | Cost  |\n|--------------------|-------|"
-          };
-
-          let costs_table = costs
-              .iter()
-              .filter_map(|(service, cost)| {
-                  if service != "Total" && service != "Data Transfer" {
-                      let change_str = if let Some(prev_cost) = prev_costs.and_then(|prev| prev.get(service)) {
-                          let diff = cost - prev_cost;
-                          let percent = (diff / prev_cost) * 100.0;
-                          if diff.abs() > 1e-9 {
-                              let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
-                              Some(format!("{} {:.1}%", sign, percent.abs()))
-                          } else {
-                              None
-                          }
-                      } else {
-                          None
-                      };
-
-                      let change_column = if has_change_for_app {
-                          format!("| {}", change_str.unwrap_or_default())
-                      } else {
-                          "".to_string()
-                      };
-
-                      Some(format!("| {} | ${:.2} {}", service, cost, change_column))
-                  } else {
-                      None
-                  }
-              })
-              .collect::<Vec<_>>()
-              .join("\n");
-
-          let total_cost: f64 = costs.values().sum();
-          let total_cost_diff = if let Some(prev_costs) = prev_costs {
-              let prev_total: f64 = prev_costs.values().sum();
-              let diff = total_cost - prev_total;
-              if diff.abs() > 1e-9 {
-                  let sign = if diff > 0.0 { "⬆️" } else { "⬇️" };
-                  format!("| {} {:.1}%", sign, ((diff / prev_total) * 100.0).abs())
-              } else {
-                  "| -".to_string()
-              }
-          } else {
-              "| -".to_string()
-          };
-
-          let total_line = format!("| **Total** | **${:.2}** {}", total_cost, total_cost_diff);
-
-          format!(
-              "### {}\n\n{}\n{}\n{}",
-              app, table_header, costs_table, total_line
-          )
-      })
-      .collect::<Vec<_>>()
-      .join("\n\n");
-
-    format!("{}\n\n{}", header, markdown)
+        page += 1;
+    }
 }
 
-// Function to comment on a commit with the cost breakdown
-pub async fn comment_on_commit(
-    client: actix_web::web::Data<Client>,
-    previous_breakdown: Option<IndexMap<String, IndexMap<String, f64>>>,
-    breakdowns: &IndexMap<String, IndexMap<String, f64>>,
-    commit_ref: &str,
+// Whether a comment was left by the bot, anyone can copy the marker into a comment of their own
+fn is_bot_comment(comment: &serde_json::Value, login: &str) -> bool {
+    comment["user"]["login"].as_str() == Some(login)
+        && comment["body"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with(COMMENT_MARKER)
+}
+
+// Function to replace the body of a pull request comment
+async fn update_pull_request_comment(
+    comment_id: u64,
+    comment: String,
     repository_name: &str,
-) -> u64 {
-    // Generate the markdown for the cost breakdown
-    let full_markdown = generate_markdown(previous_breakdown, breakdowns);
+    token: &str,
+) -> Result<(), String> {
+    // Create a new client
+    let client = reqwest::Client::new();
 
-    // Write the markdown to a comment on the commit
-    let id = write_comment_to_commit_ref(client, full_markdown, commit_ref, repository_name).await;
+    // Send a PATCH request to the GitHub API to update the comment
+    let response = client
+        .patch(format!(
+            "{}/repos/{}/issues/comments/{}",
+            api_url(),
+            repository_name,
+            comment_id
+        ))
+        .header(USER_AGENT, "Infralink Cost Bot")
+        .bearer_auth(token)
+        .json(&json!({ "body": comment }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
 
-    // Print a success message
-    println!(
-        "Successfully completed cost analysis for {} @ {}",
-        repository_name, commit_ref
-    );
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!(
+            "Failed to update pull request comment: {}",
+            response.status()
+        ));
+    }
 
-    // Return the comment ID
-    id
+    Ok(())
 }
 
-// Function to comment on a pull request with the cost breakdown
-pub async fn comment_on_pull_request(
-    previous_breakdown: Option<IndexMap<String, IndexMap<String, f64>>>,
-    breakdowns: &IndexMap<String, IndexMap<String, f64>>,
-    pull_request_number: u64,
+// Function to find the check run of an earlier attempt on a commit
+async fn find_check_run(
     repository_name: &str,
-) -> u64 {
-    // Generate the markdown for the cost breakdown
-    let full_markdown = generate_markdown(previous_breakdown, breakdowns);
+    head_sha: &str,
+    token: &str,
+) -> Result<Option<u64>, String> {
+    // Create a new client
+    let client = reqwest::Client::new();
 
-    // Write the markdown to a comment on the pull request
-    let id =
-        write_comment_to_pull_request(full_markdown, pull_request_number, repository_name).await;
+    // Send a GET request to the GitHub API to list the check runs with our name
+    let response = client
+        .get(format!(
+            "{}/repos/{}/commits/{}/check-runs?check_name={}&filter=latest",
+            api_url(),
+            repository_name,
+            head_sha,
+            forge::encode(CHECK_NAME)
+        ))
+        .header(USER_AGENT, "Cost-Bot")
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
 
-    // Print a success message
-    println!(
-        "Successfully completed cost analysis for {} @ {}",
-        repository_name, pull_request_number
-    );
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!("Failed to list check runs: {}", response.status()));
+    }
 
-    // Return the comment ID
-    id
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+
+    Ok(json["check_runs"][0]["id"].as_u64())
 }
 
-// Function to mark a check run
-pub async fn mark_check_run(
+// Function to create a queued check run on a commit
+pub async fn create_check_run(
     repository_name: &str,
-    commit_ref: &str,
-    status: &str,
-    title: &str,
-    summary: &str,
-    comment_id: u64,
-) {
-    // Fetch the GitHub token from the environment variables
-    let token = std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN not set");
+    head_sha: &str,
+    token: &str,
+) -> Result<u64, String> {
     // Create a new client
     let client = reqwest::Client::new();
 
-    // Define the URL for the check run
-    let url = format!(
-        "https://api.github.com/repos/{}/check-runs",
-        repository_name
-    );
-
-    // Define the body of the request
-    let body = json!({
-        "name": title,
-        "head_sha": commit_ref,
-        "status": "completed",
-        "conclusion": status,
-        "details_url": format!("https://github.com/{}/commit/{}#commitcomment-{}", repository_name, commit_ref, comment_id),
-        "output": {
-            "title": title,
-            "summary": summary
-        }
-    });
+    // Send a POST request to the GitHub API to create the check run
+    let response = client
+        .post(format!(
+            "{}/repos/{}/check-runs",
+            api_url(),
+            repository_name
+        ))
+        .header(USER_AGENT, "Cost-Bot")
+        .bearer_auth(token)
+        .json(&json!({
+            "name": CHECK_NAME,
+            "head_sha": head_sha,
+            "status": "queued",
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create check run: {}", e))?;
 
-    // Send a POST request to the GitHub API to mark the check run
+    // Check the response status
+    if response.status() != 201 {
+        return Err(format!("Failed to create check run: {}", response.status()));
+    }
+
+    // Parse the response JSON
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+
+    // Return the check run ID
+    json["id"]
+        .as_u64()
+        .ok_or(String::from("Failed to get check run ID"))
+}
+
+// Function to update a check run
+async fn update_check_run(
+    repository_name: &str,
+    check_run_id: u64,
+    body: serde_json::Value,
+    token: &str,
+) -> Result<(), String> {
+    // Create a new client
+    let client = reqwest::Client::new();
+
+    // Send a PATCH request to the GitHub API to update the check run
     let response = client
-        .post(&url)
+        .patch(format!(
+            "{}/repos/{}/check-runs/{}",
+            api_url(),
+            repository_name,
+            check_run_id
+        ))
         .header(USER_AGENT, "Cost-Bot")
-        .header("Authorization", format!("Bearer {}", token))
+        .bearer_auth(token)
         .json(&body)
         .send()
         .await
-        .expect("Failed to mark check run");
+        .map_err(|e| format!("Failed to update check run: {}", e))?;
+
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!("Failed to update check run: {}", response.status()));
+    }
+
+    Ok(())
+}
+
+// Function to mark a check run as in progress, once the repository has been cloned
+pub async fn start_check_run(
+    repository_name: &str,
+    check_run_id: u64,
+    token: &str,
+) -> Result<(), String> {
+    update_check_run(
+        repository_name,
+        check_run_id,
+        json!({ "status": "in_progress" }),
+        token,
+    )
+    .await
+}
+
+// Function to complete a check run with its conclusion, e.g. success or failure
+pub async fn complete_check_run(
+    repository_name: &str,
+    check_run_id: u64,
+    conclusion: &str,
+    summary: &str,
+    text: &str,
+    details_url: &str,
+    token: &str,
+) -> Result<(), String> {
+    update_check_run(
+        repository_name,
+        check_run_id,
+        json!({
+            "status": "completed",
+            "conclusion": conclusion,
+            "details_url": details_url,
+            "output": {
+                "title": CHECK_NAME,
+                "summary": summary,
+                "text": text,
+            }
+        }),
+        token,
+    )
+    .await
+}
+
+// Function to set the commit status of the cost check, used instead of check runs when the
+// bot has a token rather than a GitHub App, which is the only one allowed to create them
+async fn set_commit_status(
+    client: &Client,
+    repository_name: &str,
+    sha: &str,
+    state: &str,
+    description: &str,
+    target_url: &str,
+    token: &str,
+) -> Result<(), String> {
+    // Send a POST request to the GitHub API to set the status
+    let response = client
+        .post(format!(
+            "{}/repos/{}/statuses/{}",
+            api_url(),
+            repository_name,
+            sha
+        ))
+        .header(USER_AGENT, "Cost-Bot")
+        .bearer_auth(token)
+        .json(&json!({
+            "state": state,
+            "context": CHECK_NAME,
+            // descriptions are limited to 140 characters
+            "description": description.chars().take(140).collect::<String>(),
+            "target_url": target_url,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
 
     // Check the response status
     if response.status() != 201 {
-        panic!("Failed to mark check run");
+        return Err(format!(
+            "Failed to set commit status: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+// Function to fetch the contents of a blob
+async fn fetch_blob(
+    client: &Client,
+    repository_name: &str,
+    sha: &str,
+    token: &str,
+) -> Result<String, String> {
+    // Send a GET request to the GitHub API to fetch the blob
+    let response = client
+        .get(format!(
+            "{}/repos/{}/git/blobs/{}",
+            api_url(),
+            repository_name,
+            sha
+        ))
+        .header(USER_AGENT, "Cost-Bot")
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!(
+            "Failed to fetch blob {}: {}",
+            sha,
+            response.status()
+        ));
+    }
+
+    let blob: Blob = response.json().await.map_err(|e| e.to_string())?;
+
+    match blob.encoding.as_str() {
+        // base64 is wrapped over several lines
+        "base64" => STANDARD
+            .decode(blob.content.replace('\n', ""))
+            .map(|content| String::from_utf8_lossy(&content).to_string())
+            .map_err(|e| format!("Blob {} is not valid base64: {}", sha, e)),
+        _ => Ok(blob.content),
+    }
+}
+
+// Configuration files in a repository at a commit, without cloning it: the tree lists every
+// path and only the matching blobs are downloaded. None when the tree is too large for GitHub
+// to list in full
+pub async fn fetch_configuration_files(
+    client: &Client,
+    repository_name: &str,
+    sha: &str,
+    token: &str,
+) -> Result<Option<Configurations>, String> {
+    // Send a GET request to the GitHub API to list the tree recursively
+    let response = client
+        .get(format!(
+            "{}/repos/{}/git/trees/{}?recursive=1",
+            api_url(),
+            repository_name,
+            sha
+        ))
+        .header(USER_AGENT, "Cost-Bot")
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    // Check the response status
+    if response.status() != 200 {
+        return Err(format!(
+            "Failed to list repository tree: {}",
+            response.status()
+        ));
+    }
+
+    let tree: Tree = response.json().await.map_err(|e| e.to_string())?;
+
+    if tree.truncated {
+        return Ok(None);
+    }
+
+    let blobs = tree
+        .tree
+        .iter()
+        .filter(|entry| entry.r#type == "blob")
+        .collect::<Vec<_>>();
+
+    let settings = match blobs
+        .iter()
+        .find(|entry| entry.path == discovery::SETTINGS_FILE)
+    {
+        Some(entry) => Some(fetch_blob(client, repository_name, &entry.sha, token).await?),
+        None => None,
+    };
+
+    let mut configurations = Configurations::new(settings.as_deref());
+
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+
+    for entry in blobs {
+        let path = PathBuf::from(&entry.path);
+
+        if configurations.is_configuration_file(&path) {
+            files.push((
+                path,
+                fetch_blob(client, repository_name, &entry.sha, token).await?,
+            ));
+        }
+    }
+
+    configurations.set_files(files);
+
+    Ok(Some(configurations))
+}
+
+// Repositories on GitHub or GitHub Enterprise, through a GitHub App or a token
+pub struct GitHub {
+    auth: GitHubAuth,
+    // secret the webhook payloads are signed with
+    secret: String,
+    // login the bot comments as, fetched once
+    login: Mutex<Option<String>>,
+}
+
+impl GitHub {
+    // Set up when GITHUB_APP_ID or GITHUB_TOKEN is set
+    pub fn from_env() -> Option<Self> {
+        let auth = GitHubAuth::from_env()?;
+
+        Some(Self {
+            auth,
+            secret: forge::required_env("GITHUB_WEBHOOK_SECRET"),
+            login: Mutex::new(None),
+        })
+    }
+
+    // Function to get the login the bot comments as, to tell its comments from copies
+    async fn login(&self, client: &Client) -> Result<String, String> {
+        if let Some(login) = self.login.lock().unwrap().clone() {
+            return Ok(login);
+        }
+
+        let login = self.auth.login(client).await?;
+
+        *self.login.lock().unwrap() = Some(login.clone());
+
+        Ok(login)
+    }
+}
+
+fn forge_repository(
+    repository: &Repository,
+    installation: Option<&Installation>,
+) -> ForgeRepository {
+    ForgeRepository {
+        forge: Forge::GitHub,
+        id: repository.id.to_string(),
+        full_name: repository.full_name.clone(),
+        size: repository.size,
+        installation_id: installation.map(|installation| installation.id),
+    }
+}
+
+impl ForgeProvider for GitHub {
+    const FORGE: Forge = Forge::GitHub;
+
+    fn verify(&self, req: &HttpRequest, body: &[u8]) -> Result<Delivery, WebhookError> {
+        // only trust payloads signed with our secret
+        forge::verify_signature(
+            &self.secret,
+            forge::header(req, "X-Hub-Signature-256")?,
+            body,
+        )?;
+
+        Ok(Delivery {
+            event: forge::header(req, "X-GitHub-Event")?.to_string(),
+            id: forge::header(req, "X-GitHub-Delivery")?.to_string(),
+        })
+    }
+
+    fn parse_event(
+        &self,
+        event: &str,
+        payload: &Value,
+    ) -> Result<Option<ForgeEvent>, WebhookError> {
+        let event_type = GitHubEvent::from_str(event)
+            .map_err(|_| WebhookError::UnsupportedEvent(event.to_string()))?;
+
+        match event_type {
+            GitHubEvent::Push => {
+                let event: Push = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                // nothing to price on a deleted branch
+                let Some(head_commit) = event.head_commit else {
+                    return Ok(None);
+                };
+
+                Ok(Some(ForgeEvent::Push {
+                    repository: forge_repository(&event.repository, event.installation.as_ref()),
+                    before: event.before,
+                    after: event.after,
+                    commit: CommitDetails {
+                        branch: event.r#ref.strip_prefix("refs/heads/").map(String::from),
+                        // the name the commit was made with, for authors without an account
+                        author: head_commit
+                            .author
+                            .username
+                            .or(Some(head_commit.author.name)),
+                        committed_at: Some(head_commit.timestamp),
+                    },
+                }))
+            }
+            GitHubEvent::PullRequest => {
+                let event: PullRequest = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                let repository = forge_repository(&event.repository, event.installation.as_ref());
+
+                match event.action.as_str() {
+                    "opened" | "synchronize" => Ok(Some(ForgeEvent::ChangeRequest {
+                        repository,
+                        number: event.number,
+                        title: event.pull_request.title,
+                        base: event.pull_request.base.sha,
+                        head: event.pull_request.head.sha,
+                    })),
+                    "closed" if event.pull_request.merged => Ok(Some(ForgeEvent::Merged {
+                        repository,
+                        number: event.number,
+                        merged_at: event.pull_request.merged_at,
+                    })),
+                    _ => Ok(None),
+                }
+            }
+            GitHubEvent::Ping => Ok(None),
+        }
+    }
+
+    async fn token(&self, client: &Client, repository: &ForgeRepository) -> Result<String, String> {
+        // token of the installation the repository belongs to
+        self.auth.token(client, repository.installation_id).await
+    }
+
+    async fn resolve_commit(
+        &self,
+        _client: &Client,
+        _repository: &ForgeRepository,
+        reference: &str,
+        _token: &str,
+    ) -> Result<String, String> {
+        // GitHub webhooks carry full shas
+        Ok(reference.to_string())
+    }
+
+    fn clone_url(&self, repository: &ForgeRepository, token: &str) -> String {
+        let web_url = web_url();
+
+        match web_url.split_once("://") {
+            Some((scheme, host)) if !token.is_empty() => format!(
+                "{}://x-access-token:{}@{}/{}.git",
+                scheme, token, host, repository.full_name
+            ),
+            _ => format!("{}/{}.git", web_url, repository.full_name),
+        }
+    }
+
+    async fn fetch_configuration_files(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<Configurations>, String> {
+        fetch_configuration_files(client, &repository.full_name, sha, token).await
+    }
+
+    async fn start_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String> {
+        // statuses are found by commit and context, so setting one again replaces it
+        if let GitHubAuth::Token(_) = self.auth {
+            set_commit_status(
+                client,
+                &repository.full_name,
+                sha,
+                "pending",
+                "Pricing the infrastructure",
+                &self.commit_url(repository, sha),
+                token,
+            )
+            .await?;
+
+            return Ok(None);
+        }
+
+        // a retried job reuses the check run of its earlier attempt
+        let check_run_id = match find_check_run(&repository.full_name, sha, token).await? {
+            Some(check_run_id) => check_run_id,
+            None => create_check_run(&repository.full_name, sha, token).await?,
+        };
+
+        start_check_run(&repository.full_name, check_run_id, token).await?;
+
+        Ok(Some(check_run_id))
+    }
+
+    async fn complete_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        check_id: Option<u64>,
+        outcome: &CheckOutcome,
+        token: &str,
+    ) -> Result<(), String> {
+        let conclusion = if outcome.success {
+            "success"
+        } else {
+            "failure"
+        };
+
+        // a status only has room for the first line of the summary
+        let Some(check_run_id) = check_id else {
+            return set_commit_status(
+                client,
+                &repository.full_name,
+                sha,
+                conclusion,
+                outcome.summary.lines().next().unwrap_or_default(),
+                &outcome.details_url,
+                token,
+            )
+            .await;
+        };
+
+        complete_check_run(
+            &repository.full_name,
+            check_run_id,
+            conclusion,
+            &outcome.summary,
+            &outcome.text,
+            &outcome.details_url,
+            token,
+        )
+        .await
+    }
+
+    async fn find_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String> {
+        let login = self.login(client).await?;
+
+        find_commit_comment(client, sha, &repository.full_name, &login, token).await
+    }
+
+    async fn write_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let id = match comment_id {
+            Some(id) => {
+                update_commit_comment(client, id, body.to_string(), &repository.full_name, token)
+                    .await?;
+
+                id
+            }
+            None => {
+                write_comment_to_commit_ref(
+                    client,
+                    body.to_string(),
+                    sha,
+                    &repository.full_name,
+                    token,
+                )
+                .await?
+            }
+        };
+
+        Ok(format!(
+            "{}#commitcomment-{}",
+            self.commit_url(repository, sha),
+            id
+        ))
+    }
+
+    fn commit_url(&self, repository: &ForgeRepository, sha: &str) -> String {
+        format!("{}/{}/commit/{}", web_url(), repository.full_name, sha)
+    }
+
+    fn change_request_url(&self, repository: &ForgeRepository, number: u64) -> String {
+        format!("{}/{}/pull/{}", web_url(), repository.full_name, number)
+    }
+
+    async fn find_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        token: &str,
+    ) -> Result<Option<(u64, String)>, String> {
+        let login = self.login(client).await?;
+
+        find_pull_request_comment(number, &repository.full_name, &login, token).await
+    }
+
+    async fn write_comment(
+        &self,
+        _client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let id = match comment_id {
+            Some(id) => {
+                update_pull_request_comment(id, body.to_string(), &repository.full_name, token)
+                    .await?;
+
+                id
+            }
+            None => {
+                write_comment_to_pull_request(
+                    body.to_string(),
+                    number,
+                    &repository.full_name,
+                    token,
+                )
+                .await?
+            }
+        };
+
+        Ok(format!(
+            "{}#issuecomment-{}",
+            self.change_request_url(repository, number),
+            id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use wiremock::{
+        matchers::{body_partial_json, header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{auth::GitHubApp, mock};
+
+    fn github(auth: GitHubAuth) -> GitHub {
+        GitHub {
+            auth,
+            secret: String::from("secret"),
+            login: Mutex::new(None),
+        }
+    }
+
+    fn repository(full_name: &str) -> ForgeRepository {
+        ForgeRepository {
+            forge: Forge::GitHub,
+            id: String::from("1"),
+            full_name: full_name.to_string(),
+            size: None,
+            installation_id: Some(101),
+        }
+    }
+
+    #[test]
+    fn clone_url_carries_the_token() {
+        let server = mock::server();
+        let github = github(GitHubAuth::Token(String::from("ghp_token")));
+
+        assert_eq!(
+            github.clone_url(&repository("octo/infra"), "ghs_token"),
+            format!(
+                "http://x-access-token:ghs_token@{}/octo/infra.git",
+                server.address()
+            )
+        );
+    }
+
+    #[test]
+    fn clone_url_without_a_token_is_anonymous() {
+        let server = mock::server();
+        let github = github(GitHubAuth::Token(String::new()));
+
+        assert_eq!(
+            github.clone_url(&repository("octo/infra"), ""),
+            format!("{}/octo/infra.git", server.uri())
+        );
+    }
+
+    fn event(event: &str, fixture: &str) -> Option<ForgeEvent> {
+        let payload = serde_json::from_str(&mock::fixture(fixture)).unwrap();
+
+        github(GitHubAuth::Token(String::new()))
+            .parse_event(event, &payload)
+            .unwrap()
+    }
+
+    #[test]
+    fn push_is_priced_against_the_commit_before_it() {
+        let Some(ForgeEvent::Push {
+            repository,
+            before,
+            after,
+            commit,
+        }) = event("push", "github/push.json")
+        else {
+            panic!("push was not parsed as a push");
+        };
+
+        assert_eq!(repository.forge, Forge::GitHub);
+        assert_eq!(repository.id, "186853002");
+        assert_eq!(repository.full_name, "octo-org/infra");
+        assert_eq!(repository.size, Some(412));
+        assert_eq!(repository.installation_id, Some(2311213));
+        assert_eq!(before, "6113728f27ae82c7b1a177c8d03f9e96e0adf246");
+        assert_eq!(after, "0d1a26e67d8f5eaf1f6ba5c57fc3c7d91ac0fd1c");
+        assert_eq!(commit.branch.as_deref(), Some("main"));
+        assert_eq!(commit.author.as_deref(), Some("monalisa"));
+        assert_eq!(
+            commit.committed_at,
+            Some(Utc.with_ymd_and_hms(2024, 3, 12, 9, 15, 42).unwrap())
+        );
+    }
+
+    #[test]
+    fn push_author_without_an_account_is_their_name() {
+        let mut payload: Value = serde_json::from_str(&mock::fixture("github/push.json")).unwrap();
+        payload["head_commit"]["author"]
+            .as_object_mut()
+            .unwrap()
+            .remove("username");
+
+        let Ok(Some(ForgeEvent::Push { commit, .. })) =
+            github(GitHubAuth::Token(String::new())).parse_event("push", &payload)
+        else {
+            panic!("push was not parsed as a push");
+        };
+
+        assert_eq!(commit.author.as_deref(), Some("Mona Lisa"));
+    }
+
+    #[test]
+    fn deleted_branch_is_not_priced() {
+        assert!(event("push", "github/push_branch_deleted.json").is_none());
+    }
+
+    #[test]
+    fn opened_pull_request_is_priced_against_its_base() {
+        let Some(ForgeEvent::ChangeRequest {
+            repository,
+            number,
+            title,
+            base,
+            head,
+        }) = event("pull_request", "github/pull_request_opened.json")
+        else {
+            panic!("pull request was not parsed as a change request");
+        };
+
+        assert_eq!(repository.full_name, "octo-org/infra");
+        assert_eq!(repository.installation_id, Some(2311213));
+        assert_eq!(number, 42);
+        assert_eq!(title, "Move the database to a larger instance");
+        assert_eq!(base, "0d1a26e67d8f5eaf1f6ba5c57fc3c7d91ac0fd1c");
+        assert_eq!(head, "a3c1b8f0d6e2a9c47b5e1f2d8c9a0b3e4f5d6c7a");
+    }
+
+    #[test]
+    fn merged_pull_request_is_merged() {
+        let Some(ForgeEvent::Merged {
+            repository,
+            number,
+            merged_at,
+        }) = event("pull_request", "github/pull_request_merged.json")
+        else {
+            panic!("pull request was not parsed as merged");
+        };
+
+        assert_eq!(repository.full_name, "octo-org/infra");
+        assert_eq!(number, 42);
+        assert_eq!(
+            merged_at,
+            Some(Utc.with_ymd_and_hms(2024, 3, 13, 16, 40, 5).unwrap())
+        );
+    }
+
+    #[test]
+    fn closed_pull_request_is_ignored() {
+        let mut payload: Value =
+            serde_json::from_str(&mock::fixture("github/pull_request_merged.json")).unwrap();
+        payload["pull_request"]["merged"] = json!(false);
+
+        assert!(github(GitHubAuth::Token(String::new()))
+            .parse_event("pull_request", &payload)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn unsupported_event_is_an_error() {
+        assert!(matches!(
+            github(GitHubAuth::Token(String::new())).parse_event("issues", &json!({})),
+            Err(WebhookError::UnsupportedEvent(_))
+        ));
+    }
+
+    // The login of a token, told apart by the token so tests don't share it
+    async fn mount_login(token: &str, login: &str) -> wiremock::MockGuard {
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header(
+                "authorization",
+                format!("Bearer {}", token).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "login": login })))
+            .expect(1)
+            .mount_as_scoped(mock::server())
+            .await
+    }
+
+    #[actix_web::test]
+    async fn pull_request_comment_is_written_once() {
+        let server = mock::server();
+        let _login = mount_login("ghp_new_comment", "cost-bot").await;
+
+        // a copy of the marker by someone else is not the bot's comment
+        let _list = Mock::given(method("GET"))
+            .and(path("/repos/octo/new-comment/issues/1/comments"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 10, "user": { "login": "mallory" }, "body": format!("{}\nfree", COMMENT_MARKER) },
+            ])))
+            .mount_as_scoped(server)
+            .await;
+        let _last = Mock::given(method("GET"))
+            .and(path("/repos/octo/new-comment/issues/1/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/repos/octo/new-comment/issues/1/comments"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": 11 })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let github = github(GitHubAuth::Token(String::from("ghp_new_comment")));
+
+        let url = github
+            .comment_on_change_request(
+                &Client::new(),
+                &repository("octo/new-comment"),
+                1,
+                "a3c1b8f0d6e2",
+                Some("## Cost Breakdown"),
+                "ghp_new_comment",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            url,
+            Some(format!(
+                "{}/octo/new-comment/pull/1#issuecomment-11",
+                server.uri()
+            ))
+        );
+    }
+
+    #[actix_web::test]
+    async fn pull_request_comment_of_the_bot_is_updated() {
+        let server = mock::server();
+        let _login = mount_login("ghp_update_comment", "cost-bot").await;
+
+        let _list = Mock::given(method("GET"))
+            .and(path("/repos/octo/update-comment/issues/2/comments"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 20, "user": { "login": "octocat" }, "body": "Looks good" },
+                {
+                    "id": 21,
+                    "user": { "login": "cost-bot" },
+                    "body": format!("{}\n## Cost Breakdown\n\nold\n\n_Costs as of `0d1a26e`._", COMMENT_MARKER),
+                },
+            ])))
+            .mount_as_scoped(server)
+            .await;
+        let _update = Mock::given(method("PATCH"))
+            .and(path("/repos/octo/update-comment/issues/comments/21"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 21 })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/repos/octo/update-comment/issues/2/comments"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount_as_scoped(server)
+            .await;
+
+        let github = github(GitHubAuth::Token(String::from("ghp_update_comment")));
+
+        github
+            .comment_on_change_request(
+                &Client::new(),
+                &repository("octo/update-comment"),
+                2,
+                "a3c1b8f0d6e2",
+                Some("## Cost Breakdown\n\nnew"),
+                "ghp_update_comment",
+            )
+            .await
+            .unwrap();
+
+        // the earlier breakdown moves into the history of the comment
+        let requests = server.received_requests().await.unwrap();
+        let update = requests
+            .iter()
+            .find(|request| request.url.path() == "/repos/octo/update-comment/issues/comments/21")
+            .unwrap();
+        let body = update.body_json::<Value>().unwrap()["body"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        assert!(body.starts_with(&format!("{}\n## Cost Breakdown\n\nnew", COMMENT_MARKER)));
+        assert!(body.contains("Earlier pushes (1)"));
+        assert!(body.contains("old"));
+    }
+
+    #[actix_web::test]
+    async fn token_sets_commit_statuses() {
+        let server = mock::server();
+        let sha = "5a7c2e9d1b3f4a6c8e0d2b4f6a8c0e2d4b6f8a0c";
+
+        let _pending = Mock::given(method("POST"))
+            .and(path(format!("/repos/octo/statuses/statuses/{}", sha)))
+            .and(body_partial_json(
+                json!({ "state": "pending", "context": CHECK_NAME }),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _failure = Mock::given(method("POST"))
+            .and(path(format!("/repos/octo/statuses/statuses/{}", sha)))
+            .and(body_partial_json(json!({
+                "state": "failure",
+                "context": CHECK_NAME,
+                "description": "api is above its limit",
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let github = github(GitHubAuth::Token(String::from("ghp_statuses")));
+        let client = Client::new();
+        let repository = repository("octo/statuses");
+
+        let check_id = github
+            .start_check(&client, &repository, sha, "ghp_statuses")
+            .await
+            .unwrap();
+        assert_eq!(check_id, None);
+
+        let outcome = CheckOutcome {
+            success: false,
+            summary: String::from("api is above its limit\nworker is above its limit"),
+            text: String::new(),
+            details_url: github.commit_url(&repository, sha),
+        };
+
+        github
+            .complete_check(
+                &client,
+                &repository,
+                sha,
+                check_id,
+                &outcome,
+                "ghp_statuses",
+            )
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn app_reuses_the_check_run_of_an_earlier_attempt() {
+        let server = mock::server();
+        let sha = "8e1f3a5c7b9d0e2f4a6c8b0d2e4f6a8c0b2d4e6f";
+
+        let _find = Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/octo/check-runs/commits/{}/check-runs",
+                sha
+            )))
+            .and(query_param("check_name", CHECK_NAME))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "total_count": 1, "check_runs": [{ "id": 77 }] })),
+            )
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/repos/octo/check-runs/check-runs"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount_as_scoped(server)
+            .await;
+        let _start = Mock::given(method("PATCH"))
+            .and(path("/repos/octo/check-runs/check-runs/77"))
+            .and(body_partial_json(json!({ "status": "in_progress" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _complete = Mock::given(method("PATCH"))
+            .and(path("/repos/octo/check-runs/check-runs/77"))
+            .and(body_partial_json(json!({
+                "status": "completed",
+                "conclusion": "success",
+                "output": { "title": CHECK_NAME, "summary": "$120.00 a month" },
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let github = github(GitHubAuth::App(Box::new(GitHubApp::new(
+            String::from("12345"),
+            &mock::fixture("github/app-key.pem"),
+        ))));
+        let client = Client::new();
+        let repository = repository("octo/check-runs");
+
+        let check_id = github
+            .start_check(&client, &repository, sha, "ghs_check_runs")
+            .await
+            .unwrap();
+        assert_eq!(check_id, Some(77));
+
+        let outcome = CheckOutcome {
+            success: true,
+            summary: String::from("$120.00 a month"),
+            text: String::new(),
+            details_url: github.commit_url(&repository, sha),
+        };
+
+        github
+            .complete_check(
+                &client,
+                &repository,
+                sha,
+                check_id,
+                &outcome,
+                "ghs_check_runs",
+            )
+            .await
+            .unwrap();
     }
 }
@@ -0,0 +1,963 @@
+use std::path::PathBuf;
+
+use actix_web::HttpRequest;
+use reqwest::{header::USER_AGENT, Client, Method, RequestBuilder};
+use serde_json::{json, Value};
+use types::discovery;
+
+use crate::{
+    forge::{
+        self, CheckOutcome, CommitDetails, Delivery, Forge, ForgeEvent, ForgeProvider,
+        ForgeRepository, CHECK_NAME, COMMENT_MARKER,
+    },
+    models::gitlab::{Discussion, MergeRequestHook, Note, Project, PushHook, TreeEntry},
+    source::Configurations,
+    webhook::WebhookError,
+};
+
+// Sha GitLab sends for the missing side of a created or deleted branch
+const NULL_SHA: &str = "0000000000000000000000000000000000000000";
+
+// Base url of GitLab, gitlab.com or a self-hosted instance
+pub fn web_url() -> String {
+    std::env::var("GITLAB_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or(String::from("https://gitlab.com"))
+}
+
+pub fn api_url() -> String {
+    format!("{}/api/v4", web_url())
+}
+
+fn request(client: &Client, method: Method, path: &str, token: &str) -> RequestBuilder {
+    client
+        .request(method, format!("{}{}", api_url(), path))
+        .header(USER_AGENT, "Infralink Cost Bot")
+        .bearer_auth(token)
+}
+
+// Statuses only have a short description, the first line of the summary
+fn description(summary: &str) -> String {
+    summary
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(255)
+        .collect()
+}
+
+fn forge_repository(project: &Project) -> ForgeRepository {
+    ForgeRepository {
+        forge: Forge::GitLab,
+        id: project.id.to_string(),
+        full_name: project.path_with_namespace.clone(),
+        size: None,
+        installation_id: None,
+    }
+}
+
+// Projects on gitlab.com or a self-hosted GitLab, through a project, group or personal
+// access token
+pub struct GitLab {
+    token: String,
+    // secret token GitLab sends with every webhook
+    secret: String,
+}
+
+impl GitLab {
+    // Set up when GITLAB_TOKEN is set
+    pub fn from_env() -> Option<Self> {
+        let token = std::env::var("GITLAB_TOKEN").ok()?;
+
+        Some(Self {
+            token,
+            secret: forge::required_env("GITLAB_WEBHOOK_SECRET"),
+        })
+    }
+
+    // Function to fetch the contents of a blob
+    async fn fetch_blob(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+    ) -> Result<String, String> {
+        let response = request(
+            client,
+            Method::GET,
+            &format!("/projects/{}/repository/blobs/{}/raw", repository.id, sha),
+            &self.token,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to fetch blob {}: {}",
+                sha,
+                response.status()
+            ));
+        }
+
+        response.text().await.map_err(|e| e.to_string())
+    }
+
+    // Function to set the status of the cost check on a commit
+    async fn set_status(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        body: Value,
+    ) -> Result<(), String> {
+        let response = request(
+            client,
+            Method::POST,
+            &format!("/projects/{}/statuses/{}", repository.id, sha),
+            &self.token,
+        )
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        let status = response.status();
+
+        // Check the response status
+        if status == 201 {
+            return Ok(());
+        }
+
+        // GitLab refuses to set the state a status already has, e.g. on a retried job
+        let message = response.text().await.unwrap_or_default();
+
+        if status == 400 && message.contains("Cannot transition status") {
+            return Ok(());
+        }
+
+        Err(format!(
+            "Failed to set commit status: {} {}",
+            status, message
+        ))
+    }
+
+    // Function to find the discussion the bot started on a commit
+    async fn find_commit_discussion(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<Discussion>, String> {
+        let mut page = 1;
+
+        loop {
+            let response = request(
+                client,
+                Method::GET,
+                &format!(
+                    "/projects/{}/repository/commits/{}/discussions?per_page=100&page={}",
+                    repository.id, sha, page
+                ),
+                token,
+            )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            // Check the response status
+            if response.status() != 200 {
+                return Err(format!(
+                    "Failed to list commit discussions: {}",
+                    response.status()
+                ));
+            }
+
+            let discussions: Vec<Discussion> = response.json().await.map_err(|e| e.to_string())?;
+
+            if discussions.is_empty() {
+                return Ok(None);
+            }
+
+            if let Some(discussion) = discussions.into_iter().find(|discussion| {
+                discussion
+                    .notes
+                    .first()
+                    .is_some_and(|note| note.body.starts_with(COMMENT_MARKER))
+            }) {
+                return Ok(Some(discussion));
+            }
+
+            page += 1;
+        }
+    }
+}
+
+impl ForgeProvider for GitLab {
+    const FORGE: Forge = Forge::GitLab;
+
+    fn verify(&self, req: &HttpRequest, _body: &[u8]) -> Result<Delivery, WebhookError> {
+        // GitLab sends the secret itself rather than signing the payload
+        forge::verify_token(&self.secret, forge::header(req, "X-Gitlab-Token")?)?;
+
+        Ok(Delivery {
+            event: forge::header(req, "X-Gitlab-Event")?.to_string(),
+            id: forge::header(req, "X-Gitlab-Event-UUID")?.to_string(),
+        })
+    }
+
+    fn parse_event(
+        &self,
+        event: &str,
+        payload: &Value,
+    ) -> Result<Option<ForgeEvent>, WebhookError> {
+        match event {
+            "Push Hook" => {
+                let event: PushHook = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                // nothing to price on a deleted branch
+                if event.after == NULL_SHA {
+                    return Ok(None);
+                }
+
+                // the time of the head commit, when it is among the commits sent
+                let committed_at = event
+                    .commits
+                    .iter()
+                    .find(|commit| commit.id == event.after)
+                    .map(|commit| commit.timestamp);
+
+                Ok(Some(ForgeEvent::Push {
+                    repository: forge_repository(&event.project),
+                    before: event.before,
+                    after: event.after,
+                    commit: CommitDetails {
+                        branch: event.r#ref.strip_prefix("refs/heads/").map(String::from),
+                        author: event.user_username,
+                        committed_at,
+                    },
+                }))
+            }
+            "Merge Request Hook" => {
+                let event: MergeRequestHook = serde_json::from_value(payload.clone())
+                    .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+                let merge_request = event.object_attributes;
+
+                // GitLab only sends when the merge request was last updated, in its own format
+                if merge_request.action.as_deref() == Some("merge") {
+                    return Ok(Some(ForgeEvent::Merged {
+                        repository: forge_repository(&event.project),
+                        number: merge_request.iid,
+                        merged_at: None,
+                    }));
+                }
+
+                // updates without an oldrev only changed the title, labels and the like
+                let pushed = match merge_request.action.as_deref() {
+                    Some("open") | Some("reopen") => true,
+                    Some("update") => merge_request.oldrev.is_some(),
+                    _ => false,
+                };
+
+                if !pushed {
+                    return Ok(None);
+                }
+
+                Ok(Some(ForgeEvent::ChangeRequest {
+                    repository: forge_repository(&event.project),
+                    number: merge_request.iid,
+                    title: merge_request.title,
+                    base: merge_request.target_branch,
+                    head: merge_request.last_commit.id,
+                }))
+            }
+            // GitLab disables webhooks that keep failing, so other events are acknowledged
+            _ => Ok(None),
+        }
+    }
+
+    async fn token(
+        &self,
+        _client: &Client,
+        _repository: &ForgeRepository,
+    ) -> Result<String, String> {
+        Ok(self.token.clone())
+    }
+
+    async fn resolve_commit(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        reference: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let response = request(
+            client,
+            Method::GET,
+            &format!(
+                "/projects/{}/repository/commits/{}",
+                repository.id,
+                forge::encode(reference)
+            ),
+            token,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != 200 {
+            return Err(format!(
+                "Failed to resolve {}: {}",
+                reference,
+                response.status()
+            ));
+        }
+
+        let json: Value = response.json().await.map_err(|e| e.to_string())?;
+
+        json["id"]
+            .as_str()
+            .map(String::from)
+            .ok_or(String::from("Failed to get commit sha"))
+    }
+
+    fn clone_url(&self, repository: &ForgeRepository, token: &str) -> String {
+        let web_url = web_url();
+
+        match web_url.split_once("://") {
+            Some((scheme, host)) if !token.is_empty() => format!(
+                "{}://oauth2:{}@{}/{}.git",
+                scheme, token, host, repository.full_name
+            ),
+            _ => format!("{}/{}.git", web_url, repository.full_name),
+        }
+    }
+
+    async fn fetch_configuration_files(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<Configurations>, String> {
+        let mut blobs: Vec<TreeEntry> = Vec::new();
+        let mut page = String::from("1");
+
+        // the tree is listed a page at a time, until GitLab sends no next page
+        loop {
+            let response = request(
+                client,
+                Method::GET,
+                &format!(
+                    "/projects/{}/repository/tree?ref={}&recursive=true&per_page=100&page={}",
+                    repository.id, sha, page
+                ),
+                token,
+            )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            // Check the response status
+            if response.status() != 200 {
+                return Err(format!(
+                    "Failed to list repository tree: {}",
+                    response.status()
+                ));
+            }
+
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+
+            let entries: Vec<TreeEntry> = response.json().await.map_err(|e| e.to_string())?;
+
+            blobs.extend(entries.into_iter().filter(|entry| entry.r#type == "blob"));
+
+            if next_page.is_empty() {
+                break;
+            }
+
+            page = next_page;
+        }
+
+        let settings = match blobs
+            .iter()
+            .find(|entry| entry.path == discovery::SETTINGS_FILE)
+        {
+            Some(entry) => Some(self.fetch_blob(client, repository, &entry.id).await?),
+            None => None,
+        };
+
+        let mut configurations = Configurations::new(settings.as_deref());
+
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+
+        for entry in blobs {
+            let path = PathBuf::from(&entry.path);
+
+            if configurations.is_configuration_file(&path) {
+                files.push((path, self.fetch_blob(client, repository, &entry.id).await?));
+            }
+        }
+
+        configurations.set_files(files);
+
+        Ok(Some(configurations))
+    }
+
+    async fn start_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        _token: &str,
+    ) -> Result<Option<u64>, String> {
+        self.set_status(
+            client,
+            repository,
+            sha,
+            json!({ "state": "running", "name": CHECK_NAME }),
+        )
+        .await?;
+
+        // statuses are found by commit and name
+        Ok(None)
+    }
+
+    async fn complete_check(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        _check_id: Option<u64>,
+        outcome: &CheckOutcome,
+        _token: &str,
+    ) -> Result<(), String> {
+        self.set_status(
+            client,
+            repository,
+            sha,
+            json!({
+                "state": if outcome.success { "success" } else { "failed" },
+                "name": CHECK_NAME,
+                "description": description(&outcome.summary),
+                "target_url": outcome.details_url,
+            }),
+        )
+        .await
+    }
+
+    async fn find_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Option<u64>, String> {
+        Ok(self
+            .find_commit_discussion(client, repository, sha, token)
+            .await?
+            .and_then(|discussion| discussion.notes.first().map(|note| note.id)))
+    }
+
+    async fn write_commit_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let discussions = format!(
+            "/projects/{}/repository/commits/{}/discussions",
+            repository.id, sha
+        );
+
+        // notes of a commit are updated through the discussion they started
+        let discussion = match comment_id {
+            Some(_) => {
+                self.find_commit_discussion(client, repository, sha, token)
+                    .await?
+            }
+            None => None,
+        };
+
+        let (request, expected_status) = match (comment_id, discussion) {
+            (Some(id), Some(discussion)) => (
+                request(
+                    client,
+                    Method::PUT,
+                    &format!("{}/{}/notes/{}", discussions, discussion.id, id),
+                    token,
+                ),
+                200,
+            ),
+            _ => (request(client, Method::POST, &discussions, token), 201),
+        };
+
+        let response = request
+            .json(&json!({ "body": body }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != expected_status {
+            return Err(format!(
+                "Failed to write comment to commit: {}",
+                response.status()
+            ));
+        }
+
+        // a new discussion is returned with its note, an updated note on its own
+        let json: Value = response.json().await.map_err(|e| e.to_string())?;
+
+        let id = json["notes"][0]["id"]
+            .as_u64()
+            .or(json["id"].as_u64())
+            .ok_or(String::from("Failed to get comment ID"))?;
+
+        Ok(format!("{}#note_{}", self.commit_url(repository, sha), id))
+    }
+
+    fn commit_url(&self, repository: &ForgeRepository, sha: &str) -> String {
+        format!("{}/{}/-/commit/{}", web_url(), repository.full_name, sha)
+    }
+
+    fn change_request_url(&self, repository: &ForgeRepository, number: u64) -> String {
+        format!(
+            "{}/{}/-/merge_requests/{}",
+            web_url(),
+            repository.full_name,
+            number
+        )
+    }
+
+    async fn find_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        token: &str,
+    ) -> Result<Option<(u64, String)>, String> {
+        let mut page = 1;
+
+        loop {
+            let response = request(
+                client,
+                Method::GET,
+                &format!(
+                    "/projects/{}/merge_requests/{}/notes?per_page=100&page={}",
+                    repository.id, number, page
+                ),
+                token,
+            )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            // Check the response status
+            if response.status() != 200 {
+                return Err(format!(
+                    "Failed to list merge request notes: {}",
+                    response.status()
+                ));
+            }
+
+            let notes: Vec<Note> = response.json().await.map_err(|e| e.to_string())?;
+
+            if notes.is_empty() {
+                return Ok(None);
+            }
+
+            if let Some(note) = notes
+                .into_iter()
+                .find(|note| note.body.starts_with(COMMENT_MARKER))
+            {
+                return Ok(Some((note.id, note.body)));
+            }
+
+            page += 1;
+        }
+    }
+
+    async fn write_comment(
+        &self,
+        client: &Client,
+        repository: &ForgeRepository,
+        number: u64,
+        comment_id: Option<u64>,
+        body: &str,
+        token: &str,
+    ) -> Result<String, String> {
+        let notes = format!(
+            "/projects/{}/merge_requests/{}/notes",
+            repository.id, number
+        );
+
+        let (request, expected_status) = match comment_id {
+            Some(id) => (
+                request(client, Method::PUT, &format!("{}/{}", notes, id), token),
+                200,
+            ),
+            None => (request(client, Method::POST, &notes, token), 201),
+        };
+
+        let response = request
+            .json(&json!({ "body": body }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        // Check the response status
+        if response.status() != expected_status {
+            return Err(format!(
+                "Failed to write merge request note: {}",
+                response.status()
+            ));
+        }
+
+        let note: Note = response.json().await.map_err(|e| e.to_string())?;
+
+        Ok(format!(
+            "{}#note_{}",
+            self.change_request_url(repository, number),
+            note.id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use wiremock::{
+        matchers::{body_partial_json, header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::mock;
+
+    fn gitlab() -> GitLab {
+        GitLab {
+            token: String::from("glpat_token"),
+            secret: String::from("secret"),
+        }
+    }
+
+    // Projects are addressed by id, so each test mocks a project of its own
+    fn repository(id: u64) -> ForgeRepository {
+        ForgeRepository {
+            forge: Forge::GitLab,
+            id: id.to_string(),
+            full_name: format!("platform/project-{}", id),
+            size: None,
+            installation_id: None,
+        }
+    }
+
+    fn event(event: &str, fixture: &str) -> Option<ForgeEvent> {
+        let payload = serde_json::from_str(&mock::fixture(fixture)).unwrap();
+
+        gitlab().parse_event(event, &payload).unwrap()
+    }
+
+    #[test]
+    fn push_is_priced_against_the_commit_before_it() {
+        let Some(ForgeEvent::Push {
+            repository,
+            before,
+            after,
+            commit,
+        }) = event("Push Hook", "gitlab/push.json")
+        else {
+            panic!("push was not parsed as a push");
+        };
+
+        assert_eq!(repository.forge, Forge::GitLab);
+        assert_eq!(repository.id, "15");
+        assert_eq!(repository.full_name, "platform/services/infra");
+        assert_eq!(before, "95790bf891e76fee5e1747ab589903a6a1f80f22");
+        assert_eq!(after, "da1560886d4f094c3e6c9ef40349f7d38b5d27d7");
+        assert_eq!(commit.branch.as_deref(), Some("main"));
+        assert_eq!(commit.author.as_deref(), Some("jsmith"));
+        // the time of the head commit, not of the first one pushed
+        assert_eq!(
+            commit.committed_at,
+            Some(Utc.with_ymd_and_hms(2024, 3, 11, 15, 1, 47).unwrap())
+        );
+    }
+
+    #[test]
+    fn deleted_branch_is_not_priced() {
+        assert!(event("Push Hook", "gitlab/push_branch_deleted.json").is_none());
+    }
+
+    #[test]
+    fn opened_merge_request_is_priced_against_its_target_branch() {
+        let Some(ForgeEvent::ChangeRequest {
+            repository,
+            number,
+            title,
+            base,
+            head,
+        }) = event("Merge Request Hook", "gitlab/merge_request_open.json")
+        else {
+            panic!("merge request was not parsed as a change request");
+        };
+
+        assert_eq!(repository.id, "15");
+        assert_eq!(number, 7);
+        assert_eq!(title, "Use spot instances for the workers");
+        assert_eq!(base, "main");
+        assert_eq!(head, "b6568db1bc1dcd7f8b4d5a946b0b91f9dacd7327");
+    }
+
+    #[test]
+    fn merged_merge_request_is_merged() {
+        let Some(ForgeEvent::Merged {
+            repository,
+            number,
+            merged_at,
+        }) = event("Merge Request Hook", "gitlab/merge_request_merge.json")
+        else {
+            panic!("merge request was not parsed as merged");
+        };
+
+        assert_eq!(repository.id, "15");
+        assert_eq!(number, 7);
+        assert_eq!(merged_at, None);
+    }
+
+    #[test]
+    fn title_update_is_not_priced() {
+        assert!(event(
+            "Merge Request Hook",
+            "gitlab/merge_request_update_title.json"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn other_events_are_acknowledged() {
+        assert!(gitlab()
+            .parse_event("Note Hook", &json!({}))
+            .unwrap()
+            .is_none());
+    }
+
+    #[actix_web::test]
+    async fn merge_request_note_is_written_once() {
+        let server = mock::server();
+
+        let _list = Mock::given(method("GET"))
+            .and(path("/api/v4/projects/901/merge_requests/3/notes"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 1, "body": "Please add a budget" },
+            ])))
+            .mount_as_scoped(server)
+            .await;
+        let _last = Mock::given(method("GET"))
+            .and(path("/api/v4/projects/901/merge_requests/3/notes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/api/v4/projects/901/merge_requests/3/notes"))
+            .and(header("authorization", "Bearer glpat_token"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": 2, "body": "" })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let url = gitlab()
+            .comment_on_change_request(
+                &Client::new(),
+                &repository(901),
+                3,
+                "b6568db1bc1d",
+                Some("## Cost Breakdown"),
+                "glpat_token",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            url,
+            Some(format!(
+                "{}/platform/project-901/-/merge_requests/3#note_2",
+                server.uri()
+            ))
+        );
+    }
+
+    #[actix_web::test]
+    async fn merge_request_note_of_the_bot_is_updated() {
+        let server = mock::server();
+
+        let _list = Mock::given(method("GET"))
+            .and(path("/api/v4/projects/902/merge_requests/4/notes"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 5, "body": format!("{}\n## Cost Breakdown\n\nold", COMMENT_MARKER) },
+            ])))
+            .mount_as_scoped(server)
+            .await;
+        let _update = Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/902/merge_requests/4/notes/5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 5, "body": "" })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/api/v4/projects/902/merge_requests/4/notes"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount_as_scoped(server)
+            .await;
+
+        gitlab()
+            .comment_on_change_request(
+                &Client::new(),
+                &repository(902),
+                4,
+                "b6568db1bc1d",
+                Some("## Cost Breakdown\n\nnew"),
+                "glpat_token",
+            )
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn resolved_merge_request_without_a_note_is_left_alone() {
+        let server = mock::server();
+
+        let _list = Mock::given(method("GET"))
+            .and(path("/api/v4/projects/903/merge_requests/5/notes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount_as_scoped(server)
+            .await;
+        let _create = Mock::given(method("POST"))
+            .and(path("/api/v4/projects/903/merge_requests/5/notes"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount_as_scoped(server)
+            .await;
+
+        let url = gitlab()
+            .comment_on_change_request(
+                &Client::new(),
+                &repository(903),
+                5,
+                "b6568db1bc1d",
+                None,
+                "glpat_token",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(url, None);
+    }
+
+    #[actix_web::test]
+    async fn statuses_are_set_on_the_commit() {
+        let server = mock::server();
+        let sha = "da1560886d4f094c3e6c9ef40349f7d38b5d27d7";
+
+        let _running = Mock::given(method("POST"))
+            .and(path(format!("/api/v4/projects/904/statuses/{}", sha)))
+            .and(body_partial_json(
+                json!({ "state": "running", "name": CHECK_NAME }),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+        let _success = Mock::given(method("POST"))
+            .and(path(format!("/api/v4/projects/904/statuses/{}", sha)))
+            .and(body_partial_json(json!({
+                "state": "success",
+                "name": CHECK_NAME,
+                "description": "$120.00 a month",
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        let gitlab = gitlab();
+        let client = Client::new();
+        let repository = repository(904);
+
+        let check_id = gitlab
+            .start_check(&client, &repository, sha, "glpat_token")
+            .await
+            .unwrap();
+
+        let outcome = CheckOutcome {
+            success: true,
+            summary: String::from("$120.00 a month\nwithin every budget"),
+            text: String::new(),
+            details_url: gitlab.commit_url(&repository, sha),
+        };
+
+        gitlab
+            .complete_check(&client, &repository, sha, check_id, &outcome, "glpat_token")
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn status_already_set_is_not_an_error() {
+        let server = mock::server();
+        let sha = "b6568db1bc1dcd7f8b4d5a946b0b91f9dacd7327";
+
+        let _running = Mock::given(method("POST"))
+            .and(path(format!("/api/v4/projects/905/statuses/{}", sha)))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "message": "Cannot transition status via :run from :running (Reason(s): Status cannot transition via \"run\")",
+            })))
+            .expect(1)
+            .mount_as_scoped(server)
+            .await;
+
+        gitlab()
+            .start_check(&Client::new(), &repository(905), sha, "glpat_token")
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    async fn status_refused_for_another_reason_is_an_error() {
+        let server = mock::server();
+        let sha = "95790bf891e76fee5e1747ab589903a6a1f80f22";
+
+        let _running = Mock::given(method("POST"))
+            .and(path(format!("/api/v4/projects/906/statuses/{}", sha)))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_json(json!({ "message": "name is too long" })),
+            )
+            .mount_as_scoped(server)
+            .await;
+
+        let error = gitlab()
+            .start_check(&Client::new(), &repository(906), sha, "glpat_token")
+            .await
+            .unwrap_err();
+
+        assert!(error.starts_with("Failed to set commit status: 400 Bad Request"));
+    }
+}
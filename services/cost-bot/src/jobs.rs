@@ -0,0 +1,289 @@
+use std::{str::FromStr, time::Duration as StdDuration};
+
+use actix_web::{get, rt, web, HttpRequest, HttpResponse, ResponseError};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api, db,
+    forge::{Forge, ForgeEvent, ForgeProvider, Forges},
+    webhook,
+};
+
+// Attempts at a job before it is dead lettered
+const MAX_ATTEMPTS: i32 = 5;
+
+// Delay before the first retry, doubled after every failed attempt up to the max
+const BACKOFF_BASE_SECS: i64 = 30;
+const BACKOFF_MAX_SECS: i64 = 60 * 60;
+
+// How long to wait before looking at the queue again when it is empty
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(2);
+
+// A job running for longer than this belonged to a worker that stopped part way through
+const STALE_AFTER_SECS: i64 = 30 * 60;
+
+// A webhook delivery queued for a worker. status is one of queued, running, succeeded or dead
+#[derive(Serialize, Debug)]
+pub struct Job {
+    pub id: i64,
+    pub forge: String,
+    pub delivery_id: String,
+    pub event: String,
+    #[serde(skip)]
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Delay before running a job again after it failed for the nth time
+fn backoff(attempts: i32) -> Duration {
+    let secs = BACKOFF_BASE_SECS << (attempts - 1).clamp(0, 16);
+
+    Duration::seconds(secs.min(BACKOFF_MAX_SECS))
+}
+
+// Work through the queue until the server stops
+pub async fn worker(pool: Pool<Postgres>, client: web::Data<Client>, forges: web::Data<Forges>) {
+    loop {
+        let job = match db::claim_job(&pool).await {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                // nothing due, pick up any job a stopped worker left behind
+                match db::requeue_stale_jobs(&pool, STALE_AFTER_SECS).await {
+                    Ok(0) => {}
+                    Ok(count) => println!("Queued {} stale jobs again", count),
+                    Err(e) => eprintln!("Failed to queue stale jobs again: {}", e),
+                }
+
+                rt::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Failed to claim a job: {}", e);
+                rt::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        run(&pool, &client, &forges, job).await;
+    }
+}
+
+async fn run(
+    pool: &Pool<Postgres>,
+    client: &web::Data<Client>,
+    forges: &web::Data<Forges>,
+    job: Job,
+) {
+    println!(
+        "Running job {} for {} {} delivery {}, attempt {}",
+        job.id, job.forge, job.event, job.delivery_id, job.attempts
+    );
+
+    // processing runs as its own task, so a panic fails the job rather than the worker
+    let task = {
+        let pool = pool.clone();
+        let client = client.clone();
+        let forges = forges.clone();
+        let forge = job.forge.clone();
+        let event = job.event.clone();
+        let payload = job.payload.clone();
+
+        rt::spawn(async move {
+            match Forge::from_str(&forge) {
+                Ok(Forge::GitHub) => {
+                    dispatch(&pool, &client, forges.github.as_ref(), &event, &payload).await
+                }
+                Ok(Forge::GitLab) => {
+                    dispatch(&pool, &client, forges.gitlab.as_ref(), &event, &payload).await
+                }
+                Ok(Forge::Bitbucket) => {
+                    dispatch(&pool, &client, forges.bitbucket.as_ref(), &event, &payload).await
+                }
+                Err(()) => Err(format!("Unsupported forge: {}", forge)),
+            }
+        })
+    };
+
+    let error = match task.await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e),
+        Err(e) if e.is_panic() => {
+            let panic = e.into_panic();
+
+            Some(
+                panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "Job panicked".to_string()),
+            )
+        }
+        Err(e) => Some(e.to_string()),
+    };
+
+    let result = match error {
+        None => {
+            println!("Job {} succeeded", job.id);
+            db::complete_job(pool, job.id).await
+        }
+        Some(error) if job.attempts >= MAX_ATTEMPTS => {
+            eprintln!(
+                "Job {} failed {} times, giving up: {}",
+                job.id, job.attempts, error
+            );
+
+            // the check was left running by the last attempt
+            if let Err(e) = fail(client, forges, &job, &error).await {
+                eprintln!("Failed to fail the check of job {}: {}", job.id, e);
+            }
+
+            db::dead_letter_job(pool, job.id, &error).await
+        }
+        Some(error) => {
+            let run_at = Utc::now() + backoff(job.attempts);
+
+            eprintln!("Job {} failed, retrying at {}: {}", job.id, run_at, error);
+            db::retry_job(pool, job.id, &error, run_at).await
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to update job {}: {}", job.id, e);
+    }
+}
+
+// Process a delivery with the forge it came from, which may since have been unset
+async fn dispatch<F: ForgeProvider>(
+    pool: &Pool<Postgres>,
+    client: &Client,
+    forge: Option<&F>,
+    event: &str,
+    payload: &Value,
+) -> Result<(), String> {
+    let forge = forge.ok_or(format!("{} is not configured", F::FORGE))?;
+
+    match forge
+        .parse_event(event, payload)
+        .map_err(|e| e.to_string())?
+    {
+        Some(event) => webhook::process(pool, client, forge, event).await,
+        None => Ok(()),
+    }
+}
+
+// Complete the check of a dead lettered job as failed, with the error of its last attempt
+async fn fail(client: &Client, forges: &Forges, job: &Job, error: &str) -> Result<(), String> {
+    match Forge::from_str(&job.forge) {
+        Ok(Forge::GitHub) => fail_check(client, forges.github.as_ref(), job, error).await,
+        Ok(Forge::GitLab) => fail_check(client, forges.gitlab.as_ref(), job, error).await,
+        Ok(Forge::Bitbucket) => fail_check(client, forges.bitbucket.as_ref(), job, error).await,
+        Err(()) => Err(format!("Unsupported forge: {}", job.forge)),
+    }
+}
+
+async fn fail_check<F: ForgeProvider>(
+    client: &Client,
+    forge: Option<&F>,
+    job: &Job,
+    error: &str,
+) -> Result<(), String> {
+    let forge = forge.ok_or(format!("{} is not configured", F::FORGE))?;
+
+    let event = forge
+        .parse_event(&job.event, &job.payload)
+        .map_err(|e| e.to_string())?;
+
+    match event {
+        Some(ForgeEvent::Push {
+            repository, after, ..
+        }) => {
+            let token = forge.token(client, &repository).await?;
+
+            forge
+                .fail_check(client, &repository, &after, error, &token)
+                .await
+        }
+        Some(ForgeEvent::ChangeRequest {
+            repository, head, ..
+        }) => {
+            let token = forge.token(client, &repository).await?;
+            let head = forge
+                .resolve_commit(client, &repository, &head, &token)
+                .await?;
+
+            forge
+                .fail_check(client, &repository, &head, error, &token)
+                .await
+        }
+        // merges have no check
+        Some(ForgeEvent::Merged { .. }) | None => Ok(()),
+    }
+}
+
+// Status of a queued delivery, by the job id the webhook was acknowledged with. Job ids are
+// sequential and payloads name private repositories, so requests need API_TOKEN like the
+// cost history
+#[get("/jobs/{id}")]
+pub async fn status(
+    pool: web::Data<Pool<Postgres>>,
+    req: HttpRequest,
+    id: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(e) = api::authorize(&req) {
+        return e.error_response();
+    }
+
+    let id = id.into_inner();
+
+    match db::fetch_job(&pool, id).await {
+        Ok(Some(job)) => HttpResponse::Ok().json(job),
+        Ok(None) => HttpResponse::NotFound().json(json!({
+            "error": "not_found",
+            "message": format!("No job with id {}", id),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": "database",
+            "message": format!("Failed to fetch job: {}", e),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn status_needs_the_api_token() {
+        // never connected, the request is refused before the job is looked up
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/cost_bot")
+            .unwrap();
+
+        let app =
+            test::init_service(App::new().app_data(web::Data::new(pool)).service(status)).await;
+
+        for authorization in [None, Some("Bearer wrong-token")] {
+            let mut request = test::TestRequest::get().uri("/jobs/1");
+
+            if let Some(authorization) = authorization {
+                request = request.insert_header(("Authorization", authorization));
+            }
+
+            let response = test::call_service(&app, request.to_request()).await;
+
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -1,9 +1,17 @@
 pub mod api;
+pub mod auth;
+pub mod bitbucket;
 pub mod cost;
 pub mod db;
+pub mod forge;
 mod git;
 pub mod github;
+pub mod gitlab;
+pub mod jobs;
+#[cfg(test)]
+mod mock;
 pub mod models;
+pub mod source;
 pub mod webhook;
 
 use actix_web::{web::Data, App, HttpServer};
@@ -15,13 +23,44 @@ async fn main() -> std::io::Result<()> {
     // Connect to the database
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = db::create_pool(&database_url).await;
+
+    // bring the tables up to date before any delivery is taken
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run migrations");
     let client = reqwest::Client::builder().use_rustls_tls().build().unwrap();
 
+    // shared by every worker so installation tokens are cached once
+    let forges = Data::new(forge::Forges::from_env());
+
+    // deliveries are acknowledged by the listener and processed here, off the request
+    actix_web::rt::spawn(jobs::worker(
+        pool.clone(),
+        Data::new(client.clone()),
+        forges.clone(),
+    ));
+
+    // the cost history and job statuses are only served to requests with the token
+    let serve_api = std::env::var("API_TOKEN").is_ok();
+
+    if !serve_api {
+        println!("API_TOKEN is not set, the cost history and job status APIs are off");
+    }
+
     HttpServer::new(move || {
         App::new()
             .app_data(Data::new(pool.clone()))
-            .app_data(Data::new(client.clone()))
-            .service(webhook::listener)
+            .app_data(forges.clone())
+            .service(webhook::github_listener)
+            .service(webhook::gitlab_listener)
+            .service(webhook::bitbucket_listener)
+            .configure(|cfg| {
+                if serve_api {
+                    api::configure(cfg);
+                    cfg.service(jobs::status);
+                }
+            })
     })
     .bind(("127.0.0.1", 8080))
     .unwrap()
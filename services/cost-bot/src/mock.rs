@@ -0,0 +1,36 @@
+use std::sync::OnceLock;
+
+use actix_web::rt::System;
+use wiremock::MockServer;
+
+// Mock of the forge APIs the tests talk to. Their urls are read from the environment, which
+// every test shares, so one server stands in for all of them and each test mocks paths of
+// its own
+pub fn server() -> &'static MockServer {
+    static SERVER: OnceLock<MockServer> = OnceLock::new();
+
+    SERVER.get_or_init(|| {
+        // the server runs on a thread of its own, so outlives the runtime it was started on
+        let server = std::thread::spawn(|| System::new().block_on(MockServer::start()))
+            .join()
+            .unwrap();
+
+        for name in [
+            "GITHUB_API_URL",
+            "GITHUB_URL",
+            "GITLAB_URL",
+            "BITBUCKET_API_URL",
+            "BITBUCKET_URL",
+        ] {
+            std::env::set_var(name, server.uri());
+        }
+
+        server
+    })
+}
+
+// Contents of a file in the fixtures directory
+pub fn fixture(path: &str) -> String {
+    std::fs::read_to_string(format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), path))
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path, e))
+}
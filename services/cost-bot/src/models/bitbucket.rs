@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PushEvent {
+    // branches and tags pushed to
+    pub push: PushChanges,
+
+    // repository pushed to
+    pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PushChanges {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Change {
+    // branch before the push, None when it was created
+    pub old: Option<Branch>,
+
+    // branch after the push, None when it was deleted
+    pub new: Option<Branch>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Branch {
+    // branch or tag
+    pub r#type: String,
+
+    // name of the branch or tag
+    pub name: String,
+
+    // head of the branch
+    pub target: Commit,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequestEvent {
+    pub pullrequest: PullRequest,
+
+    // repository of the pull request
+    pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    // pull request id
+    pub id: u64,
+
+    pub title: String,
+
+    // branch the pull request is from
+    pub source: Endpoint,
+
+    // branch the pull request is into
+    pub destination: Endpoint,
+
+    // when the pull request was last updated, when it was merged for a fulfilled one
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Endpoint {
+    pub commit: Commit,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Commit {
+    // sha of the commit, shortened in pull request events
+    pub hash: String,
+
+    // when the commit was made, only sent in push events
+    pub date: Option<DateTime<Utc>>,
+
+    // only sent in push events
+    pub author: Option<CommitAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitAuthor {
+    // name and email as written in the commit
+    pub raw: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+    // repository uuid, in braces
+    pub uuid: String,
+
+    // workspace/repository
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Page<T> {
+    pub values: Vec<T>,
+
+    // link to the next page, if any
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Comment {
+    // comment id
+    pub id: u64,
+
+    pub content: Content,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Content {
+    // markdown of the comment
+    pub raw: String,
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PushHook {
+    // ref pushed to, e.g. refs/heads/main
+    pub r#ref: String,
+
+    // username of whoever pushed
+    pub user_username: Option<String>,
+
+    // commit before the push, all zeros for a new branch
+    pub before: String,
+
+    // commit after the push, all zeros when the branch was deleted
+    pub after: String,
+
+    // project pushed to
+    pub project: Project,
+
+    // commits pushed, up to 20
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PushCommit {
+    // sha of the commit
+    pub id: String,
+
+    // when the commit was made
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRequestHook {
+    // project of the merge request
+    pub project: Project,
+
+    // the merge request itself
+    pub object_attributes: MergeRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    // project id
+    pub id: i64,
+
+    // path of the project with its namespace, e.g. group/subgroup/project
+    pub path_with_namespace: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRequest {
+    // number of the merge request within its project
+    pub iid: u64,
+
+    pub title: String,
+
+    // open, reopen, update, close, merge...
+    pub action: Option<String>,
+
+    // branch the merge request is into
+    pub target_branch: String,
+
+    // head of the merge request
+    pub last_commit: LastCommit,
+
+    // previous head, only set when an update pushed new commits
+    pub oldrev: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LastCommit {
+    // sha of the commit
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TreeEntry {
+    // sha of the object
+    pub id: String,
+
+    // blob, tree or commit
+    pub r#type: String,
+
+    // path from the root of the repository
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Note {
+    // note id
+    pub id: u64,
+
+    // markdown of the note
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Discussion {
+    // discussion id, a sha rather than a number
+    pub id: String,
+
+    // the note that started the discussion, then its replies
+    pub notes: Vec<Note>,
+}
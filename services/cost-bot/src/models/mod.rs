@@ -1,5 +1,8 @@
+pub mod bitbucket;
 pub mod events;
+pub mod gitlab;
 pub mod graphql;
 pub mod pull_request;
 pub mod push;
 pub mod search;
+pub mod tree;
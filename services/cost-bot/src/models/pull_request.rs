@@ -1,6 +1,7 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-use super::push::Repository;
+use super::push::{Installation, Repository};
 
 #[derive(Deserialize, Debug)]
 pub struct PullRequest {
@@ -8,14 +9,17 @@ pub struct PullRequest {
     pub number: u64,
     pub pull_request: PullRequestData,
     pub repository: Repository,
+    pub installation: Option<Installation>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct PullRequestData {
+    pub title: String,
     pub head: Head,
     pub base: Base,
     pub merged: bool,
     pub merged_commit_sha: Option<String>,
+    pub merged_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -1,18 +1,25 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct Push {
+    // ref pushed to, e.g. refs/heads/main
+    pub r#ref: String,
+
     // ref after the push
     pub after: String,
 
     // ref before the push
     pub before: String,
 
-    // primary commit
-    pub head_commit: Commit,
+    // primary commit, null when the push deleted the branch
+    pub head_commit: Option<Commit>,
 
     // repository
     pub repository: Repository,
+
+    // installation of the github app the webhook was delivered to, if any
+    pub installation: Option<Installation>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +39,9 @@ pub struct Commit {
     // commit message
     pub message: String,
 
+    // when the commit was made
+    pub timestamp: DateTime<Utc>,
+
     // author
     pub author: Author,
 }
@@ -41,8 +51,8 @@ pub struct Author {
     // name of the author
     pub name: String,
 
-    // username of the author
-    pub username: String,
+    // username of the author, missing when the email is not linked to a GitHub account
+    pub username: Option<String>,
 
     // email of the author
     pub email: String,
@@ -55,4 +65,13 @@ pub struct Repository {
 
     // full name of the repository
     pub full_name: String,
+
+    // size of the repository in kilobytes
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Installation {
+    // installation id
+    pub id: u64,
 }
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Tree {
+    // every entry of the tree, when listed recursively
+    pub tree: Vec<TreeEntry>,
+
+    // whether GitHub left entries out because the tree is too large
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TreeEntry {
+    // path from the root of the repository
+    pub path: String,
+
+    // blob, tree or commit
+    pub r#type: String,
+
+    // sha of the object
+    pub sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Blob {
+    // contents of the blob, in the encoding below
+    pub content: String,
+
+    // utf-8 or base64
+    pub encoding: String,
+}
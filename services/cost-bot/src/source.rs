@@ -0,0 +1,117 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use actix_web::rt;
+use indexmap::IndexMap;
+use reqwest::Client;
+use types::discovery::{self, Discovery};
+
+use crate::{
+    forge::{ForgeProvider, ForgeRepository},
+    git,
+};
+
+// Largest repository, in kilobytes, that is cloned rather than read through the API
+const DEFAULT_CLONE_MAX_SIZE_KB: u64 = 10_000;
+
+// Configuration files of a repository at a commit
+pub struct Configurations {
+    // contents of each file by path, shallowest first
+    pub files: IndexMap<PathBuf, String>,
+    // the .infralink.yml the files were found with, to tell which environment each belongs to
+    pub discovery: Discovery,
+    // files that could not be read, with why, by path
+    pub errors: IndexMap<PathBuf, String>,
+}
+
+impl Configurations {
+    // Find files with the .infralink.yml of the commit, the defaults when it has none or it
+    // is invalid
+    pub fn new(settings: Option<&str>) -> Self {
+        let mut errors = IndexMap::new();
+
+        let discovery = match settings.map(Discovery::from_str) {
+            Some(Ok(discovery)) => discovery,
+            Some(Err(e)) => {
+                errors.insert(PathBuf::from(discovery::SETTINGS_FILE), e);
+                Discovery::default()
+            }
+            None => Discovery::default(),
+        };
+
+        Self {
+            files: IndexMap::new(),
+            discovery,
+            errors,
+        }
+    }
+
+    pub fn is_configuration_file(&self, path: &Path) -> bool {
+        self.discovery.is_configuration_file(path)
+    }
+
+    // Keep the files found, shallowest first
+    pub fn set_files(&mut self, mut files: Vec<(PathBuf, String)>) {
+        discovery::sort_by_depth(&mut files);
+
+        self.files = files.into_iter().collect();
+    }
+}
+
+// Where the configuration files of a repository are read from
+pub enum ConfigSource {
+    // the trees API, downloading only the configuration files
+    Api,
+    // a shallow fetch of the commit into a temporary directory
+    Clone,
+}
+
+impl ConfigSource {
+    // Small repositories are cloned, which costs no API requests, while large ones are read
+    // through the API rather than downloaded in full. The limit is read from
+    // CONFIG_CLONE_MAX_SIZE_KB
+    pub fn for_repository(size: Option<u64>) -> Self {
+        let max_size = std::env::var("CONFIG_CLONE_MAX_SIZE_KB")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_CLONE_MAX_SIZE_KB);
+
+        match size {
+            Some(size) if size <= max_size => ConfigSource::Clone,
+            _ => ConfigSource::Api,
+        }
+    }
+
+    // The configuration files in the repository at a commit
+    pub async fn configuration_files<F: ForgeProvider>(
+        &self,
+        forge: &F,
+        client: &Client,
+        repository: &ForgeRepository,
+        sha: &str,
+        token: &str,
+    ) -> Result<Configurations, String> {
+        let api_files = match self {
+            ConfigSource::Api => {
+                forge
+                    .fetch_configuration_files(client, repository, sha, token)
+                    .await?
+            }
+            ConfigSource::Clone => None,
+        };
+
+        if let Some(configurations) = api_files {
+            return Ok(configurations);
+        }
+
+        // the forge can't list every file, so clone it after all
+        let url = forge.clone_url(repository, token);
+        let sha = sha.to_string();
+
+        rt::task::spawn_blocking(move || git::clone_configuration_files(&url, &sha))
+            .await
+            .map_err(|e| format!("Failed to clone the repository: {}", e))?
+    }
+}
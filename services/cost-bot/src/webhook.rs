@@ -1,176 +1,427 @@
-use std::str::FromStr;
-
 use crate::{
-    cost, db, git, github,
-    models::{events::GitHubEvent, pull_request::PullRequest, push::Push},
+    cost::{self, CostReport, Severity, Violation},
+    db,
+    forge::{CheckOutcome, Forge, ForgeEvent, ForgeProvider, ForgeRepository, Forges},
+    git,
+    source::ConfigSource,
 };
-use actix_web::{post, web, HttpRequest, Responder};
+use actix_web::{http::StatusCode, post, web, HttpRequest, HttpResponse, ResponseError};
 use indexmap::IndexMap;
-use serde_json::Value;
+use math::report;
+use reqwest::Client;
+use serde_json::{json, Value};
 use sqlx::{Pool, Postgres};
-use types::config::InfrastructureConfiguration;
+use types::{
+    budget::Budget,
+    currency::{Currency, ExchangeRate},
+};
+
+// Why a webhook delivery was rejected
+#[derive(Debug)]
+pub enum WebhookError {
+    MissingHeader(&'static str),
+    InvalidSignature,
+    UnsupportedEvent(String),
+    MalformedPayload(String),
+    ForgeNotConfigured(Forge),
+    Database(String),
+}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookError::MissingHeader(header) => write!(f, "Missing {} header", header),
+            WebhookError::InvalidSignature => write!(f, "Signature does not match the payload"),
+            WebhookError::UnsupportedEvent(event) => write!(f, "Unsupported event: {}", event),
+            WebhookError::MalformedPayload(e) => write!(f, "Malformed payload: {}", e),
+            WebhookError::ForgeNotConfigured(forge) => {
+                write!(f, "The bot is not set up for {}", forge)
+            }
+            WebhookError::Database(e) => write!(f, "Failed to queue delivery: {}", e),
+        }
+    }
+}
+
+impl ResponseError for WebhookError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            WebhookError::InvalidSignature => StatusCode::UNAUTHORIZED,
+            WebhookError::MissingHeader(_)
+            | WebhookError::UnsupportedEvent(_)
+            | WebhookError::MalformedPayload(_) => StatusCode::BAD_REQUEST,
+            WebhookError::ForgeNotConfigured(_) => StatusCode::NOT_FOUND,
+            WebhookError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let code = match self {
+            WebhookError::MissingHeader(_) => "missing_header",
+            WebhookError::InvalidSignature => "invalid_signature",
+            WebhookError::UnsupportedEvent(_) => "unsupported_event",
+            WebhookError::MalformedPayload(_) => "malformed_payload",
+            WebhookError::ForgeNotConfigured(_) => "forge_not_configured",
+            WebhookError::Database(_) => "database",
+        };
+
+        HttpResponse::build(self.status_code()).json(json!({
+            "error": code,
+            "message": self.to_string(),
+        }))
+    }
+}
+
+// Acknowledge a delivery straight away and queue it for a worker, forges give up on a webhook
+// after 10 seconds
+async fn receive<F: ForgeProvider>(
+    pool: &Pool<Postgres>,
+    forge: Option<&F>,
+    req: &HttpRequest,
+    body: &[u8],
+) -> Result<HttpResponse, WebhookError> {
+    let forge = forge.ok_or(WebhookError::ForgeNotConfigured(F::FORGE))?;
+
+    // only trust deliveries the forge can be shown to have sent
+    let delivery = forge.verify(req, body)?;
+
+    let payload: Value =
+        serde_json::from_slice(body).map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+    // reject payloads now rather than have the job fail on them later
+    if forge.parse_event(&delivery.event, &payload)?.is_none() {
+        return Ok(HttpResponse::Ok().json(json!({ "status": "ignored" })));
+    }
 
+    let job_id = db::enqueue_job(pool, F::FORGE, &delivery.id, &delivery.event, &payload)
+        .await
+        .map_err(|e| WebhookError::Database(e.to_string()))?;
+
+    match job_id {
+        Some(job_id) => Ok(HttpResponse::Accepted().json(json!({
+            "status": "queued",
+            "job_id": job_id,
+        }))),
+        None => {
+            println!("Skipping delivery {}, it was already received", delivery.id);
+            Ok(HttpResponse::Ok().json(json!({ "status": "duplicate" })))
+        }
+    }
+}
+
+// GitHub deliveries, at the path the bot has always had
 #[post("/webhook")]
-pub async fn listener(
+pub async fn github_listener(
     pool: web::Data<Pool<Postgres>>,
-    client: web::Data<reqwest::Client>,
+    forges: web::Data<Forges>,
     req: HttpRequest,
-    body: web::Json<Value>,
-) -> impl Responder {
-    let builder = git2::build::RepoBuilder::new();
-
-    let event_type = GitHubEvent::from_str(
-        req.headers()
-            .get("X-GitHub-Event")
-            .unwrap()
-            .to_str()
-            .unwrap(),
-    )
-    .unwrap();
-
-    let payload = body.into_inner();
-
-    match event_type {
-        GitHubEvent::Push => {
-            let event: Push = serde_json::from_value(payload).unwrap();
-            let repo = &event.repository.full_name;
-
-            // clone the repository
-            git::clone(
-                repo.to_string(),
-                String::new(),
-                event.after.clone(),
-                builder,
-            );
+    body: web::Bytes,
+) -> Result<HttpResponse, WebhookError> {
+    receive(&pool, forges.github.as_ref(), &req, &body).await
+}
+
+#[post("/webhook/gitlab")]
+pub async fn gitlab_listener(
+    pool: web::Data<Pool<Postgres>>,
+    forges: web::Data<Forges>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, WebhookError> {
+    receive(&pool, forges.gitlab.as_ref(), &req, &body).await
+}
+
+#[post("/webhook/bitbucket")]
+pub async fn bitbucket_listener(
+    pool: web::Data<Pool<Postgres>>,
+    forges: web::Data<Forges>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, WebhookError> {
+    receive(&pool, forges.bitbucket.as_ref(), &req, &body).await
+}
 
-            let files = git::configuration_files(repo.to_string());
+// Work a queued delivery: price the configuration in the repository and report back on its
+// forge
+pub async fn process<F: ForgeProvider>(
+    pool: &Pool<Postgres>,
+    client: &Client,
+    forge: &F,
+    event: ForgeEvent,
+) -> Result<(), String> {
+    match event {
+        ForgeEvent::Push {
+            repository,
+            before,
+            after,
+            commit,
+        } => {
+            // token for the repository
+            let token = forge.token(client, &repository).await?;
 
-            // delete the repository once we've detected all the infra.toml files in it
-            git::delete(repo.to_string());
+            // show the check before reading the configuration so the commit shows it
+            // straight away
+            let check_id = forge
+                .start_check(client, &repository, &after, &token)
+                .await?;
+
+            // the infra.toml files at the pushed commit
+            let configurations = ConfigSource::for_repository(repository.size)
+                .configuration_files(forge, client, &repository, &after, &token)
+                .await?;
+
+            // discounts and tax the repository pays on top of list prices
+            let adjustments = db::fetch_repository_adjustments(pool, &repository)
+                .await
+                .map_err(|e| e.to_string())?
+                .unwrap_or_default();
 
             // get a breakdown for each file of the cost and prices
-            let breakdowns = git::cost_breakdowns(files).await;
+            let report = git::cost_breakdowns(configurations, &adjustments).await;
+
+            // the repository setting wins over the currency declared in infra.toml
+            let currency = db::fetch_repository_currency(pool, &repository)
+                .await
+                .map_err(|e| e.to_string())?
+                .or(report.currency)
+                .unwrap_or(Currency::Usd);
 
             // fetch the previous breakdown
-            let previous_breakdown =
-                db::fetch_previous_breakdown(&pool, event.repository.id, &event.before)
-                    .await
-                    .unwrap();
+            let previous_breakdown = db::fetch_previous_breakdown(pool, &repository, &before)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            let rate = cost::exchange_rate(currency);
 
             // Output the breakdown as a comment on the commit
-            let id = github::comment_on_commit(
-                client,
-                previous_breakdown,
-                &breakdowns,
-                &event.after,
-                &event.repository.full_name,
+            let comment_url = forge
+                .comment_on_commit(
+                    client,
+                    &repository,
+                    &after,
+                    &report::markdown(previous_breakdown.clone(), &report, &rate),
+                    &token,
+                )
+                .await?;
+
+            // pass or fail the check against the budgets
+            let outcome = cost_check(
+                pool,
+                &repository,
+                &report,
+                // the pushed commit is already on its branch, so its own budgets apply
+                IndexMap::new(),
+                previous_breakdown.as_ref(),
+                &rate,
+                comment_url,
             )
-            .await;
+            .await?;
 
-            // cost limits
-            let limits = db::fetch_repository_cost_limits(&pool, event.repository.id)
+            forge
+                .complete_check(client, &repository, &after, check_id, &outcome, &token)
+                .await?;
+
+            // Store the breakdown in the database, earlier ones are kept for the history
+            db::store_breakdown(pool, &repository, &after, &commit, report.breakdowns)
                 .await
-                .unwrap();
-
-            let mut success = true;
-
-            // Check this against our threshold
-            for (app_name, costs) in breakdowns.iter() {
-                if let Some(limit) = limits.get(app_name) {
-                    let total_cost = costs.values().sum::<f64>();
-
-                    if total_cost > *limit {
-                        success = false;
-
-                        github::mark_check_run(
-                            &event.repository.full_name,
-                            &event.after,
-                            "failure",
-                            "Cost Analysis",
-                            format!("Cost for {} above {} limit", app_name, limit,).as_str(),
-                            id,
-                        )
-                        .await
-                    }
-                }
-            }
+                .map_err(|e| e.to_string())?;
+        }
+        ForgeEvent::ChangeRequest {
+            repository,
+            number,
+            title,
+            base,
+            head,
+        } => {
+            // token for the repository
+            let token = forge.token(client, &repository).await?;
 
-            if success {
-                // Mark the check run for the commit as successful
-                // github::mark_check_run(
-                //     &event.repository.full_name,
-                //     &event.after,
-                //     "success",
-                //     "Cost Analysis",
-                //     "Cost breakdown",
-                //     id,
-                // )
-                // .await;
-            }
+            let head = forge
+                .resolve_commit(client, &repository, &head, &token)
+                .await?;
+            let base = forge
+                .resolve_commit(client, &repository, &base, &token)
+                .await?;
+
+            // show the check on the head of the change request
+            let check_id = forge
+                .start_check(client, &repository, &head, &token)
+                .await?;
+
+            // the infra.toml files at the head of the change request
+            let source = ConfigSource::for_repository(repository.size);
+            let configurations = source
+                .configuration_files(forge, client, &repository, &head, &token)
+                .await?;
+
+            // budgets are read from the base, so the change request can't loosen its own
+            let base_budgets = git::declared_budgets(
+                &source
+                    .configuration_files(forge, client, &repository, &base, &token)
+                    .await?,
+            );
 
-            // Store the breakdown in the database
-            db::store_breakdown(&pool, event.repository.id, &event.after, breakdowns)
+            // discounts and tax the repository pays on top of list prices
+            let adjustments = db::fetch_repository_adjustments(pool, &repository)
                 .await
-                .unwrap();
+                .map_err(|e| e.to_string())?
+                .unwrap_or_default();
 
-            // remove the previous breakdown
-            db::remove_breakdown(&pool, event.repository.id, &event.before)
+            // get a breakdown for each file of the cost and prices
+            let report = git::cost_breakdowns(configurations, &adjustments).await;
+
+            // the repository setting wins over the currency declared in infra.toml
+            let currency = db::fetch_repository_currency(pool, &repository)
+                .await
+                .map_err(|e| e.to_string())?
+                .or(report.currency)
+                .unwrap_or(Currency::Usd);
+
+            // fetch the previous breakdown
+            let previous_breakdown = db::fetch_previous_breakdown(pool, &repository, &base)
                 .await
-                .unwrap();
+                .map_err(|e| e.to_string())?;
+
+            let rate = cost::exchange_rate(currency);
+
+            // configuration errors are shown even when the costs are unchanged
+            let changed = report::has_changes(previous_breakdown.as_ref(), &report.breakdowns)
+                || !report.errors.is_empty();
+
+            let markdown =
+                changed.then(|| report::markdown(previous_breakdown.clone(), &report, &rate));
+
+            let comment_url = forge
+                .comment_on_change_request(
+                    client,
+                    &repository,
+                    number,
+                    &head,
+                    markdown.as_deref(),
+                    &token,
+                )
+                .await?;
+
+            // pass or fail the check against the budgets
+            let details_url =
+                comment_url.unwrap_or_else(|| forge.change_request_url(&repository, number));
+
+            let outcome = cost_check(
+                pool,
+                &repository,
+                &report,
+                base_budgets,
+                previous_breakdown.as_ref(),
+                &rate,
+                details_url,
+            )
+            .await?;
+
+            forge
+                .complete_check(client, &repository, &head, check_id, &outcome, &token)
+                .await?;
+
+            // the cost the change request would add, counted once it is merged
+            let cost_before = previous_breakdown
+                .as_ref()
+                .map(|previous| previous.values().map(report::app_total).sum());
+
+            db::store_change_request(
+                pool,
+                &repository,
+                number,
+                &title,
+                &base,
+                &head,
+                cost_before,
+                report.total(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        ForgeEvent::Merged {
+            repository,
+            number,
+            merged_at,
+        } => {
+            let priced = db::mark_change_request_merged(pool, &repository, number, merged_at)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if !priced {
+                println!(
+                    "Skipping merge of {} #{}, it was never priced",
+                    repository.full_name, number
+                );
+            }
         }
-        GitHubEvent::PullRequest => {
-            let event: PullRequest = serde_json::from_value(payload).unwrap();
-
-            match event.action.as_str() {
-                "opened" | "synchronize" => {
-                    let repo = &event.repository.full_name;
-
-                    git::clone(
-                        repo.to_string(),
-                        String::new(),
-                        event.pull_request.head.sha.clone(),
-                        builder,
-                    );
-
-                    let files = git::configuration_files(repo.to_string());
-
-                    git::delete(repo.to_string());
-
-                    let mut breakdowns: IndexMap<String, IndexMap<String, f64>> = IndexMap::new();
-
-                    for (_path, contents) in files {
-                        let config = InfrastructureConfiguration::from_str(&contents).unwrap();
-
-                        // next, parse the contents of the infra.toml file and analyse it.
-                        let breakdown = cost::calculate_cost(&config).await;
-
-                        breakdowns.insert(config.app.name, breakdown);
-                    }
-
-                    // fetch the previous breakdown
-                    let previous_breakdown = db::fetch_previous_breakdown(
-                        &pool,
-                        event.repository.id,
-                        &event.pull_request.base.sha,
-                    )
-                    .await
-                    .unwrap();
-
-                    github::comment_on_pull_request(
-                        previous_breakdown,
-                        &breakdowns,
-                        event.number,
-                        &event.repository.full_name,
-                    )
-                    .await;
-
-                    // todo: mark checks on the pull request
-                }
-                &_ => {}
+    }
+
+    Ok(())
+}
+
+// Outcome of the cost check, failed when any app is over a hard limit of its budget.
+// Warnings are listed without failing it
+async fn cost_check(
+    pool: &Pool<Postgres>,
+    repository: &ForgeRepository,
+    report: &CostReport,
+    base_budgets: IndexMap<String, Budget>,
+    previous_breakdown: Option<&IndexMap<String, IndexMap<String, f64>>>,
+    rate: &ExchangeRate,
+    details_url: String,
+) -> Result<CheckOutcome, String> {
+    let mut stored = IndexMap::new();
+    let mut violations = Vec::new();
+
+    // a budget stored for the repository that can't be read fails the check, rather than
+    // the app going unchecked
+    for (app_name, budget) in db::fetch_repository_budgets(pool, repository)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        match budget {
+            Ok(budget) => {
+                stored.insert(app_name, budget);
             }
+            Err(e) => violations.push(Violation {
+                message: format!("The budget stored for {} is not valid: {}", app_name, e),
+                app_name,
+                severity: Severity::Failure,
+            }),
         }
-        _ => {}
     }
 
-    "ping"
+    let budgets = cost::budgets(report, base_budgets, stored);
+
+    violations.extend(cost::budget_violations(
+        report,
+        &budgets,
+        previous_breakdown,
+        rate,
+    ));
+
+    let success = !violations
+        .iter()
+        .any(|violation| violation.severity == Severity::Failure);
+
+    let summary = if violations.is_empty() {
+        format!(
+            "Total monthly cost of {}, every app is within its budget.",
+            rate.format(report.total())
+        )
+    } else {
+        cost::violation_lines(&violations)
+    };
+
+    let text = format!(
+        "{}\n\n{}",
+        cost::budgets_markdown(report, &budgets, &violations, rate),
+        math::report::markdown(None, report, rate)
+    );
+
+    Ok(CheckOutcome {
+        success,
+        summary,
+        text,
+        details_url,
+    })
 }
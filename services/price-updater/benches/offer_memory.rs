@@ -0,0 +1,148 @@
+// Peak heap use of visiting a regional EC2 offer file, with the products listed before and after
+// the terms. Besides the recorded fixtures, offers of many products are generated in both orders:
+// holding on to their terms would show in the peak, so it must stay under the same bound. Set
+// OFFER_FILE to the path of a downloaded offer file to measure it instead, e.g.
+//
+//   curl -o /tmp/us-east-1.json \
+//     https://pricing.us-east-1.amazonaws.com/offers/v1.0/aws/AmazonEC2/current/us-east-1/index.json
+//   OFFER_FILE=/tmp/us-east-1.json cargo bench --bench offer_memory
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use price_updater::api::aws::instance::InstanceOffer;
+use price_updater::api::aws::offer::visit_offer_file;
+
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+// Most the heap may hold while a fixture or generated offer is visited, whichever order it is in
+const MAX_PEAK_BYTES: usize = 256 * 1024;
+
+// Products of a generated offer, tens of megabytes worth
+const GENERATED_PRODUCTS: usize = 50_000;
+
+// Most bytes held on the heap at once while the offer file is visited. The handler is built
+// before profiling starts, compiling its regex would otherwise outweigh a small fixture
+fn peak_bytes(path: &Path) -> usize {
+    let file = File::open(path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+    let mut offer = InstanceOffer::new("us-east-1");
+
+    let _profiler = dhat::Profiler::builder().testing().build();
+
+    visit_offer_file(BufReader::new(file), &mut offer).unwrap();
+
+    dhat::HeapStats::get().max_bytes
+}
+
+fn report(name: &str, path: &Path) -> usize {
+    let file_bytes = std::fs::metadata(path).unwrap().len();
+    let peak = peak_bytes(path);
+
+    println!(
+        "{:<12} offer file {:>12} bytes, peak heap {:>12} bytes ({:.1}%)",
+        name,
+        file_bytes,
+        peak,
+        peak as f64 / file_bytes as f64 * 100.0
+    );
+
+    peak
+}
+
+// An offer of CPU credit products, which the instance offer doesn't keep, each with an
+// on-demand term
+fn generate_offer(path: &Path, terms_first: bool) {
+    let mut file = BufWriter::new(File::create(path).unwrap());
+
+    let products = |file: &mut BufWriter<File>| {
+        write!(file, r#""products": {{"#).unwrap();
+
+        for i in 0..GENERATED_PRODUCTS {
+            write!(
+                file,
+                r#"{}"SKU{i}": {{ "sku": "SKU{i}", "productFamily": "CPU Credits", "attributes": {{ "servicecode": "AmazonEC2", "regionCode": "us-east-1", "usagetype": "CPUCredits:t3", "operation": "RunInstances" }} }}"#,
+                if i == 0 { "" } else { ", " },
+            )
+            .unwrap();
+        }
+
+        write!(file, "}}").unwrap();
+    };
+
+    let terms = |file: &mut BufWriter<File>| {
+        write!(file, r#""terms": {{ "OnDemand": {{"#).unwrap();
+
+        for i in 0..GENERATED_PRODUCTS {
+            write!(
+                file,
+                r#"{}"SKU{i}": {{ "SKU{i}.JRTCKXETXF": {{ "offerTermCode": "JRTCKXETXF", "sku": "SKU{i}", "effectiveDate": "2024-03-01T00:00:00Z", "priceDimensions": {{ "SKU{i}.JRTCKXETXF.6YS6EN2CT7": {{ "rateCode": "SKU{i}.JRTCKXETXF.6YS6EN2CT7", "description": "$0.05 per vCPU-Hour", "beginRange": "0", "endRange": "Inf", "unit": "vCPU-Hours", "pricePerUnit": {{ "USD": "0.0500000000" }}, "appliesTo": [] }} }}, "termAttributes": {{}} }} }}"#,
+                if i == 0 { "" } else { ", " },
+            )
+            .unwrap();
+        }
+
+        write!(file, "}} }}").unwrap();
+    };
+
+    write!(
+        file,
+        r#"{{ "formatVersion": "v1.0", "offerCode": "AmazonEC2", "#
+    )
+    .unwrap();
+
+    if terms_first {
+        terms(&mut file);
+        write!(file, ", ").unwrap();
+        products(&mut file);
+    } else {
+        products(&mut file);
+        write!(file, ", ").unwrap();
+        terms(&mut file);
+    }
+
+    write!(file, " }}").unwrap();
+
+    file.flush().unwrap();
+}
+
+fn main() {
+    if let Ok(offer_file) = std::env::var("OFFER_FILE") {
+        report("offer", Path::new(&offer_file));
+
+        return;
+    }
+
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/aws");
+
+    let mut peaks = vec![
+        (
+            "products",
+            report("products", &fixtures.join("ec2_offer.json")),
+        ),
+        (
+            "terms",
+            report("terms", &fixtures.join("ec2_offer_terms_first.json")),
+        ),
+    ];
+
+    for (name, terms_first) in [("generated", false), ("generated-t", true)] {
+        let path = std::env::temp_dir().join(format!("offer_memory_{}.json", name));
+
+        generate_offer(&path, terms_first);
+        peaks.push((name, report(name, &path)));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    for (name, peak) in peaks {
+        assert!(
+            peak <= MAX_PEAK_BYTES,
+            "{} offer peaked at {} bytes, over the bound of {}",
+            name,
+            peak,
+            MAX_PEAK_BYTES
+        );
+    }
+}
@@ -0,0 +1,106 @@
+-- Tag every price with the cloud provider it was ingested from.
+-- Existing rows were all ingested from AWS.
+
+-- The AWS tables predate the migrations, they are created as they were on a fresh database.
+CREATE TABLE IF NOT EXISTS on_demand (
+    region TEXT NOT NULL,
+    instance_type TEXT NOT NULL,
+    vcpu_count DOUBLE PRECISION NOT NULL,
+    memory DOUBLE PRECISION NOT NULL,
+    price_per_hour DOUBLE PRECISION NOT NULL,
+    architecture TEXT NOT NULL,
+    storage TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (region, instance_type)
+);
+
+CREATE TABLE IF NOT EXISTS spot (
+    region TEXT NOT NULL,
+    availability_zone TEXT NOT NULL,
+    instance_type TEXT NOT NULL,
+    price_per_hour DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (region, availability_zone, instance_type)
+);
+
+CREATE TABLE IF NOT EXISTS storage (
+    region TEXT NOT NULL,
+    volume_api_name TEXT NOT NULL,
+    storage_media TEXT NOT NULL,
+    price_per_gb_month DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (region, volume_api_name)
+);
+
+CREATE TABLE IF NOT EXISTS inter_region_data_transfer (
+    from_region_code TEXT NOT NULL,
+    to_region_code TEXT NOT NULL,
+    price_per_gb DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (from_region_code, to_region_code)
+);
+
+CREATE TABLE IF NOT EXISTS external_data_transfer (
+    from_region_code TEXT NOT NULL,
+    start_range BIGINT NOT NULL,
+    end_range BIGINT NOT NULL,
+    price_per_gb DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (from_region_code, start_range, end_range)
+);
+
+ALTER TABLE on_demand ADD COLUMN IF NOT EXISTS provider TEXT NOT NULL DEFAULT 'aws';
+ALTER TABLE spot ADD COLUMN IF NOT EXISTS provider TEXT NOT NULL DEFAULT 'aws';
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS provider TEXT NOT NULL DEFAULT 'aws';
+ALTER TABLE inter_region_data_transfer ADD COLUMN IF NOT EXISTS provider TEXT NOT NULL DEFAULT 'aws';
+ALTER TABLE external_data_transfer ADD COLUMN IF NOT EXISTS provider TEXT NOT NULL DEFAULT 'aws';
+
+-- The old keys without the provider would reject a GCP or Azure price for a region or
+-- instance type AWS also has. Databases set up by hand named them differently, so every
+-- primary key, unique constraint and unique index without the provider column is dropped.
+DO $$
+DECLARE
+    old_key RECORD;
+BEGIN
+    FOR old_key IN
+        SELECT key_index.indexrelid::regclass AS index_name,
+               key_index.indrelid::regclass AS table_name,
+               key_constraint.conname AS constraint_name
+        FROM pg_index key_index
+        LEFT JOIN pg_constraint key_constraint ON key_constraint.conindid = key_index.indexrelid
+            AND key_constraint.contype IN ('p', 'u')
+        WHERE key_index.indisunique
+          AND key_index.indrelid IN (
+              'on_demand'::regclass,
+              'spot'::regclass,
+              'storage'::regclass,
+              'inter_region_data_transfer'::regclass,
+              'external_data_transfer'::regclass
+          )
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_attribute attribute
+              WHERE attribute.attrelid = key_index.indrelid
+                AND attribute.attnum = ANY (key_index.indkey)
+                AND attribute.attname = 'provider'
+          )
+    LOOP
+        IF old_key.constraint_name IS NOT NULL THEN
+            EXECUTE format('ALTER TABLE %s DROP CONSTRAINT %I', old_key.table_name, old_key.constraint_name);
+        ELSE
+            EXECUTE format('DROP INDEX %s', old_key.index_name);
+        END IF;
+    END LOOP;
+END
+$$;
+
+CREATE UNIQUE INDEX IF NOT EXISTS on_demand_provider_key
+    ON on_demand (provider, region, instance_type);
+CREATE UNIQUE INDEX IF NOT EXISTS spot_provider_key
+    ON spot (provider, region, availability_zone, instance_type);
+CREATE UNIQUE INDEX IF NOT EXISTS storage_provider_key
+    ON storage (provider, region, volume_api_name);
+CREATE UNIQUE INDEX IF NOT EXISTS inter_region_data_transfer_provider_key
+    ON inter_region_data_transfer (provider, from_region_code, to_region_code);
+CREATE UNIQUE INDEX IF NOT EXISTS external_data_transfer_provider_key
+    ON external_data_transfer (provider, from_region_code, start_range, end_range);
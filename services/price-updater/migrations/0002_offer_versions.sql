@@ -0,0 +1,14 @@
+-- Last ingested version of every offer file, so unchanged offers are not downloaded again.
+-- etag and last_modified are the validators returned with the file, sent back on the next request.
+
+CREATE TABLE IF NOT EXISTS offer_versions (
+    provider TEXT NOT NULL,
+    service TEXT NOT NULL,
+    region TEXT NOT NULL,
+    version TEXT NOT NULL,
+    publication_date TEXT NOT NULL,
+    etag TEXT,
+    last_modified TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, service, region)
+);
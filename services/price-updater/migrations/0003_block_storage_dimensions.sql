@@ -0,0 +1,8 @@
+-- Price block storage volumes by provisioned performance and snapshots, not only by size.
+-- included_iops and included_throughput are the baseline covered by price_per_gb_month (gp3).
+
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS price_per_iops_month DOUBLE PRECISION;
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS price_per_mibps_month DOUBLE PRECISION;
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS included_iops INTEGER;
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS included_throughput INTEGER;
+ALTER TABLE storage ADD COLUMN IF NOT EXISTS snapshot_price_per_gb_month DOUBLE PRECISION;
@@ -0,0 +1,13 @@
+-- Object storage prices per storage class. Storage is tiered by volume, only the first tier is kept.
+
+CREATE TABLE IF NOT EXISTS object_storage (
+    provider TEXT NOT NULL DEFAULT 'aws',
+    region TEXT NOT NULL,
+    storage_class TEXT NOT NULL,
+    price_per_gb_month DOUBLE PRECISION NOT NULL,
+    price_per_1k_put_requests DOUBLE PRECISION NOT NULL,
+    price_per_1k_get_requests DOUBLE PRECISION NOT NULL,
+    retrieval_price_per_gb DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, region, storage_class)
+);
@@ -0,0 +1,26 @@
+-- Managed database prices. Engines and deployment options use lowercase codes (postgres, multi-az).
+-- backup_price_per_gb_month applies to backups beyond the free allowance of the provisioned storage.
+
+CREATE TABLE IF NOT EXISTS database_instances (
+    provider TEXT NOT NULL DEFAULT 'aws',
+    region TEXT NOT NULL,
+    engine TEXT NOT NULL,
+    deployment_option TEXT NOT NULL,
+    instance_type TEXT NOT NULL,
+    vcpu_count REAL NOT NULL,
+    memory REAL NOT NULL,
+    price_per_hour DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, region, engine, deployment_option, instance_type)
+);
+
+CREATE TABLE IF NOT EXISTS database_storage (
+    provider TEXT NOT NULL DEFAULT 'aws',
+    region TEXT NOT NULL,
+    deployment_option TEXT NOT NULL,
+    volume_type TEXT NOT NULL,
+    price_per_gb_month DOUBLE PRECISION NOT NULL,
+    backup_price_per_gb_month DOUBLE PRECISION,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, region, deployment_option, volume_type)
+);
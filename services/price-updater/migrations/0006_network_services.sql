@@ -0,0 +1,13 @@
+-- Fixed network costs: load balancer hours and LCUs, NAT gateway hours and processed data,
+-- and public IPv4 address hours. One row per service and usage, priced per unit.
+
+CREATE TABLE IF NOT EXISTS network_services (
+    provider TEXT NOT NULL DEFAULT 'aws',
+    region TEXT NOT NULL,
+    service TEXT NOT NULL,
+    usage TEXT NOT NULL,
+    unit TEXT NOT NULL,
+    price_per_unit DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (provider, region, service, usage)
+);
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+
+use crate::api::aws::offer::{
+    fetch_changed_offer, pricing_url, region_offer_version, OfferHandler, RDS_OFFER,
+};
+use crate::constants::database_engines::{RDS_DEPLOYMENT_OPTIONS, RDS_ENGINES, RDS_VOLUME_TYPES};
+use crate::db;
+use crate::models::database::{DatabaseInstance, DatabaseStorage};
+use crate::models::offer_index::RegionIndex;
+use crate::models::on_demand_pricing::{OnDemandTerms, Product};
+
+fn code(codes: &[(&str, &'static str)], name: Option<&str>) -> Option<&'static str> {
+    codes
+        .iter()
+        .find(|(offer_name, _)| Some(*offer_name) == name)
+        .map(|(_, code)| *code)
+}
+
+// Collects the instance, storage and backup prices of a regional RDS offer file
+struct DatabaseOffer {
+    region_code: String,
+    // Map of the sku id -> database instance
+    sku_to_instance: HashMap<String, DatabaseInstance>,
+    // Map of the sku id -> database storage
+    sku_to_storage: HashMap<String, DatabaseStorage>,
+    // Map of the sku id -> backup storage price
+    sku_to_backup: HashMap<String, f32>,
+}
+
+impl OfferHandler for DatabaseOffer {
+    type Product = Product;
+    type Term = OnDemandTerms;
+
+    fn product(&mut self, sku: String, product: Product) {
+        let attributes = match product.attributes {
+            Some(attributes) => attributes,
+            None => return,
+        };
+
+        let deployment_option = code(
+            &RDS_DEPLOYMENT_OPTIONS,
+            attributes.deployment_option.as_deref(),
+        );
+
+        match product.product_family.as_str() {
+            "Database Instance" => {
+                let engine = code(&RDS_ENGINES, attributes.database_engine.as_deref());
+
+                if let (Some(engine), Some(deployment_option), Some(instance_type)) =
+                    (engine, deployment_option, attributes.instance_type)
+                {
+                    // for memory, parse the string and extract the number
+                    // 4 GiB -> 4
+                    let memory = attributes
+                        .memory
+                        .as_deref()
+                        .and_then(|memory| memory.split_whitespace().next())
+                        .and_then(|memory| memory.parse::<f32>().ok())
+                        .unwrap_or(0.0);
+
+                    self.sku_to_instance.insert(
+                        sku,
+                        DatabaseInstance {
+                            region: self.region_code.clone(),
+                            engine: engine.to_string(),
+                            deployment_option: deployment_option.to_string(),
+                            instance_type,
+                            vcpu_count: attributes.vcpu.map(|vcpu| vcpu.0).unwrap_or(0.0),
+                            memory,
+                            price_per_hour: 0.0,
+                        },
+                    );
+                }
+            }
+            "Database Storage" => {
+                let volume_type = code(&RDS_VOLUME_TYPES, attributes.volume_type.as_deref());
+
+                if let (Some(volume_type), Some(deployment_option)) =
+                    (volume_type, deployment_option)
+                {
+                    self.sku_to_storage.insert(
+                        sku,
+                        DatabaseStorage {
+                            region: self.region_code.clone(),
+                            deployment_option: deployment_option.to_string(),
+                            volume_type: volume_type.to_string(),
+                            price_per_gb_month: 0.0,
+                            backup_price_per_gb_month: None,
+                        },
+                    );
+                }
+            }
+            // usage types are prefixed with the region, except in us-east-1
+            "Storage Snapshot"
+                if attributes
+                    .usagetype
+                    .is_some_and(|usagetype| usagetype.ends_with("RDS:ChargedBackupUsage")) =>
+            {
+                self.sku_to_backup.insert(sku, 0.0);
+            }
+            _ => {}
+        }
+    }
+
+    fn indexes(&self, sku: &str) -> bool {
+        self.sku_to_instance.contains_key(sku)
+            || self.sku_to_storage.contains_key(sku)
+            || self.sku_to_backup.contains_key(sku)
+    }
+
+    fn term(&mut self, sku: String, terms: HashMap<String, OnDemandTerms>) {
+        let price = terms
+            .values()
+            .flat_map(|term| term.price_dimensions.values())
+            .filter_map(|dimension| dimension.price_per_unit.usd.as_ref())
+            .map(|price| price.0)
+            .next()
+            .unwrap_or(0.0);
+
+        if let Some(instance) = self.sku_to_instance.get_mut(&sku) {
+            instance.price_per_hour = price;
+        } else if let Some(storage) = self.sku_to_storage.get_mut(&sku) {
+            storage.price_per_gb_month = price;
+        } else if let Some(backup) = self.sku_to_backup.get_mut(&sku) {
+            *backup = price;
+        }
+    }
+}
+
+impl DatabaseOffer {
+    // Storage entries keyed by deployment option and volume type, with the backup price filled in
+    fn storage_entries(&self) -> Vec<DatabaseStorage> {
+        let backup_price = self
+            .sku_to_backup
+            .values()
+            .copied()
+            .find(|price| *price > 0.0);
+
+        let mut storage_entries: HashMap<(String, String), DatabaseStorage> = HashMap::new();
+
+        for storage in self.sku_to_storage.values() {
+            storage_entries.insert(
+                (
+                    storage.deployment_option.clone(),
+                    storage.volume_type.clone(),
+                ),
+                DatabaseStorage {
+                    backup_price_per_gb_month: backup_price,
+                    ..storage.clone()
+                },
+            );
+        }
+
+        storage_entries.into_values().collect()
+    }
+}
+
+// Function to update database pricing for a specific region
+pub async fn update_database_pricing_for_region(
+    pool: PgPool,
+    region_code: &str,
+    region_index: &RegionIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offer = DatabaseOffer {
+        region_code: region_code.to_string(),
+        sku_to_instance: HashMap::new(),
+        sku_to_storage: HashMap::new(),
+        sku_to_backup: HashMap::new(),
+    };
+
+    let version = region_offer_version(region_index, region_code)?;
+
+    let Some((offer, offer_version)) = fetch_changed_offer(
+        &pool,
+        &pricing_url(),
+        RDS_OFFER,
+        region_code,
+        version,
+        offer,
+    )
+    .await?
+    else {
+        println!(
+            "Database pricing for {} is up to date.",
+            region_code.bright_cyan()
+        );
+
+        return Ok(());
+    };
+
+    let instance_entries: Vec<DatabaseInstance> = offer
+        .sku_to_instance
+        .values()
+        .filter(|instance| instance.price_per_hour > 0.0)
+        .cloned()
+        .collect();
+    let storage_entries: Vec<DatabaseStorage> = offer.storage_entries();
+
+    drop(offer);
+
+    let instance_entries_len = instance_entries.len();
+    let storage_entries_len = storage_entries.len();
+
+    db::insert::database_instance_pricing_in_bulk(&pool, "aws", instance_entries).await?;
+    db::insert::database_storage_pricing_in_bulk(&pool, "aws", storage_entries).await?;
+
+    // Record the ingested version only once its prices are stored
+    db::offer_version::save_offer_version(&pool, "aws", RDS_OFFER, region_code, &offer_version)
+        .await?;
+
+    println!(
+        "Updated database pricing for {} with {} instance types and {} storage types.",
+        region_code.bright_cyan(),
+        instance_entries_len.to_string().bright_green(),
+        storage_entries_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
@@ -1,16 +1,21 @@
+use crate::api::aws::network_services::NetworkServicePrices;
+use crate::api::aws::offer::{
+    fetch_changed_offer, pricing_url, region_offer_version, OfferHandler, EC2_OFFER,
+};
+use crate::constants::machine_types::EBS_VOLUME_BASELINES;
 use crate::db;
-use crate::models::on_demand_pricing::{BulkPricingResponse, OnDemandInstance};
+use crate::models::network_service::NetworkService;
+use crate::models::offer_index::RegionIndex;
+use crate::models::on_demand_pricing::{OnDemandInstance, OnDemandTerms, Product};
 use crate::models::spot_pricing::SpotInstance;
 use crate::models::storage::Storage;
 use aws_sdk_ec2::config::Region;
 use aws_sdk_ec2::primitives::DateTime;
 use aws_sdk_ec2::types::{InstanceType, SpotPrice};
 use colored::Colorize;
-use futures_util::stream::StreamExt;
 use regex::Regex;
-use reqwest;
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 fn convert_to_spot_instance(spot_price: &SpotPrice) -> SpotInstance {
     SpotInstance {
@@ -79,6 +84,7 @@ pub async fn update_spot_pricing_for_region(
 
     crate::db::insert::spot_pricing_in_bulk(
         &pool,
+        "aws",
         region_code_string.clone(),
         latest_prices.clone(),
     )
@@ -89,144 +95,250 @@ pub async fn update_spot_pricing_for_region(
     Ok(())
 }
 
-// Function to update pricing for a specific region (on-demand)
-pub async fn update_pricing_for_region(
-    pool: PgPool,
-    region_code: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Construct the URL for the specific region
-    let url = format!(
-        "https://pricing.us-east-1.amazonaws.com/offers/v1.0/aws/AmazonEC2/current/{}/index.json",
-        region_code
-    );
+// Collects the instance, storage and NAT gateway prices of a regional EC2 offer file
+pub struct InstanceOffer {
+    region_code: String,
+    pattern: Regex,
+    // SKUs of the instance and storage products, whose terms are kept
+    indexed_skus: HashSet<String>,
+    // Map of the instance name -> instance name, vcpu count, and memory
+    sku_to_instance: HashMap<String, OnDemandInstance>,
+    // Map of the sku id -> storage
+    sku_to_storage: HashMap<String, Storage>,
+    // Map of the sku id -> iops, throughput and snapshot prices
+    sku_to_volume_price: HashMap<String, VolumePrice>,
+    // NAT gateway prices
+    network_services: NetworkServicePrices,
+}
 
-    // Create a client
-    let client = reqwest::Client::new();
+// Priced dimensions of an EBS volume besides its size
+#[derive(Clone, Copy, PartialEq)]
+enum VolumeDimension {
+    Iops,
+    Throughput,
+    Snapshot,
+}
 
-    // Send a GET request
-    let response = client.get(&url).send().await?;
+struct VolumePrice {
+    volume_api_name: Option<String>,
+    dimension: VolumeDimension,
+    price: f32,
+}
 
-    // Download the content
-    let mut content = Vec::new();
-    let mut stream = response.bytes_stream();
+impl InstanceOffer {
+    pub fn new(region_code: &str) -> Self {
+        Self {
+            region_code: region_code.to_string(),
+            pattern: Regex::new(r"(.*) per On Demand Linux ([A-Za-z0-9.-]+) Instance Hour")
+                .unwrap(),
+            indexed_skus: HashSet::new(),
+            sku_to_instance: HashMap::new(),
+            sku_to_storage: HashMap::new(),
+            sku_to_volume_price: HashMap::new(),
+            network_services: NetworkServicePrices::new(region_code),
+        }
+    }
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        content.extend_from_slice(&chunk);
+    fn volume_price(
+        &self,
+        volume_api_name: Option<&str>,
+        dimension: VolumeDimension,
+    ) -> Option<f32> {
+        self.sku_to_volume_price
+            .values()
+            .find(|volume_price| {
+                volume_price.dimension == dimension
+                    && volume_price.volume_api_name.as_deref() == volume_api_name
+            })
+            .map(|volume_price| volume_price.price)
     }
 
-    // Parse the JSON content
-    let region_response: BulkPricingResponse = serde_json::from_slice(&content)?;
-
-    drop(content);
-
-    // Create a map of the sku id -> instance name, vcpu count, and memory
-    let mut sku_to_instance: HashMap<String, OnDemandInstance> = HashMap::new();
-    // Create a map of the sku id -> storage
-    let mut sku_to_storage: HashMap<String, Storage> = HashMap::new();
-
-    if let Some(products) = &region_response.products {
-        for (_, details) in products.iter() {
-            if let Some(attribute) = &details.attributes {
-                if details.product_family == "Storage" {
-                    let sku = details.sku.as_str();
-
-                    sku_to_storage.insert(
-                        sku.to_owned(),
-                        Storage {
-                            volume_api_name: attribute.volume_api_name.as_ref().unwrap().clone(),
-                            storage_media: if attribute.storage_media.as_ref().unwrap()
-                                == "HDD-backed"
-                            {
-                                "HDD".to_string()
-                            } else {
-                                "SSD".to_string()
-                            },
-                            price_per_gb_month: 0.0,
-                            region: region_code.to_string(),
-                        },
-                    );
+    // Storage entries with the prices of their provisioned performance and snapshots filled in
+    fn storage_entries(&self) -> Vec<Storage> {
+        let snapshot_price = self.volume_price(None, VolumeDimension::Snapshot);
+
+        self.sku_to_storage
+            .values()
+            .map(|storage| {
+                let volume_api_name = Some(storage.volume_api_name.as_str());
+
+                let baseline = EBS_VOLUME_BASELINES
+                    .iter()
+                    .find(|(name, _, _)| *name == storage.volume_api_name);
+
+                Storage {
+                    price_per_iops_month: self.volume_price(volume_api_name, VolumeDimension::Iops),
+                    price_per_mibps_month: self
+                        .volume_price(volume_api_name, VolumeDimension::Throughput),
+                    included_iops: baseline.map(|(_, iops, _)| *iops),
+                    included_throughput: baseline.map(|(_, _, throughput)| *throughput),
+                    snapshot_price_per_gb_month: snapshot_price,
+                    ..storage.clone()
                 }
+            })
+            .collect()
+    }
+}
 
-                if let Some(instance_name) = &attribute.instance_type {
-                    let instance_name = instance_name.as_str();
-
-                    let vcpu_count: f32 = attribute.vcpu.as_ref().unwrap().0;
-
-                    // for memory, parse the string and extract the number
-                    // 4 GiB -> 4
-                    let memory = attribute
-                        .memory
-                        .as_ref()
-                        .unwrap()
-                        .split_whitespace()
-                        .next()
-                        .unwrap()
-                        .parse::<f32>()
-                        .unwrap_or(0.0);
-
-                    let storage = attribute.storage.as_ref().unwrap();
-
-                    let physical_processor = attribute.physical_processor.as_ref().unwrap();
-
-                    let arch = if physical_processor.starts_with("AWS Graviton")
-                        || physical_processor.starts_with("Ampere")
-                    {
-                        "arm64".to_string()
-                    } else {
-                        "x86_64".to_string()
-                    };
+// Classify the usage type of an EBS product, e.g. USE2-EBS:VolumeP-IOPS.io2
+fn volume_dimension(usagetype: &str) -> Option<VolumeDimension> {
+    let (_, usage) = usagetype.split_once("EBS:")?;
+
+    if usage == "SnapshotUsage" {
+        Some(VolumeDimension::Snapshot)
+    } else if usage.starts_with("VolumeP-IOPS.") && !usage.contains(".tier") {
+        // io2 has cheaper tiers above 32000 iops, only the first tier is kept
+        Some(VolumeDimension::Iops)
+    } else if usage.starts_with("VolumeP-Throughput.") {
+        Some(VolumeDimension::Throughput)
+    } else {
+        None
+    }
+}
 
-                    if memory != 0.0 {
-                        sku_to_instance.insert(
-                            instance_name.to_owned(),
-                            OnDemandInstance {
-                                region: region_code.to_string(),
-                                instance_name: instance_name.to_string(),
-                                vcpu_count,
-                                memory,
-                                price_per_hour: 0.0,
-                                arch,
-                                storage: storage.to_owned(),
-                            },
-                        );
-                    }
+impl OfferHandler for InstanceOffer {
+    type Product = Product;
+    type Term = OnDemandTerms;
+
+    fn product(&mut self, _: String, details: Product) {
+        if self.network_services.product(&details.sku, &details) {
+            return;
+        }
+
+        if let Some(attribute) = &details.attributes {
+            if details.product_family == "Storage" {
+                let sku = details.sku.as_str();
+
+                self.indexed_skus.insert(sku.to_owned());
+
+                self.sku_to_storage.insert(
+                    sku.to_owned(),
+                    Storage {
+                        volume_api_name: attribute.volume_api_name.as_ref().unwrap().clone(),
+                        storage_media: if attribute.storage_media.as_ref().unwrap() == "HDD-backed"
+                        {
+                            "HDD".to_string()
+                        } else {
+                            "SSD".to_string()
+                        },
+                        price_per_gb_month: 0.0,
+                        price_per_iops_month: None,
+                        price_per_mibps_month: None,
+                        included_iops: None,
+                        included_throughput: None,
+                        snapshot_price_per_gb_month: None,
+                        region: self.region_code.clone(),
+                    },
+                );
+            }
+
+            if let Some(dimension) = attribute.usagetype.as_deref().and_then(volume_dimension) {
+                self.indexed_skus.insert(details.sku.clone());
+
+                self.sku_to_volume_price.insert(
+                    details.sku.clone(),
+                    VolumePrice {
+                        volume_api_name: match dimension {
+                            VolumeDimension::Snapshot => None,
+                            _ => attribute.volume_api_name.clone(),
+                        },
+                        dimension,
+                        price: 0.0,
+                    },
+                );
+            }
+
+            if let Some(instance_name) = &attribute.instance_type {
+                let instance_name = instance_name.as_str();
+
+                self.indexed_skus.insert(details.sku.clone());
+
+                let vcpu_count: f32 = attribute.vcpu.as_ref().unwrap().0;
+
+                // for memory, parse the string and extract the number
+                // 4 GiB -> 4
+                let memory = attribute
+                    .memory
+                    .as_ref()
+                    .unwrap()
+                    .split_whitespace()
+                    .next()
+                    .unwrap()
+                    .parse::<f32>()
+                    .unwrap_or(0.0);
+
+                let storage = attribute.storage.as_ref().unwrap();
+
+                let physical_processor = attribute.physical_processor.as_ref().unwrap();
+
+                let arch = if physical_processor.starts_with("AWS Graviton")
+                    || physical_processor.starts_with("Ampere")
+                {
+                    "arm64".to_string()
+                } else {
+                    "x86_64".to_string()
+                };
+
+                if memory != 0.0 {
+                    self.sku_to_instance.insert(
+                        instance_name.to_owned(),
+                        OnDemandInstance {
+                            region: self.region_code.clone(),
+                            instance_name: instance_name.to_string(),
+                            vcpu_count,
+                            memory,
+                            price_per_hour: 0.0,
+                            arch,
+                            storage: storage.to_owned(),
+                        },
+                    );
                 }
             }
         }
     }
 
-    let pattern = Regex::new(r"(.*) per On Demand Linux ([A-Za-z0-9.-]+) Instance Hour").unwrap();
-
-    if let Some(terms) = &region_response.terms {
-        for details in terms.on_demand.values() {
-            for term in details.values() {
-                for price_dimensions in term.price_dimensions.values() {
-                    if sku_to_storage.contains_key(&term.sku) {
-                        sku_to_storage
-                            .get_mut(&term.sku)
-                            .unwrap()
-                            .price_per_gb_month =
-                            price_dimensions.price_per_unit.usd.as_ref().unwrap().0;
-                    } else {
-                        let description = &price_dimensions.description.as_ref().unwrap();
-
-                        if !description.is_empty() {
-                            let captures = pattern.captures(description);
-
-                            if let Some(captures) = captures {
-                                let instance_name = captures.get(2).unwrap().as_str();
-
-                                if let Some(instance) = sku_to_instance.get_mut(instance_name) {
-                                    if let Some(price_per_hour) =
-                                        price_dimensions.price_per_unit.usd.as_ref()
-                                    {
-                                        // Convert the price per hour to f64 and round to 5 decimal places
-                                        instance.price_per_hour =
-                                            (price_per_hour.0 * 100000.0).round() / 100000.0;
-                                    } else {
-                                        instance.price_per_hour = 0.0;
-                                    }
+    fn indexes(&self, sku: &str) -> bool {
+        self.indexed_skus.contains(sku) || self.network_services.contains(sku)
+    }
+
+    fn term(&mut self, sku: String, details: HashMap<String, OnDemandTerms>) {
+        if self.network_services.contains(&sku) {
+            self.network_services.term(&sku, &details);
+
+            return;
+        }
+
+        for term in details.values() {
+            for price_dimensions in term.price_dimensions.values() {
+                if let Some(storage) = self.sku_to_storage.get_mut(&term.sku) {
+                    storage.price_per_gb_month =
+                        price_dimensions.price_per_unit.usd.as_ref().unwrap().0;
+                } else if let Some(volume_price) = self.sku_to_volume_price.get_mut(&term.sku) {
+                    let price = price_dimensions.price_per_unit.usd.as_ref().unwrap().0;
+
+                    // throughput may be listed per GiBps-month, store it per MiBps-month
+                    volume_price.price = match price_dimensions.unit.as_deref() {
+                        Some(unit) if unit.starts_with("GiBps") => price / 1024.0,
+                        _ => price,
+                    };
+                } else {
+                    let description = &price_dimensions.description.as_ref().unwrap();
+
+                    if !description.is_empty() {
+                        let captures = self.pattern.captures(description);
+
+                        if let Some(captures) = captures {
+                            let instance_name = captures.get(2).unwrap().as_str();
+
+                            if let Some(instance) = self.sku_to_instance.get_mut(instance_name) {
+                                if let Some(price_per_hour) =
+                                    price_dimensions.price_per_unit.usd.as_ref()
+                                {
+                                    // Convert the price per hour to f64 and round to 5 decimal places
+                                    instance.price_per_hour =
+                                        (price_per_hour.0 * 100000.0).round() / 100000.0;
+                                } else {
+                                    instance.price_per_hour = 0.0;
                                 }
                             }
                         }
@@ -235,24 +347,56 @@ pub async fn update_pricing_for_region(
             }
         }
     }
+}
 
-    drop(region_response);
+// Function to update pricing for a specific region (on-demand), skipping offers that have not
+// changed since they were last ingested
+pub async fn update_pricing_for_region(
+    pool: PgPool,
+    region_code: &str,
+    region_index: &RegionIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offer = InstanceOffer::new(region_code);
+
+    let version = region_offer_version(region_index, region_code)?;
+
+    let Some((offer, offer_version)) = fetch_changed_offer(
+        &pool,
+        &pricing_url(),
+        EC2_OFFER,
+        region_code,
+        version,
+        offer,
+    )
+    .await?
+    else {
+        println!("Pricing for {} is up to date.", region_code.bright_cyan());
+
+        return Ok(());
+    };
 
     // Prepare a vector to collect all pricing entries
-    let instance_entries: Vec<OnDemandInstance> = sku_to_instance.values().cloned().collect();
-    let storage_entries: Vec<Storage> = sku_to_storage.values().cloned().collect();
+    let instance_entries: Vec<OnDemandInstance> = offer.sku_to_instance.values().cloned().collect();
+    let storage_entries: Vec<Storage> = offer.storage_entries();
+    let network_service_entries: Vec<NetworkService> = offer.network_services.entries();
 
-    drop(sku_to_instance);
-    drop(sku_to_storage);
+    drop(offer);
 
     let instance_entries_len = instance_entries.len();
     let storage_entries_len = storage_entries.len();
 
     // Insert on demand pricing
-    db::insert::on_demand_pricing_in_bulk(&pool, instance_entries).await?;
+    db::insert::on_demand_pricing_in_bulk(&pool, "aws", instance_entries).await?;
 
     // Insert storage pricing
-    db::insert::storage_pricing_in_bulk(&pool, storage_entries).await?;
+    db::insert::storage_pricing_in_bulk(&pool, "aws", storage_entries).await?;
+
+    // Insert NAT gateway pricing
+    db::insert::network_services_pricing_in_bulk(&pool, "aws", network_service_entries).await?;
+
+    // Record the ingested version only once its prices are stored
+    db::offer_version::save_offer_version(&pool, "aws", EC2_OFFER, region_code, &offer_version)
+        .await?;
 
     println!(
         "Updated pricing for {} with {} instance types and {} storage types.",
@@ -263,3 +407,137 @@ pub async fn update_pricing_for_region(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::aws::offer::visit_offer_file;
+    use crate::fixture::fixture;
+    use std::io::Cursor;
+
+    fn instance_offer(path: &str) -> InstanceOffer {
+        let mut offer = InstanceOffer::new("us-east-1");
+
+        visit_offer_file(Cursor::new(fixture(path)), &mut offer).unwrap();
+
+        offer
+    }
+
+    #[test]
+    fn instances_are_priced_from_their_linux_terms() {
+        let offer = instance_offer("aws/ec2_offer.json");
+
+        assert_eq!(offer.sku_to_instance.len(), 3);
+
+        let t3_micro = &offer.sku_to_instance["t3.micro"];
+        assert_eq!(t3_micro.price_per_hour, 0.0104);
+        assert_eq!(t3_micro.vcpu_count, 2.0);
+        assert_eq!(t3_micro.memory, 1.0);
+        assert_eq!(t3_micro.arch, "x86_64");
+        assert_eq!(t3_micro.storage, "EBS only");
+
+        assert_eq!(offer.sku_to_instance["m5.large"].price_per_hour, 0.096);
+
+        let m7g_large = &offer.sku_to_instance["m7g.large"];
+        assert_eq!(m7g_large.price_per_hour, 0.0816);
+        assert_eq!(m7g_large.arch, "arm64");
+    }
+
+    #[test]
+    fn volumes_carry_their_performance_and_snapshot_prices() {
+        let offer = instance_offer("aws/ec2_offer.json");
+
+        let mut storage = offer.storage_entries();
+        storage.sort_by(|a, b| a.volume_api_name.cmp(&b.volume_api_name));
+
+        assert_eq!(storage.len(), 2);
+
+        let gp3 = &storage[0];
+        assert_eq!(gp3.volume_api_name, "gp3");
+        assert_eq!(gp3.storage_media, "SSD");
+        assert_eq!(gp3.price_per_gb_month, 0.08);
+        assert_eq!(gp3.price_per_iops_month, Some(0.005));
+        assert_eq!(gp3.price_per_mibps_month, Some(0.04));
+        assert_eq!(gp3.snapshot_price_per_gb_month, Some(0.05));
+
+        let st1 = &storage[1];
+        assert_eq!(st1.volume_api_name, "st1");
+        assert_eq!(st1.storage_media, "HDD");
+        assert_eq!(st1.price_per_gb_month, 0.045);
+        assert_eq!(st1.price_per_iops_month, None);
+        assert_eq!(st1.price_per_mibps_month, None);
+    }
+
+    #[test]
+    fn nat_gateway_prices_are_kept() {
+        let offer = instance_offer("aws/ec2_offer.json");
+
+        let mut services = offer.network_services.entries();
+        services.sort_by(|a, b| a.usage.cmp(&b.usage));
+
+        let services: Vec<(&str, &str, f32)> = services
+            .iter()
+            .map(|service| {
+                (
+                    service.service.as_str(),
+                    service.usage.as_str(),
+                    service.price_per_unit,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            services,
+            [
+                ("nat-gateway", "data-processed", 0.045),
+                ("nat-gateway", "hours", 0.045)
+            ]
+        );
+    }
+
+    #[test]
+    fn terms_listed_first_give_the_same_prices() {
+        let products_first = instance_offer("aws/ec2_offer.json");
+        let terms_first = instance_offer("aws/ec2_offer_terms_first.json");
+
+        assert_eq!(
+            terms_first.sku_to_instance.len(),
+            products_first.sku_to_instance.len()
+        );
+
+        for (name, instance) in &products_first.sku_to_instance {
+            assert_eq!(
+                terms_first.sku_to_instance[name].price_per_hour, instance.price_per_hour,
+                "{}",
+                name
+            );
+        }
+
+        let storage_prices = |offer: &InstanceOffer| {
+            let mut prices: Vec<_> = offer
+                .storage_entries()
+                .into_iter()
+                .map(|storage| {
+                    (
+                        storage.volume_api_name,
+                        storage.price_per_gb_month,
+                        storage.price_per_iops_month,
+                        storage.price_per_mibps_month,
+                        storage.snapshot_price_per_gb_month,
+                    )
+                })
+                .collect();
+            prices.sort_by(|a, b| a.0.cmp(&b.0));
+            prices
+        };
+
+        assert_eq!(
+            storage_prices(&terms_first),
+            storage_prices(&products_first)
+        );
+        assert_eq!(
+            terms_first.network_services.entries().len(),
+            products_first.network_services.entries().len()
+        );
+    }
+}
@@ -1,3 +1,7 @@
+pub mod database;
 pub mod instance;
 pub mod network;
+pub mod network_services;
+pub mod object_storage;
+pub mod offer;
 pub mod storage;
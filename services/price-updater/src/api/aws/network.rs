@@ -1,43 +1,30 @@
 use std::collections::HashMap;
 
 use colored::Colorize;
-use futures_util::StreamExt;
+use regex::Regex;
 use sqlx::PgPool;
 
+use crate::api::aws::offer::{
+    fetch_changed_offer, fetch_version_index, pricing_url, OfferHandler, DATA_TRANSFER_OFFER,
+};
 use crate::db;
-use crate::models::network::{DataTransferResponse, ExternalPrice, ExternalTier, InterRegionPrice};
-
-pub async fn update_inter_region_networking_pricing(
-    pool: PgPool,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Construct the URL for the specific region
-    let url = "https://pricing.us-east-1.amazonaws.com/offers/v1.0/aws/AWSDataTransfer/current/index.json".to_string();
-
-    // Create a client
-    let client = reqwest::Client::new();
-
-    // Send a GET request
-    let response = client.get(&url).send().await?;
-
-    // Download the content
-    let mut content = Vec::new();
-    let mut stream = response.bytes_stream();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        content.extend_from_slice(&chunk);
-    }
-
-    // Parse the JSON content
-    let network_response: DataTransferResponse = serde_json::from_slice(&content)?;
-
-    let mut inter_region_transfer_prices: HashMap<String, InterRegionPrice> = HashMap::new();
-    let mut external_transfer_prices: HashMap<String, ExternalPrice> = HashMap::new();
+use crate::models::network::{
+    ExternalPrice, ExternalTier, InterRegionPrice, OnDemandTerm, Product,
+};
+use crate::models::offer_index::OfferVersion;
+
+// Collects the inter-region and external transfer prices of the data transfer offer file
+struct DataTransferOffer {
+    region_regex: Regex,
+    inter_region_transfer_prices: HashMap<String, InterRegionPrice>,
+    external_transfer_prices: HashMap<String, ExternalPrice>,
+}
 
-    // regex to only match primary aws regions (not wavelength, local zones, etc.)
-    let region_regex = regex::Regex::new(r"^[a-z]{2}-[a-z]+-\d$").unwrap();
+impl OfferHandler for DataTransferOffer {
+    type Product = Product;
+    type Term = OnDemandTerm;
 
-    for (sku, product) in network_response.products {
+    fn product(&mut self, sku: String, product: Product) {
         if let Some(attributes) = product.attributes {
             if let Some(transfer_type) = attributes.transfer_type {
                 if transfer_type == "InterRegion Outbound" {
@@ -47,7 +34,7 @@ pub async fn update_inter_region_networking_pricing(
                     if attributes.from_location_type == Some("AWS Region".to_string())
                         && attributes.to_location_type == Some("AWS Region".to_string())
                     {
-                        inter_region_transfer_prices.insert(
+                        self.inter_region_transfer_prices.insert(
                             sku,
                             InterRegionPrice {
                                 price_per_gb: 0.0,
@@ -59,9 +46,11 @@ pub async fn update_inter_region_networking_pricing(
                 } else if transfer_type == "AWS Outbound"
                     && attributes.from_location_type == Some("AWS Region".to_string())
                     && attributes.to_location == Some("External".to_string())
-                    && region_regex.is_match(attributes.from_region_code.as_ref().unwrap())
+                    && self
+                        .region_regex
+                        .is_match(attributes.from_region_code.as_ref().unwrap())
                 {
-                    external_transfer_prices.insert(
+                    self.external_transfer_prices.insert(
                         sku,
                         ExternalPrice {
                             from_region_code: attributes.from_region_code.unwrap(),
@@ -73,54 +62,105 @@ pub async fn update_inter_region_networking_pricing(
         }
     }
 
-    for (sku, term_map) in network_response.terms.on_demand {
+    fn indexes(&self, sku: &str) -> bool {
+        self.inter_region_transfer_prices.contains_key(sku)
+            || self.external_transfer_prices.contains_key(sku)
+    }
+
+    fn term(&mut self, sku: String, term_map: HashMap<String, OnDemandTerm>) {
         for (_, term) in term_map {
-            if inter_region_transfer_prices.contains_key(&sku) {
+            if let Some(inter_region_price) = self.inter_region_transfer_prices.get_mut(&sku) {
                 for (_, dimension) in term.price_dimensions {
                     if let Some(price) = dimension.price_per_unit.usd {
-                        let rounded_price = (price.0 * 1000.0).round() / 1000.0;
-
-                        if let Some(inter_region_price) = inter_region_transfer_prices.get_mut(&sku)
-                        {
-                            inter_region_price.price_per_gb = rounded_price;
-                        }
+                        inter_region_price.price_per_gb = (price.0 * 1000.0).round() / 1000.0;
                     }
                 }
-            } else if external_transfer_prices.contains_key(&sku) {
+            } else if let Some(external_transfer_price) =
+                self.external_transfer_prices.get_mut(&sku)
+            {
                 for (_, dimension) in term.price_dimensions {
                     if let Some(price) = dimension.price_per_unit.usd {
-                        let rounded_price = (price.0 * 1000.0).round() / 1000.0;
-
-                        if let Some(external_transfer_price) =
-                            external_transfer_prices.get_mut(&sku)
-                        {
-                            external_transfer_price.tiers.push(ExternalTier {
-                                price_per_gb: rounded_price,
-                                start_range: dimension.begin_range.unwrap().0,
-                                end_range: dimension.end_range.unwrap().0,
-                            });
-                        }
+                        external_transfer_price.tiers.push(ExternalTier {
+                            price_per_gb: (price.0 * 1000.0).round() / 1000.0,
+                            start_range: dimension.begin_range.unwrap().0,
+                            end_range: dimension.end_range.unwrap().0,
+                        });
                     }
                 }
 
-                if let Some(external_transfer_price) = external_transfer_prices.get_mut(&sku) {
-                    external_transfer_price.tiers.sort_by(|a, b| {
-                        a.start_range
-                            .cmp(&b.start_range)
-                            .then_with(|| a.end_range.cmp(&b.end_range))
-                    });
-                }
+                external_transfer_price.tiers.sort_by(|a, b| {
+                    a.start_range
+                        .cmp(&b.start_range)
+                        .then_with(|| a.end_range.cmp(&b.end_range))
+                });
             }
         }
     }
+}
 
-    drop(content);
+// Update inter-region and external transfer pricing, skipping the offer if it has not changed
+// since it was last ingested
+pub async fn update_inter_region_networking_pricing(
+    pool: PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let version_index = fetch_version_index(DATA_TRANSFER_OFFER).await?;
+
+    // recorded by the url of its file, like the regional offers
+    let current_version = version_index
+        .versions
+        .get(&version_index.current_version)
+        .ok_or("Current version missing from the version index")?;
+
+    let offer = DataTransferOffer {
+        // regex to only match primary aws regions (not wavelength, local zones, etc.)
+        region_regex: Regex::new(r"^[a-z]{2}-[a-z]+-\d$").unwrap(),
+        inter_region_transfer_prices: HashMap::new(),
+        external_transfer_prices: HashMap::new(),
+    };
+
+    // The data transfer offer is not split by region
+    let Some((offer, offer_version)) = fetch_changed_offer(
+        &pool,
+        &pricing_url(),
+        DATA_TRANSFER_OFFER,
+        "global",
+        OfferVersion {
+            version: current_version.offer_version_url.clone(),
+            publication_date: version_index.publication_date,
+            etag: None,
+            last_modified: None,
+        },
+        offer,
+    )
+    .await?
+    else {
+        println!("Network pricing is up to date.");
+
+        return Ok(());
+    };
+
+    let DataTransferOffer {
+        inter_region_transfer_prices,
+        external_transfer_prices,
+        ..
+    } = offer;
 
     let inter_region_transfer_prices_len = inter_region_transfer_prices.len();
     let external_transfer_prices_len = external_transfer_prices.len();
 
-    db::insert::inter_region_data_transfer_in_bulk(&pool, inter_region_transfer_prices).await?;
-    db::insert::external_data_transfer_in_bulk(&pool, external_transfer_prices).await?;
+    db::insert::inter_region_data_transfer_in_bulk(&pool, "aws", inter_region_transfer_prices)
+        .await?;
+    db::insert::external_data_transfer_in_bulk(&pool, "aws", external_transfer_prices).await?;
+
+    // Record the ingested version only once its prices are stored
+    db::offer_version::save_offer_version(
+        &pool,
+        "aws",
+        DATA_TRANSFER_OFFER,
+        "global",
+        &offer_version,
+    )
+    .await?;
 
     println!(
         "Updated inter-region pricing for {} routes.",
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+
+use crate::api::aws::offer::{
+    fetch_changed_offer, matches_usage, pricing_url, region_offer_version, OfferHandler,
+};
+use crate::constants::network_services::AWS_NETWORK_SERVICES;
+use crate::db;
+use crate::models::network_service::NetworkService;
+use crate::models::offer_index::RegionIndex;
+use crate::models::on_demand_pricing::{OnDemandTerms, Product};
+
+// Prices of the load balancers, NAT gateways and public addresses listed in an offer file.
+// NAT gateways are part of the EC2 offer, so the instance offer embeds these too
+pub struct NetworkServicePrices {
+    region_code: String,
+    // Map of the sku id -> network service
+    sku_to_service: HashMap<String, NetworkService>,
+}
+
+impl NetworkServicePrices {
+    pub fn new(region_code: &str) -> Self {
+        Self {
+            region_code: region_code.to_string(),
+            sku_to_service: HashMap::new(),
+        }
+    }
+
+    // Keep the product if it is a network service, returns whether it was kept
+    pub fn product(&mut self, sku: &str, product: &Product) -> bool {
+        let usagetype = match product
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.usagetype.as_deref())
+        {
+            Some(usagetype) => usagetype,
+            None => return false,
+        };
+
+        let service = AWS_NETWORK_SERVICES
+            .iter()
+            .find(|(product_family, usage, _, _)| {
+                product_family.is_none_or(|product_family| product_family == product.product_family)
+                    && matches_usage(usagetype, usage)
+            });
+
+        match service {
+            Some((_, _, service, usage)) => {
+                self.sku_to_service.insert(
+                    sku.to_string(),
+                    NetworkService {
+                        region: self.region_code.clone(),
+                        service: service.to_string(),
+                        usage: usage.to_string(),
+                        unit: String::new(),
+                        price_per_unit: 0.0,
+                    },
+                );
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, sku: &str) -> bool {
+        self.sku_to_service.contains_key(sku)
+    }
+
+    pub fn term(&mut self, sku: &str, terms: &HashMap<String, OnDemandTerms>) {
+        let service = match self.sku_to_service.get_mut(sku) {
+            Some(service) => service,
+            None => return,
+        };
+
+        for term in terms.values() {
+            for dimension in term.price_dimensions.values() {
+                // keep the price of the first tier
+                if dimension
+                    .begin_range
+                    .as_ref()
+                    .is_some_and(|begin_range| begin_range.0 != 0.0)
+                {
+                    continue;
+                }
+
+                if let Some(price) = dimension.price_per_unit.usd.as_ref() {
+                    service.price_per_unit = price.0;
+                    service.unit = dimension.unit.clone().unwrap_or_default();
+                }
+            }
+        }
+    }
+
+    // One entry per service and usage, a few are listed under several skus
+    pub fn entries(&self) -> Vec<NetworkService> {
+        let mut entries: HashMap<(&str, &str), NetworkService> = HashMap::new();
+
+        for service in self.sku_to_service.values() {
+            if service.price_per_unit == 0.0 {
+                continue;
+            }
+
+            entries.insert(
+                (service.service.as_str(), service.usage.as_str()),
+                service.clone(),
+            );
+        }
+
+        entries.into_values().collect()
+    }
+}
+
+// Collects the network service prices of a regional ELB or VPC offer file
+struct NetworkServicesOffer {
+    prices: NetworkServicePrices,
+}
+
+impl OfferHandler for NetworkServicesOffer {
+    type Product = Product;
+    type Term = OnDemandTerms;
+
+    fn product(&mut self, sku: String, product: Product) {
+        self.prices.product(&sku, &product);
+    }
+
+    fn indexes(&self, sku: &str) -> bool {
+        self.prices.contains(sku)
+    }
+
+    fn term(&mut self, sku: String, terms: HashMap<String, OnDemandTerms>) {
+        self.prices.term(&sku, &terms);
+    }
+}
+
+// Function to update the network services of an offer for a specific region
+pub async fn update_network_services_pricing_for_region(
+    pool: PgPool,
+    service: &str,
+    region_code: &str,
+    region_index: &RegionIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offer = NetworkServicesOffer {
+        prices: NetworkServicePrices::new(region_code),
+    };
+
+    let version = region_offer_version(region_index, region_code)?;
+
+    let Some((offer, offer_version)) =
+        fetch_changed_offer(&pool, &pricing_url(), service, region_code, version, offer).await?
+    else {
+        println!(
+            "{} pricing for {} is up to date.",
+            service,
+            region_code.bright_cyan()
+        );
+
+        return Ok(());
+    };
+
+    let entries = offer.prices.entries();
+
+    drop(offer);
+
+    let entries_len = entries.len();
+
+    db::insert::network_services_pricing_in_bulk(&pool, "aws", entries).await?;
+
+    // Record the ingested version only once its prices are stored
+    db::offer_version::save_offer_version(&pool, "aws", service, region_code, &offer_version)
+        .await?;
+
+    println!(
+        "Updated {} pricing for {} with {} network services.",
+        service,
+        region_code.bright_cyan(),
+        entries_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+
+use crate::api::aws::offer::{
+    fetch_changed_offer, matches_usage, pricing_url, region_offer_version, OfferHandler, S3_OFFER,
+};
+use crate::constants::storage_classes::S3_STORAGE_CLASSES;
+use crate::db;
+use crate::models::object_storage::ObjectStorage;
+use crate::models::offer_index::RegionIndex;
+use crate::models::on_demand_pricing::{OnDemandTerms, Product};
+
+// What an S3 sku is priced for
+#[derive(Clone, Copy)]
+enum ObjectUsage {
+    Storage,
+    PutRequests,
+    GetRequests,
+    Retrieval,
+}
+
+// Collects the storage, request and retrieval prices of a regional S3 offer file
+struct ObjectStorageOffer {
+    region_code: String,
+    // Map of the sku id -> storage class and usage
+    sku_to_usage: HashMap<String, (&'static str, ObjectUsage)>,
+    // Map of the storage class -> prices
+    class_to_storage: HashMap<&'static str, ObjectStorage>,
+}
+
+impl OfferHandler for ObjectStorageOffer {
+    type Product = Product;
+    type Term = OnDemandTerms;
+
+    fn product(&mut self, sku: String, product: Product) {
+        let usagetype = match product
+            .attributes
+            .and_then(|attributes| attributes.usagetype)
+        {
+            Some(usagetype) => usagetype,
+            None => return,
+        };
+
+        for (storage_class, storage, put_requests, get_requests, retrieval) in
+            S3_STORAGE_CLASSES.iter()
+        {
+            let usage = if matches_usage(&usagetype, storage) {
+                ObjectUsage::Storage
+            } else if matches_usage(&usagetype, put_requests) {
+                ObjectUsage::PutRequests
+            } else if matches_usage(&usagetype, get_requests) {
+                ObjectUsage::GetRequests
+            } else if retrieval.is_some_and(|retrieval| matches_usage(&usagetype, retrieval)) {
+                ObjectUsage::Retrieval
+            } else {
+                continue;
+            };
+
+            self.sku_to_usage.insert(sku, (storage_class, usage));
+
+            return;
+        }
+    }
+
+    fn indexes(&self, sku: &str) -> bool {
+        self.sku_to_usage.contains_key(sku)
+    }
+
+    fn term(&mut self, sku: String, terms: HashMap<String, OnDemandTerms>) {
+        let (storage_class, usage) = self.sku_to_usage[&sku];
+
+        let storage = self
+            .class_to_storage
+            .entry(storage_class)
+            .or_insert_with(|| ObjectStorage {
+                region: self.region_code.clone(),
+                storage_class: storage_class.to_string(),
+                price_per_gb_month: 0.0,
+                price_per_1k_put_requests: 0.0,
+                price_per_1k_get_requests: 0.0,
+                retrieval_price_per_gb: 0.0,
+            });
+
+        for term in terms.values() {
+            for dimension in term.price_dimensions.values() {
+                // storage is tiered by volume, keep the price of the first tier
+                if dimension
+                    .begin_range
+                    .as_ref()
+                    .is_some_and(|begin_range| begin_range.0 != 0.0)
+                {
+                    continue;
+                }
+
+                let price = match dimension.price_per_unit.usd.as_ref() {
+                    Some(price) => price.0,
+                    None => continue,
+                };
+
+                match usage {
+                    ObjectUsage::Storage => storage.price_per_gb_month = price,
+                    ObjectUsage::PutRequests => storage.price_per_1k_put_requests = price * 1000.0,
+                    ObjectUsage::GetRequests => storage.price_per_1k_get_requests = price * 1000.0,
+                    ObjectUsage::Retrieval => storage.retrieval_price_per_gb = price,
+                }
+            }
+        }
+    }
+}
+
+// Function to update object storage pricing for a specific region
+pub async fn update_object_storage_pricing_for_region(
+    pool: PgPool,
+    region_code: &str,
+    region_index: &RegionIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offer = ObjectStorageOffer {
+        region_code: region_code.to_string(),
+        sku_to_usage: HashMap::new(),
+        class_to_storage: HashMap::new(),
+    };
+
+    let version = region_offer_version(region_index, region_code)?;
+
+    let Some((offer, offer_version)) =
+        fetch_changed_offer(&pool, &pricing_url(), S3_OFFER, region_code, version, offer).await?
+    else {
+        println!(
+            "Object storage pricing for {} is up to date.",
+            region_code.bright_cyan()
+        );
+
+        return Ok(());
+    };
+
+    let storage_entries: Vec<ObjectStorage> = offer.class_to_storage.into_values().collect();
+
+    let storage_entries_len = storage_entries.len();
+
+    db::insert::object_storage_pricing_in_bulk(&pool, "aws", storage_entries).await?;
+
+    // Record the ingested version only once its prices are stored
+    db::offer_version::save_offer_version(&pool, "aws", S3_OFFER, region_code, &offer_version)
+        .await?;
+
+    println!(
+        "Updated object storage pricing for {} with {} storage classes.",
+        region_code.bright_cyan(),
+        storage_entries_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,795 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Cursor, Read, Seek};
+
+use futures_util::StreamExt;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Response, StatusCode};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor,
+};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+
+use crate::db;
+use crate::models::offer_index::{OfferVersion, RegionIndex, VersionIndex};
+
+pub const EC2_OFFER: &str = "AmazonEC2";
+pub const DATA_TRANSFER_OFFER: &str = "AWSDataTransfer";
+pub const S3_OFFER: &str = "AmazonS3";
+pub const RDS_OFFER: &str = "AmazonRDS";
+pub const ELB_OFFER: &str = "AWSELB";
+pub const VPC_OFFER: &str = "AmazonVPC";
+
+const DEFAULT_PRICING_URL: &str = "https://pricing.us-east-1.amazonaws.com";
+
+// Number of downloaded chunks that may be queued ahead of the parser
+const CHUNK_BUFFER: usize = 16;
+
+// What a pass over an offer file left to read
+#[derive(Debug, PartialEq)]
+pub enum OfferPass {
+    Complete,
+    // the terms came before the products, so are still to be read
+    TermsSkipped,
+}
+
+// Result of a conditional offer file download
+pub enum OfferDownload<H> {
+    NotModified,
+    Modified {
+        handler: H,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+// Receives the products and on-demand terms of an offer file as they are parsed
+pub trait OfferHandler {
+    type Product: DeserializeOwned;
+    type Term: DeserializeOwned;
+
+    fn product(&mut self, sku: String, product: Self::Product);
+
+    // Terms for SKUs that are not indexed are skipped without being deserialized. Terms listed
+    // before the products are read in a second pass over the file, once the products are known
+    fn indexes(&self, sku: &str) -> bool;
+
+    fn term(&mut self, sku: String, terms: HashMap<String, Self::Term>);
+}
+
+// Blocking reader over the chunks forwarded by the download task
+pub struct OfferReader {
+    receiver: mpsc::Receiver<io::Result<Vec<u8>>>,
+    chunk: Cursor<Vec<u8>>,
+}
+
+impl Read for OfferReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.chunk.read(buf)?;
+
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+
+            match self.receiver.blocking_recv() {
+                Some(chunk) => self.chunk = Cursor::new(chunk?),
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+// Base URL of the price list, overridable to run the updater against a mirror
+pub fn pricing_url() -> String {
+    std::env::var("AWS_PRICING_URL").unwrap_or_else(|_| DEFAULT_PRICING_URL.to_string())
+}
+
+// Fetch the index of the current regional offer files of a service
+pub async fn fetch_region_index(service: &str) -> Result<RegionIndex, Box<dyn std::error::Error>> {
+    let url = format!(
+        "{}/offers/v1.0/aws/{}/current/region_index.json",
+        pricing_url(),
+        service
+    );
+
+    Ok(reqwest::get(url).await?.error_for_status()?.json().await?)
+}
+
+// Fetch the index of the published versions of a service
+pub async fn fetch_version_index(
+    service: &str,
+) -> Result<VersionIndex, Box<dyn std::error::Error>> {
+    let url = format!("{}/offers/v1.0/aws/{}/index.json", pricing_url(), service);
+
+    Ok(reqwest::get(url).await?.error_for_status()?.json().await?)
+}
+
+fn header(response: &Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+// Download an offer file and feed it to the handler while it is still downloading,
+// so that at most a few chunks of the file are held in memory at any time.
+// The validators of the previously ingested version make the request conditional.
+pub async fn stream_offer_file<H>(
+    url: &str,
+    previous: Option<&OfferVersion>,
+    handler: H,
+) -> Result<OfferDownload<H>, Box<dyn std::error::Error>>
+where
+    H: OfferHandler + Send + 'static,
+{
+    let client = reqwest::Client::new();
+
+    let mut request = client.get(url);
+
+    if let Some(previous) = previous {
+        if let Some(etag) = &previous.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        if let Some(last_modified) = &previous.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(OfferDownload::NotModified);
+    }
+
+    let response = response.error_for_status()?;
+
+    let etag = header(&response, ETAG);
+    let last_modified = header(&response, LAST_MODIFIED);
+
+    let (handler, pass) = stream_into(response, handler, visit_offer).await?;
+
+    // the terms came first, they are read from a second download now that the products are known
+    let handler = match pass {
+        OfferPass::Complete => handler,
+        OfferPass::TermsSkipped => {
+            let response = client.get(url).send().await?.error_for_status()?;
+
+            stream_into(response, handler, visit_offer_terms).await?.0
+        }
+    };
+
+    Ok(OfferDownload::Modified {
+        handler,
+        etag,
+        last_modified,
+    })
+}
+
+// Feed a downloading offer file to a visit of it, returning the handler and what the visit
+// returned
+async fn stream_into<H, T>(
+    response: Response,
+    handler: H,
+    visit: fn(OfferReader, &mut H) -> serde_json::Result<T>,
+) -> Result<(H, T), Box<dyn std::error::Error>>
+where
+    H: OfferHandler + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(CHUNK_BUFFER);
+
+    let parser = tokio::task::spawn_blocking(move || {
+        let mut handler = handler;
+
+        let reader = OfferReader {
+            receiver,
+            chunk: Cursor::new(Vec::new()),
+        };
+
+        visit(reader, &mut handler).map(|visited| (handler, visited))
+    });
+
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map(|chunk| chunk.to_vec()).map_err(io::Error::other);
+
+        // The parser hangs up once it fails, there is no point in downloading the rest
+        if sender.send(chunk).await.is_err() {
+            break;
+        }
+    }
+
+    drop(sender);
+
+    Ok(parser.await??)
+}
+
+// Download and parse an offer unless its version was already ingested. The file is fetched
+// from the version itself under pricing_url rather than the current one, which may have moved on
+// since the index was read. Returns the handler together with the version to record once its prices are stored,
+// or None if nothing changed.
+pub async fn fetch_changed_offer<H>(
+    pool: &PgPool,
+    pricing_url: &str,
+    service: &str,
+    region: &str,
+    version: OfferVersion,
+    handler: H,
+) -> Result<Option<(H, OfferVersion)>, Box<dyn std::error::Error>>
+where
+    H: OfferHandler + Send + 'static,
+{
+    let previous = db::offer_version::fetch_offer_version(pool, "aws", service, region).await?;
+
+    if previous
+        .as_ref()
+        .is_some_and(|previous| previous.version == version.version)
+    {
+        return Ok(None);
+    }
+
+    // Parse the offer file while it downloads
+    let download = stream_offer_file(
+        &offer_file_url(pricing_url, &version),
+        previous.as_ref(),
+        handler,
+    )
+    .await?;
+
+    match download {
+        OfferDownload::Modified {
+            handler,
+            etag,
+            last_modified,
+        } => Ok(Some((
+            handler,
+            OfferVersion {
+                etag,
+                last_modified,
+                ..version
+            },
+        ))),
+        OfferDownload::NotModified => {
+            let previous = previous.unwrap();
+
+            db::offer_version::save_offer_version(
+                pool,
+                "aws",
+                service,
+                region,
+                &OfferVersion {
+                    etag: previous.etag,
+                    last_modified: previous.last_modified,
+                    ..version
+                },
+            )
+            .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+// Version of a regional offer as listed in the region index
+pub fn region_offer_version(
+    region_index: &RegionIndex,
+    region_code: &str,
+) -> Result<OfferVersion, Box<dyn std::error::Error>> {
+    let region_offer = region_index
+        .regions
+        .get(region_code)
+        .ok_or("Region missing from the offer index")?;
+
+    Ok(OfferVersion {
+        version: region_offer.current_version_url.clone(),
+        publication_date: region_index.publication_date.clone(),
+        etag: None,
+        last_modified: None,
+    })
+}
+
+// Usage types are prefixed with the region, except in us-east-1 (USE2-Requests-Tier1)
+pub fn matches_usage(usagetype: &str, usage: &str) -> bool {
+    usagetype == usage || usagetype.ends_with(&format!("-{}", usage))
+}
+
+// URL of the offer file of a version, which is the path listed in the offer index, e.g.
+// /offers/v1.0/aws/AmazonEC2/20240311000000/us-east-1/index.json
+pub fn offer_file_url(pricing_url: &str, version: &OfferVersion) -> String {
+    format!("{}{}", pricing_url, version.version)
+}
+
+// Walk an offer file, passing every product and on-demand term to the handler one at a time.
+// Terms listed before the products are skipped, to be read by visit_offer_terms
+pub fn visit_offer<R: Read, H: OfferHandler>(
+    reader: R,
+    handler: &mut H,
+) -> serde_json::Result<OfferPass> {
+    visit(reader, handler, Pass::First)
+}
+
+// Second pass over an offer file, passing on the terms skipped by the first
+pub fn visit_offer_terms<R: Read, H: OfferHandler>(
+    reader: R,
+    handler: &mut H,
+) -> serde_json::Result<()> {
+    visit(reader, handler, Pass::Terms).map(|_| ())
+}
+
+// Walk an offer file that can be read again, in two passes if its terms come first
+pub fn visit_offer_file<R: Read + Seek, H: OfferHandler>(
+    mut reader: R,
+    handler: &mut H,
+) -> serde_json::Result<()> {
+    if visit_offer(&mut reader, handler)? == OfferPass::TermsSkipped {
+        reader.rewind().map_err(serde_json::Error::io)?;
+
+        visit_offer_terms(reader, handler)?;
+    }
+
+    Ok(())
+}
+
+fn visit<R: Read, H: OfferHandler>(
+    reader: R,
+    handler: &mut H,
+    pass: Pass,
+) -> serde_json::Result<OfferPass> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+
+    let visited = OfferSeed { handler, pass }.deserialize(&mut deserializer)?;
+
+    deserializer.end()?;
+
+    Ok(visited)
+}
+
+// Which part of an offer file a pass over it reads
+#[derive(Clone, Copy, PartialEq)]
+enum Pass {
+    // the products, and the terms if they come after them
+    First,
+    Terms,
+}
+
+struct OfferSeed<'a, H> {
+    handler: &'a mut H,
+    pass: Pass,
+}
+
+impl<'de, 'a, H: OfferHandler> DeserializeSeed<'de> for OfferSeed<'a, H> {
+    type Value = OfferPass;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<OfferPass, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, H: OfferHandler> Visitor<'de> for OfferSeed<'a, H> {
+    type Value = OfferPass;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an offer file")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OfferPass, A::Error> {
+        // AWS lists the products first. Terms listed before them can't be told apart yet, and
+        // holding on to all of them would cost as much memory as the file, so they are skipped
+        let mut products_read = false;
+        let mut terms_skipped = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match (key.as_str(), self.pass) {
+                ("products", Pass::First) => {
+                    map.next_value_seed(ProductsSeed {
+                        handler: &mut *self.handler,
+                    })?;
+
+                    products_read = true;
+                }
+                ("terms", Pass::First) if !products_read => {
+                    map.next_value::<IgnoredAny>()?;
+
+                    terms_skipped = true;
+                }
+                ("terms", _) => map.next_value_seed(TermsSeed {
+                    handler: &mut *self.handler,
+                })?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        // the terms would otherwise be dropped without a price being read
+        if self.pass == Pass::First && !products_read {
+            return Err(de::Error::missing_field("products"));
+        }
+
+        Ok(if terms_skipped {
+            OfferPass::TermsSkipped
+        } else {
+            OfferPass::Complete
+        })
+    }
+}
+
+struct ProductsSeed<'a, H> {
+    handler: &'a mut H,
+}
+
+impl<'de, 'a, H: OfferHandler> DeserializeSeed<'de> for ProductsSeed<'a, H> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, H: OfferHandler> Visitor<'de> for ProductsSeed<'a, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of products")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some((sku, product)) = map.next_entry::<String, H::Product>()? {
+            self.handler.product(sku, product);
+        }
+
+        Ok(())
+    }
+}
+
+struct TermsSeed<'a, H> {
+    handler: &'a mut H,
+}
+
+impl<'de, 'a, H: OfferHandler> DeserializeSeed<'de> for TermsSeed<'a, H> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, H: OfferHandler> Visitor<'de> for TermsSeed<'a, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of term types")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "OnDemand" {
+                map.next_value_seed(OnDemandSeed {
+                    handler: &mut *self.handler,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct OnDemandSeed<'a, H> {
+    handler: &'a mut H,
+}
+
+impl<'de, 'a, H: OfferHandler> DeserializeSeed<'de> for OnDemandSeed<'a, H> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, H: OfferHandler> Visitor<'de> for OnDemandSeed<'a, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of on-demand terms")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(sku) = map.next_key::<String>()? {
+            if self.handler.indexes(&sku) {
+                let terms = map.next_value::<HashMap<String, H::Term>>()?;
+                self.handler.term(sku, terms);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use crate::models::on_demand_pricing::{OnDemandTerms, Product};
+    use wiremock::matchers::{header, header_exists, headers, method, path, HeaderExactMatcher};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const VERSION: &str = "/offers/v1.0/aws/AmazonEC2/20240311000000/us-east-1/index.json";
+    const ETAG: &str = "\"8c4a1f0e2b7d9c3a\"";
+    const LAST_MODIFIED: &str = "Mon, 11 Mar 2024 09:48:03 GMT";
+
+    // Counts the products of an offer without reading them
+    #[derive(Default)]
+    struct ProductCount(usize);
+
+    impl OfferHandler for ProductCount {
+        type Product = IgnoredAny;
+        type Term = IgnoredAny;
+
+        fn product(&mut self, _: String, _: IgnoredAny) {
+            self.0 += 1;
+        }
+
+        fn indexes(&self, _: &str) -> bool {
+            false
+        }
+
+        fn term(&mut self, _: String, _: HashMap<String, IgnoredAny>) {}
+    }
+
+    fn offer_version(etag: Option<&str>, last_modified: Option<&str>) -> OfferVersion {
+        OfferVersion {
+            version: VERSION.to_string(),
+            publication_date: "2024-03-11T00:00:00Z".to_string(),
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+        }
+    }
+
+    // wiremock splits header values on commas, which http dates contain
+    fn if_modified_since() -> HeaderExactMatcher {
+        headers("If-Modified-Since", LAST_MODIFIED.split(", ").collect())
+    }
+
+    fn changed_offer() -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .insert_header("ETag", ETAG)
+            .insert_header("Last-Modified", LAST_MODIFIED)
+            .set_body_string(fixture("aws/ec2_offer.json"))
+    }
+
+    #[tokio::test]
+    async fn changed_offer_is_parsed_with_its_validators() {
+        let server = MockServer::start().await;
+
+        // nothing was ingested yet, so the request is not conditional
+        Mock::given(header_exists("If-None-Match"))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(VERSION))
+            .respond_with(changed_offer())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}{}", server.uri(), VERSION);
+
+        let OfferDownload::Modified {
+            handler,
+            etag,
+            last_modified,
+        } = stream_offer_file(&url, None, ProductCount::default())
+            .await
+            .unwrap()
+        else {
+            panic!("the offer was not downloaded");
+        };
+
+        assert_eq!(handler.0, 12);
+        assert_eq!(etag.as_deref(), Some(ETAG));
+        assert_eq!(last_modified.as_deref(), Some(LAST_MODIFIED));
+    }
+
+    #[tokio::test]
+    async fn offer_matching_the_etag_is_not_downloaded_again() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(VERSION))
+            .and(header("If-None-Match", ETAG))
+            .and(if_modified_since())
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}{}", server.uri(), VERSION);
+        let previous = offer_version(Some(ETAG), Some(LAST_MODIFIED));
+
+        let download = stream_offer_file(&url, Some(&previous), ProductCount::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(download, OfferDownload::NotModified));
+    }
+
+    #[tokio::test]
+    async fn offer_not_modified_since_the_last_download_is_not_downloaded_again() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(VERSION))
+            .and(if_modified_since())
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}{}", server.uri(), VERSION);
+        let previous = offer_version(None, Some(LAST_MODIFIED));
+
+        let download = stream_offer_file(&url, Some(&previous), ProductCount::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(download, OfferDownload::NotModified));
+    }
+
+    // Keeps the on-demand terms of the instances, like the instance offer does
+    #[derive(Default)]
+    struct InstanceTerms {
+        instances: Vec<String>,
+        terms: HashMap<String, HashMap<String, OnDemandTerms>>,
+    }
+
+    impl OfferHandler for InstanceTerms {
+        type Product = Product;
+        type Term = OnDemandTerms;
+
+        fn product(&mut self, sku: String, product: Product) {
+            if product.product_family == "Compute Instance" {
+                self.instances.push(sku);
+            }
+        }
+
+        fn indexes(&self, sku: &str) -> bool {
+            self.instances.iter().any(|instance| instance == sku)
+        }
+
+        fn term(&mut self, sku: String, terms: HashMap<String, OnDemandTerms>) {
+            assert!(
+                self.terms.insert(sku.clone(), terms).is_none(),
+                "terms of {} were passed on twice",
+                sku
+            );
+        }
+    }
+
+    fn visit(offer: &str) -> serde_json::Result<InstanceTerms> {
+        let mut handler = InstanceTerms::default();
+
+        visit_offer_file(Cursor::new(offer), &mut handler).map(|_| handler)
+    }
+
+    fn term_skus(handler: &InstanceTerms) -> Vec<&str> {
+        let mut skus: Vec<&str> = handler.terms.keys().map(String::as_str).collect();
+        skus.sort();
+        skus
+    }
+
+    #[test]
+    fn terms_of_indexed_products_are_passed_on() {
+        let handler = visit(&fixture("aws/ec2_offer.json")).unwrap();
+
+        assert_eq!(
+            term_skus(&handler),
+            ["4C7N4APU9G", "6QCMYABX3D", "P4Z2PAUJMW", "QCCE8M5H7T"]
+        );
+
+        // only the on-demand terms, not the reserved ones
+        let terms: Vec<&String> = handler.terms["6QCMYABX3D"].keys().collect();
+        assert_eq!(terms, ["6QCMYABX3D.JRTCKXETXF"]);
+    }
+
+    #[test]
+    fn terms_listed_before_the_products_are_passed_on() {
+        let products_first = visit(&fixture("aws/ec2_offer.json")).unwrap();
+        let terms_first = visit(&fixture("aws/ec2_offer_terms_first.json")).unwrap();
+
+        assert_eq!(term_skus(&terms_first), term_skus(&products_first));
+    }
+
+    #[test]
+    fn terms_listed_before_the_products_are_left_for_a_second_pass() {
+        let offer = fixture("aws/ec2_offer_terms_first.json");
+        let mut handler = InstanceTerms::default();
+
+        let pass = visit_offer(offer.as_bytes(), &mut handler).unwrap();
+
+        assert_eq!(pass, OfferPass::TermsSkipped);
+        assert_eq!(handler.instances.len(), 4);
+        assert!(handler.terms.is_empty());
+
+        visit_offer_terms(offer.as_bytes(), &mut handler).unwrap();
+
+        assert_eq!(
+            term_skus(&handler),
+            ["4C7N4APU9G", "6QCMYABX3D", "P4Z2PAUJMW", "QCCE8M5H7T"]
+        );
+    }
+
+    #[tokio::test]
+    async fn offer_listing_the_terms_first_is_downloaded_twice() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(VERSION))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", ETAG)
+                    .set_body_string(fixture("aws/ec2_offer_terms_first.json")),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}{}", server.uri(), VERSION);
+
+        let OfferDownload::Modified { handler, etag, .. } =
+            stream_offer_file(&url, None, InstanceTerms::default())
+                .await
+                .unwrap()
+        else {
+            panic!("offer was not downloaded");
+        };
+
+        assert_eq!(etag.as_deref(), Some(ETAG));
+        assert_eq!(
+            term_skus(&handler),
+            ["4C7N4APU9G", "6QCMYABX3D", "P4Z2PAUJMW", "QCCE8M5H7T"]
+        );
+    }
+
+    #[test]
+    fn offer_without_products_is_an_error() {
+        let error = visit(r#"{ "terms": { "OnDemand": {} } }"#)
+            .err()
+            .expect("an offer without products was accepted");
+
+        assert!(error.to_string().contains("missing field `products`"));
+    }
+
+    #[tokio::test]
+    async fn offer_is_downloaded_from_its_version() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(VERSION))
+            .respond_with(changed_offer())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = offer_file_url(&server.uri(), &offer_version(None, None));
+
+        assert_eq!(url, format!("{}{}", server.uri(), VERSION));
+
+        let download = stream_offer_file(&url, None, ProductCount::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(download, OfferDownload::Modified { .. }));
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+use types::region::AzureRegion;
+
+use crate::api::azure::retail_prices::{fetch_prices, RETAIL_PRICES_URL};
+use crate::constants::machine_types::{AZURE_DISK_TYPES, AZURE_VM_SIZES};
+use crate::db;
+use crate::models::on_demand_pricing::OnDemandInstance;
+use crate::models::retail_prices::RetailPrice;
+use crate::models::spot_pricing::SpotInstance;
+use crate::models::storage::Storage;
+
+// Vm size, spot and managed disk prices of a region read from the retail prices
+pub struct ComputePrices {
+    pub instances: Vec<OnDemandInstance>,
+    pub spot: Vec<SpotInstance>,
+    pub storage: Vec<Storage>,
+}
+
+// Function to price the vm sizes and disks we index from the retail prices of a region
+pub fn compute_prices(
+    region_code: &str,
+    vm_prices: &[RetailPrice],
+    disk_prices: &[RetailPrice],
+) -> ComputePrices {
+    // Create a map of the vm size -> on-demand instance
+    let mut size_to_instance: HashMap<String, OnDemandInstance> = HashMap::new();
+    // Create a map of the vm size -> spot instance
+    let mut size_to_spot: HashMap<String, SpotInstance> = HashMap::new();
+
+    for price in vm_prices.iter() {
+        // only linux, pay-as-you-go hourly prices
+        if price.r#type != "Consumption"
+            || price.unit_of_measure != "1 Hour"
+            || price.product_name.contains("Windows")
+            || price.sku_name.contains("Low Priority")
+        {
+            continue;
+        }
+
+        let Some((size, vcpu_count, memory)) = AZURE_VM_SIZES
+            .iter()
+            .find(|(size, _, _)| *size == price.arm_sku_name)
+        else {
+            continue;
+        };
+
+        // Round to 5 decimal places, the same precision as the AWS prices
+        let price_per_hour = (price.retail_price * 100000.0).round() / 100000.0;
+
+        if price.sku_name.ends_with(" Spot") {
+            size_to_spot.insert(
+                size.to_string(),
+                SpotInstance {
+                    instance_type: size.to_string(),
+                    spot_price: price_per_hour,
+                },
+            );
+        } else {
+            // arm64 sizes are the ampere altra "p" sizes, e.g. Standard_D2ps_v5
+            let arch = if size.contains("ps_") || size.contains("pls_") {
+                "arm64".to_string()
+            } else {
+                "x86_64".to_string()
+            };
+
+            size_to_instance.insert(
+                size.to_string(),
+                OnDemandInstance {
+                    region: region_code.to_string(),
+                    instance_name: size.to_string(),
+                    vcpu_count: *vcpu_count,
+                    memory: *memory,
+                    arch,
+                    price_per_hour: price_per_hour as f32,
+                    storage: "Managed Disk only".to_string(),
+                },
+            );
+        }
+    }
+
+    // Create a map of the volume api name -> storage
+    let mut disk_to_storage: HashMap<String, Storage> = HashMap::new();
+
+    for price in disk_prices.iter() {
+        if price.r#type != "Consumption" {
+            continue;
+        }
+
+        for (product_name, meter_name, volume_api_name, storage_media, size) in
+            AZURE_DISK_TYPES.iter()
+        {
+            if price.product_name == *product_name && price.meter_name == *meter_name {
+                disk_to_storage.insert(
+                    volume_api_name.to_string(),
+                    Storage {
+                        region: region_code.to_string(),
+                        storage_media: storage_media.to_string(),
+                        volume_api_name: volume_api_name.to_string(),
+                        price_per_gb_month: price.retail_price as f32 / size,
+                        price_per_iops_month: None,
+                        price_per_mibps_month: None,
+                        included_iops: None,
+                        included_throughput: None,
+                        snapshot_price_per_gb_month: None,
+                    },
+                );
+            }
+        }
+    }
+
+    ComputePrices {
+        instances: size_to_instance.into_values().collect(),
+        spot: size_to_spot.into_values().collect(),
+        storage: disk_to_storage.into_values().collect(),
+    }
+}
+
+// Function to update vm size, spot and managed disk pricing for a specific region
+pub async fn update_pricing_for_region(
+    pool: PgPool,
+    region: AzureRegion,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let region_code = region.code();
+
+    let vm_prices = fetch_prices(
+        RETAIL_PRICES_URL,
+        &format!(
+            "serviceName eq 'Virtual Machines' and armRegionName eq '{}' and priceType eq 'Consumption'",
+            region_code
+        ),
+    )
+    .await?;
+
+    let disk_products = AZURE_DISK_TYPES
+        .iter()
+        .map(|(product_name, _, _, _, _)| format!("productName eq '{}'", product_name))
+        .collect::<Vec<String>>()
+        .join(" or ");
+
+    let disk_prices = fetch_prices(
+        RETAIL_PRICES_URL,
+        &format!(
+            "serviceName eq 'Storage' and armRegionName eq '{}' and ({})",
+            region_code, disk_products
+        ),
+    )
+    .await?;
+
+    let prices = compute_prices(&region_code, &vm_prices, &disk_prices);
+
+    let instance_entries = prices.instances;
+    let spot_entries = prices.spot;
+    let storage_entries = prices.storage;
+
+    let instance_entries_len = instance_entries.len();
+    let storage_entries_len = storage_entries.len();
+
+    if !instance_entries.is_empty() {
+        db::insert::on_demand_pricing_in_bulk(&pool, "azure", instance_entries).await?;
+    }
+
+    // Spot prices are regional on Azure, so the region doubles as the availability zone
+    if !spot_entries.is_empty() {
+        let mut latest_prices: HashMap<String, Vec<SpotInstance>> = HashMap::new();
+        latest_prices.insert(region_code.clone(), spot_entries);
+
+        db::insert::spot_pricing_in_bulk(&pool, "azure", region_code.clone(), latest_prices)
+            .await?;
+    }
+
+    if !storage_entries.is_empty() {
+        db::insert::storage_pricing_in_bulk(&pool, "azure", storage_entries).await?;
+    }
+
+    println!(
+        "Updated pricing for {} with {} vm sizes and {} disk types.",
+        region_code.bright_cyan(),
+        instance_entries_len.to_string().bright_green(),
+        storage_entries_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use crate::models::retail_prices::RetailPricesResponse;
+
+    fn page(path: &str) -> Vec<RetailPrice> {
+        let page: RetailPricesResponse = serde_json::from_str(&fixture(path)).unwrap();
+
+        page.items
+    }
+
+    fn prices() -> ComputePrices {
+        compute_prices(
+            "eastus",
+            &page("azure/vm_prices.json"),
+            &page("azure/disk_prices.json"),
+        )
+    }
+
+    fn instance<'a>(prices: &'a ComputePrices, name: &str) -> &'a OnDemandInstance {
+        prices
+            .instances
+            .iter()
+            .find(|instance| instance.instance_name == name)
+            .unwrap_or_else(|| panic!("{} is not priced", name))
+    }
+
+    fn assert_price(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn vm_sizes_are_priced_at_the_linux_pay_as_you_go_rate() {
+        let prices = prices();
+
+        // windows, low priority and dev/test prices of the size are ignored
+        let d2s = instance(&prices, "Standard_D2s_v5");
+        assert_price(d2s.price_per_hour, 0.096);
+        assert_eq!(d2s.region, "eastus");
+        assert_eq!(d2s.vcpu_count, 2.0);
+        assert_eq!(d2s.memory, 8.0);
+        assert_eq!(d2s.arch, "x86_64");
+        assert_eq!(d2s.storage, "Managed Disk only");
+
+        assert_price(instance(&prices, "Standard_B1s").price_per_hour, 0.0104);
+    }
+
+    #[test]
+    fn ampere_sizes_are_arm64() {
+        let prices = prices();
+
+        assert_eq!(instance(&prices, "Standard_D2ps_v5").arch, "arm64");
+        assert_eq!(instance(&prices, "Standard_D2pls_v5").arch, "arm64");
+    }
+
+    #[test]
+    fn only_indexed_sizes_with_a_consumption_price_are_priced() {
+        let prices = prices();
+
+        let mut names: Vec<&str> = prices
+            .instances
+            .iter()
+            .map(|instance| instance.instance_name.as_str())
+            .collect();
+        names.sort();
+
+        // Standard_NC6s_v3 isn't indexed, Standard_D4s_v5 only has a dev/test price
+        assert_eq!(
+            names,
+            [
+                "Standard_B1s",
+                "Standard_D2pls_v5",
+                "Standard_D2ps_v5",
+                "Standard_D2s_v5"
+            ]
+        );
+    }
+
+    #[test]
+    fn spot_prices_are_read_from_spot_skus() {
+        let spot = prices().spot;
+
+        assert_eq!(spot.len(), 1);
+        assert_eq!(spot[0].instance_type, "Standard_D2s_v5");
+        assert_price(spot[0].spot_price as f32, 0.0193);
+    }
+
+    #[test]
+    fn managed_disks_are_priced_per_gb_month() {
+        let mut storage = prices().storage;
+        storage.sort_by(|a, b| a.volume_api_name.cmp(&b.volume_api_name));
+
+        // zone redundant disks, reservations and disk operations are not the lrs disk price
+        let disks: Vec<(&str, &str, &str)> = storage
+            .iter()
+            .map(|disk| {
+                (
+                    disk.region.as_str(),
+                    disk.volume_api_name.as_str(),
+                    disk.storage_media.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            disks,
+            [
+                ("eastus", "premium-ssd", "SSD"),
+                ("eastus", "standard-hdd", "HDD"),
+                ("eastus", "standard-ssd", "SSD"),
+            ]
+        );
+
+        // the price of a 128 GiB disk spread over its size
+        assert_price(storage[0].price_per_gb_month, 19.71 / 128.0);
+        assert_price(storage[1].price_per_gb_month, 5.89 / 128.0);
+        assert_price(storage[2].price_per_gb_month, 9.6 / 128.0);
+    }
+}
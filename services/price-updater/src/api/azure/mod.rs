@@ -0,0 +1,3 @@
+pub mod instance;
+pub mod network;
+pub mod retail_prices;
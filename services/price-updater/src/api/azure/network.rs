@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use colored::Colorize;
+use sqlx::PgPool;
+use types::region::AzureRegion;
+
+use crate::api::azure::retail_prices::{fetch_prices, RETAIL_PRICES_URL};
+use crate::db;
+use crate::models::network::{ExternalPrice, ExternalTier};
+use crate::models::retail_prices::RetailPrice;
+
+// Function to read the tiered internet egress prices of every region from the retail prices
+// of Bandwidth
+pub fn external_transfer_prices(prices: &[RetailPrice]) -> HashMap<String, ExternalPrice> {
+    // Create a map of the region -> (tier minimum units, price per gb)
+    let mut region_to_tiers: HashMap<String, Vec<(f64, f32)>> = HashMap::new();
+
+    for price in prices.iter() {
+        if price.r#type != "Consumption" || price.meter_name != "Standard Data Transfer Out" {
+            continue;
+        }
+
+        // skip zone-wide and government prices
+        if AzureRegion::from_str(&price.arm_region_name).is_err() {
+            continue;
+        }
+
+        region_to_tiers
+            .entry(price.arm_region_name.clone())
+            .or_default()
+            .push((
+                price.tier_minimum_units,
+                ((price.retail_price * 1000.0).round() / 1000.0) as f32,
+            ));
+    }
+
+    let mut external_transfer_prices: HashMap<String, ExternalPrice> = HashMap::new();
+
+    for (region_code, mut rates) in region_to_tiers {
+        rates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        rates.dedup_by(|a, b| a.0 == b.0);
+
+        // each tier ends where the next one starts, the last one is unbounded
+        let tiers = rates
+            .iter()
+            .enumerate()
+            .map(|(index, (start_range, price_per_gb))| ExternalTier {
+                start_range: *start_range as u32,
+                end_range: rates
+                    .get(index + 1)
+                    .map(|next| next.0 as u32)
+                    .unwrap_or(u32::MAX),
+                price_per_gb: *price_per_gb,
+            })
+            .collect();
+
+        external_transfer_prices.insert(
+            region_code.clone(),
+            ExternalPrice {
+                from_region_code: region_code,
+                tiers,
+            },
+        );
+    }
+
+    external_transfer_prices
+}
+
+// Function to update internet egress pricing for every region
+pub async fn update_networking_pricing(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let bandwidth_prices = fetch_prices(RETAIL_PRICES_URL, "serviceName eq 'Bandwidth'").await?;
+
+    let external_transfer_prices = external_transfer_prices(&bandwidth_prices);
+
+    let external_transfer_prices_len = external_transfer_prices.len();
+
+    if !external_transfer_prices.is_empty() {
+        db::insert::external_data_transfer_in_bulk(&pool, "azure", external_transfer_prices)
+            .await?;
+    }
+
+    println!(
+        "Updated {} external pricing for {} routes.",
+        "azure".bright_cyan(),
+        external_transfer_prices_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use crate::models::retail_prices::RetailPricesResponse;
+
+    fn prices() -> HashMap<String, ExternalPrice> {
+        let page: RetailPricesResponse =
+            serde_json::from_str(&fixture("azure/bandwidth_prices.json")).unwrap();
+
+        external_transfer_prices(&page.items)
+    }
+
+    fn tiers(price: &ExternalPrice) -> Vec<(u32, u32, f32)> {
+        price
+            .tiers
+            .iter()
+            .map(|tier| (tier.start_range, tier.end_range, tier.price_per_gb))
+            .collect()
+    }
+
+    #[test]
+    fn egress_to_the_internet_is_priced_per_region() {
+        let prices = prices();
+
+        // zone-wide and government prices are left out
+        let mut regions: Vec<&String> = prices.keys().collect();
+        regions.sort();
+        assert_eq!(regions, ["eastus", "westeurope"]);
+
+        assert_eq!(prices["eastus"].from_region_code, "eastus");
+    }
+
+    #[test]
+    fn egress_tiers_end_where_the_next_one_starts() {
+        let prices = prices();
+
+        // the tiers aren't listed in order, and a tier listed twice is kept once
+        assert_eq!(
+            tiers(&prices["eastus"]),
+            [
+                (0, 100, 0.0),
+                (100, 10340, 0.087),
+                (10340, 51300, 0.083),
+                (51300, 153700, 0.07),
+                (153700, u32::MAX, 0.05)
+            ]
+        );
+        assert_eq!(
+            tiers(&prices["westeurope"]),
+            [(0, 100, 0.0), (100, 10340, 0.087), (10340, u32::MAX, 0.083)]
+        );
+    }
+}
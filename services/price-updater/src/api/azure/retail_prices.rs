@@ -0,0 +1,121 @@
+use crate::models::retail_prices::{RetailPrice, RetailPricesResponse};
+
+// Endpoint of the Azure Retail Prices API
+pub const RETAIL_PRICES_URL: &str = "https://prices.azure.com/api/retail/prices";
+
+// Function to fetch every price matching an OData filter from the Azure Retail Prices API at url
+pub async fn fetch_prices(
+    url: &str,
+    filter: &str,
+) -> Result<Vec<RetailPrice>, Box<dyn std::error::Error>> {
+    // Create a client
+    let client = reqwest::Client::new();
+
+    let mut prices: Vec<RetailPrice> = Vec::new();
+
+    let mut request = client
+        .get(url)
+        .query(&[("api-version", "2023-01-01-preview"), ("$filter", filter)]);
+
+    // The API is paginated, keep following the next page link until it runs out
+    loop {
+        let response: RetailPricesResponse =
+            request.send().await?.error_for_status()?.json().await?;
+
+        prices.extend(response.items);
+
+        match response.next_page_link {
+            Some(next_page_link) if !next_page_link.is_empty() => {
+                request = client.get(next_page_link);
+            }
+            _ => break,
+        }
+    }
+
+    Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const FILTER: &str = "serviceName eq 'Bandwidth'";
+
+    #[tokio::test]
+    async fn next_page_links_are_followed_until_they_run_out() {
+        let server = MockServer::start().await;
+
+        // the first page links to the second, which ends the listing with an empty link
+        let mut first_page: RetailPricesResponse =
+            serde_json::from_str(&fixture("azure/bandwidth_prices.json")).unwrap();
+        let first_page_len = first_page.items.len();
+        first_page.next_page_link = Some(format!(
+            "{}/api/retail/prices?api-version=2023-01-01-preview&$skip=100",
+            server.uri()
+        ));
+
+        Mock::given(method("GET"))
+            .and(path("/api/retail/prices"))
+            .and(query_param("api-version", "2023-01-01-preview"))
+            .and(query_param("$filter", FILTER))
+            .and(query_param_is_missing("$skip"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&first_page))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/retail/prices"))
+            .and(query_param("$skip", "100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "BillingCurrency": "USD",
+                "Items": [{
+                    "currencyCode": "USD",
+                    "tierMinimumUnits": 0.0,
+                    "retailPrice": 0.0,
+                    "armRegionName": "westeurope",
+                    "productName": "Rtn Preference: MGN",
+                    "skuName": "Standard",
+                    "serviceName": "Bandwidth",
+                    "meterName": "Standard Data Transfer In",
+                    "unitOfMeasure": "1 GB",
+                    "type": "Consumption"
+                }],
+                "NextPageLink": ""
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let prices = fetch_prices(&format!("{}/api/retail/prices", server.uri()), FILTER)
+            .await
+            .unwrap();
+
+        assert_eq!(prices.len(), first_page_len + 1);
+        assert_eq!(
+            prices.last().unwrap().meter_name,
+            "Standard Data Transfer In"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_page_is_an_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = fetch_prices(&format!("{}/api/retail/prices", server.uri()), FILTER)
+            .await
+            .expect_err("a rate limited page was accepted");
+
+        assert!(error.to_string().contains("429"));
+    }
+}
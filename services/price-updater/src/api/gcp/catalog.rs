@@ -0,0 +1,46 @@
+use crate::models::catalog::{CatalogResponse, Sku};
+
+// Service id of Compute Engine in the Cloud Billing Catalog
+pub const COMPUTE_ENGINE_SERVICE_ID: &str = "6F81-5844-456A";
+
+// Function to fetch every sku of a service from the Cloud Billing Catalog
+pub async fn fetch_skus(service_id: &str) -> Result<Vec<Sku>, Box<dyn std::error::Error>> {
+    let api_key = std::env::var("GCP_API_KEY")?;
+
+    let url = format!(
+        "https://cloudbilling.googleapis.com/v1/services/{}/skus",
+        service_id
+    );
+
+    // Create a client
+    let client = reqwest::Client::new();
+
+    let mut skus: Vec<Sku> = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    // The catalog is paginated, keep following the page token until it runs out
+    loop {
+        let mut request = client.get(&url).query(&[
+            ("key", api_key.as_str()),
+            ("currencyCode", "USD"),
+            ("pageSize", "5000"),
+        ]);
+
+        if let Some(page_token) = &page_token {
+            request = request.query(&[("pageToken", page_token.as_str())]);
+        }
+
+        let response: CatalogResponse = request.send().await?.error_for_status()?.json().await?;
+
+        skus.extend(response.skus);
+
+        match response.next_page_token {
+            Some(next_page_token) if !next_page_token.is_empty() => {
+                page_token = Some(next_page_token)
+            }
+            _ => break,
+        }
+    }
+
+    Ok(skus)
+}
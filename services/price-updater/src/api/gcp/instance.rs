@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+use strum::IntoEnumIterator;
+use types::region::GcpRegion;
+
+use crate::constants::machine_types::{GCP_DISK_TYPES, GCP_MACHINE_FAMILIES, GCP_MACHINE_TYPES};
+use crate::db;
+use crate::models::catalog::Sku;
+use crate::models::on_demand_pricing::OnDemandInstance;
+use crate::models::spot_pricing::SpotInstance;
+use crate::models::storage::Storage;
+
+// Price of a vcpu hour and a GiB of memory hour for a machine family in a region
+#[derive(Default, Debug, Clone, Copy)]
+struct ResourcePrice {
+    core: Option<f32>,
+    ram: Option<f32>,
+}
+
+// Returns the codes of the regions we index that a sku is sold in
+pub fn sku_regions(sku: &Sku) -> Vec<String> {
+    GcpRegion::iter()
+        .map(|region| region.code())
+        .filter(|code| sku.service_regions.contains(code))
+        .collect()
+}
+
+// Checks whether a sku description is the regional price of the given resource
+// e.g. "E2 Instance Core running in Americas"
+fn is_resource(description: &str, resource: &str) -> bool {
+    description
+        .strip_prefix(resource)
+        .is_some_and(|rest| rest.starts_with(" running in"))
+}
+
+fn machine_price(
+    prices: &HashMap<(&str, String), ResourcePrice>,
+    family: &str,
+    region: &str,
+    vcpu_count: f32,
+    memory: f32,
+) -> Option<f32> {
+    let price = prices.get(&(family, region.to_string()))?;
+    let price_per_hour = vcpu_count * price.core? + memory * price.ram?;
+
+    // Round to 5 decimal places, the same precision as the AWS prices
+    Some((price_per_hour * 100000.0).round() / 100000.0)
+}
+
+// Machine type, preemptible and persistent disk prices read from the catalog
+pub struct ComputePrices {
+    pub instances: Vec<OnDemandInstance>,
+    // preemptible prices by region
+    pub spot: HashMap<String, Vec<SpotInstance>>,
+    pub storage: Vec<Storage>,
+}
+
+// Function to price the machine types and disks we index from the skus of Compute Engine
+pub fn compute_prices(skus: &[Sku]) -> ComputePrices {
+    // Create a map of (machine family, region) -> on-demand core and ram prices
+    let mut on_demand_prices: HashMap<(&str, String), ResourcePrice> = HashMap::new();
+    // Create a map of (machine family, region) -> preemptible core and ram prices
+    let mut preemptible_prices: HashMap<(&str, String), ResourcePrice> = HashMap::new();
+    // Create a map of (region, volume api name) -> storage
+    let mut disk_to_storage: HashMap<(String, &str), Storage> = HashMap::new();
+
+    for sku in skus {
+        let usage_type = sku.category.usage_type.as_str();
+
+        if sku.category.resource_family == "Compute" {
+            let prices = match usage_type {
+                "OnDemand" => &mut on_demand_prices,
+                "Preemptible" => &mut preemptible_prices,
+                _ => continue,
+            };
+
+            let description = sku.description.trim_start_matches("Spot Preemptible ");
+
+            for (family, core, ram) in GCP_MACHINE_FAMILIES.iter() {
+                let is_core = is_resource(description, core);
+                let is_ram = is_resource(description, ram);
+
+                if !is_core && !is_ram {
+                    continue;
+                }
+
+                if let Some(price) = sku.unit_price() {
+                    for region in sku_regions(sku) {
+                        let entry = prices.entry((family, region)).or_default();
+
+                        if is_core {
+                            entry.core = Some(price);
+                        } else {
+                            entry.ram = Some(price);
+                        }
+                    }
+                }
+            }
+        } else if sku.category.resource_family == "Storage" && usage_type == "OnDemand" {
+            for (prefix, volume_api_name, storage_media) in GCP_DISK_TYPES.iter() {
+                if !sku.description.starts_with(prefix) {
+                    continue;
+                }
+
+                if let Some(price) = sku.unit_price() {
+                    for region in sku_regions(sku) {
+                        disk_to_storage.insert(
+                            (region.clone(), volume_api_name),
+                            Storage {
+                                region,
+                                storage_media: storage_media.to_string(),
+                                volume_api_name: volume_api_name.to_string(),
+                                price_per_gb_month: price,
+                                price_per_iops_month: None,
+                                price_per_mibps_month: None,
+                                included_iops: None,
+                                included_throughput: None,
+                                snapshot_price_per_gb_month: None,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut instance_entries: Vec<OnDemandInstance> = Vec::new();
+    let mut spot_entries: HashMap<String, Vec<SpotInstance>> = HashMap::new();
+
+    for region in GcpRegion::iter().map(|region| region.code()) {
+        for (machine_type, vcpu_count, memory) in GCP_MACHINE_TYPES.iter() {
+            let family = machine_type.split('-').next().unwrap();
+
+            let arch = if family == "t2a" {
+                "arm64".to_string()
+            } else {
+                "x86_64".to_string()
+            };
+
+            if let Some(price_per_hour) =
+                machine_price(&on_demand_prices, family, &region, *vcpu_count, *memory)
+            {
+                instance_entries.push(OnDemandInstance {
+                    region: region.to_string(),
+                    instance_name: machine_type.to_string(),
+                    vcpu_count: *vcpu_count,
+                    memory: *memory,
+                    arch,
+                    price_per_hour,
+                    storage: "Persistent Disk only".to_string(),
+                });
+            }
+
+            if let Some(spot_price) =
+                machine_price(&preemptible_prices, family, &region, *vcpu_count, *memory)
+            {
+                spot_entries
+                    .entry(region.clone())
+                    .or_default()
+                    .push(SpotInstance {
+                        instance_type: machine_type.to_string(),
+                        spot_price: spot_price as f64,
+                    });
+            }
+        }
+    }
+
+    ComputePrices {
+        instances: instance_entries,
+        spot: spot_entries,
+        storage: disk_to_storage.into_values().collect(),
+    }
+}
+
+// Function to update machine type, preemptible and persistent disk pricing from the catalog
+pub async fn update_compute_pricing(
+    pool: PgPool,
+    skus: &[Sku],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let ComputePrices {
+        instances: instance_entries,
+        spot: spot_entries,
+        storage: storage_entries,
+    } = compute_prices(skus);
+
+    let instance_entries_len = instance_entries.len();
+    let storage_entries_len = storage_entries.len();
+
+    if !instance_entries.is_empty() {
+        db::insert::on_demand_pricing_in_bulk(&pool, "gcp", instance_entries).await?;
+    }
+
+    // Spot prices are regional on GCP, so the region doubles as the availability zone
+    for (region, instances) in spot_entries {
+        let mut latest_prices: HashMap<String, Vec<SpotInstance>> = HashMap::new();
+        latest_prices.insert(region.clone(), instances);
+
+        db::insert::spot_pricing_in_bulk(&pool, "gcp", region, latest_prices).await?;
+    }
+
+    if !storage_entries.is_empty() {
+        db::insert::storage_pricing_in_bulk(&pool, "gcp", storage_entries).await?;
+    }
+
+    println!(
+        "Updated {} pricing with {} machine types and {} disk types.",
+        "gcp".bright_cyan(),
+        instance_entries_len.to_string().bright_green(),
+        storage_entries_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use crate::models::catalog::CatalogResponse;
+
+    fn prices() -> ComputePrices {
+        let page: CatalogResponse =
+            serde_json::from_str(&fixture("gcp/compute_engine_skus.json")).unwrap();
+
+        compute_prices(&page.skus)
+    }
+
+    fn instance<'a>(prices: &'a ComputePrices, region: &str, name: &str) -> &'a OnDemandInstance {
+        prices
+            .instances
+            .iter()
+            .find(|instance| instance.region == region && instance.instance_name == name)
+            .unwrap_or_else(|| panic!("{} is not priced in {}", name, region))
+    }
+
+    fn assert_price(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn machine_types_are_priced_by_core_and_ram() {
+        let prices = prices();
+
+        // 2 vcpus at $0.021811063 and 8 GiB at $0.002923316, commitments are ignored
+        let e2 = instance(&prices, "us-central1", "e2-standard-2");
+        assert_price(e2.price_per_hour, 0.06701);
+        assert_eq!(e2.vcpu_count, 2.0);
+        assert_eq!(e2.memory, 8.0);
+        assert_eq!(e2.arch, "x86_64");
+        assert_eq!(e2.storage, "Persistent Disk only");
+
+        // shared-core machines are billed for a quarter of a vcpu
+        assert_price(
+            instance(&prices, "us-central1", "e2-micro").price_per_hour,
+            0.00838,
+        );
+
+        let t2a = instance(&prices, "us-central1", "t2a-standard-1");
+        assert_price(t2a.price_per_hour, 0.04620);
+        assert_eq!(t2a.arch, "arm64");
+    }
+
+    #[test]
+    fn machine_types_are_priced_in_every_indexed_region_of_a_sku() {
+        let prices = prices();
+
+        instance(&prices, "us-east1", "e2-standard-2");
+
+        // us-central2 is not indexed, t2a is only sold in us-central1
+        assert!(!prices
+            .instances
+            .iter()
+            .any(|instance| instance.region == "us-central2"));
+        assert!(!prices.instances.iter().any(|instance| {
+            instance.region == "us-east1" && instance.instance_name.starts_with("t2a")
+        }));
+    }
+
+    #[test]
+    fn machine_family_without_a_ram_price_is_not_priced() {
+        // n2 has a core sku but no ram sku, and sole tenancy cores are not n2 cores
+        assert!(!prices()
+            .instances
+            .iter()
+            .any(|instance| instance.instance_name.starts_with("n2-")));
+    }
+
+    #[test]
+    fn preemptible_prices_are_regional() {
+        let prices = prices();
+
+        let mut regions: Vec<&String> = prices.spot.keys().collect();
+        regions.sort();
+        assert_eq!(regions, ["us-central1", "us-east1"]);
+
+        let e2 = prices.spot["us-east1"]
+            .iter()
+            .find(|instance| instance.instance_type == "e2-standard-2")
+            .unwrap();
+        assert_price(e2.spot_price as f32, 0.0201);
+    }
+
+    #[test]
+    fn persistent_disks_are_priced_per_gb_month() {
+        let mut storage = prices().storage;
+        storage.sort_by(|a, b| a.volume_api_name.cmp(&b.volume_api_name));
+
+        // regional ssd disks and snapshots are not zonal pd-ssd capacity
+        let disks: Vec<(&str, &str, &str)> = storage
+            .iter()
+            .map(|disk| {
+                (
+                    disk.region.as_str(),
+                    disk.volume_api_name.as_str(),
+                    disk.storage_media.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            disks,
+            [
+                ("us-central1", "pd-balanced", "SSD"),
+                ("us-central1", "pd-standard", "HDD"),
+            ]
+        );
+
+        assert_price(storage[0].price_per_gb_month, 0.1);
+        assert_price(storage[1].price_per_gb_month, 0.04);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use sqlx::PgPool;
+
+use crate::api::gcp::instance::sku_regions;
+use crate::db;
+use crate::models::catalog::Sku;
+use crate::models::network::{ExternalPrice, ExternalTier};
+
+// Function to read the tiered internet egress prices of every region from the skus of
+// Compute Engine
+pub fn external_transfer_prices(skus: &[Sku]) -> HashMap<String, ExternalPrice> {
+    let mut external_transfer_prices: HashMap<String, ExternalPrice> = HashMap::new();
+
+    for sku in skus {
+        // only premium tier egress to the internet, excluding the china & australia surcharges
+        if sku.category.resource_group != "PremiumInternetEgress"
+            || sku.category.usage_type != "OnDemand"
+            || !sku.description.contains("Worldwide Destinations")
+        {
+            continue;
+        }
+
+        let rates = sku.tiered_rates();
+
+        let mut tiers: Vec<ExternalTier> = Vec::new();
+
+        // each tier ends where the next one starts, the last one is unbounded
+        for (index, rate) in rates.iter().enumerate() {
+            let end_range = rates
+                .get(index + 1)
+                .map(|next| next.start_usage_amount as u32)
+                .unwrap_or(u32::MAX);
+
+            tiers.push(ExternalTier {
+                start_range: rate.start_usage_amount as u32,
+                end_range,
+                price_per_gb: (rate.unit_price.amount() * 1000.0).round() / 1000.0,
+            });
+        }
+
+        for region in sku_regions(sku) {
+            external_transfer_prices.insert(
+                region.clone(),
+                ExternalPrice {
+                    from_region_code: region,
+                    tiers: tiers.clone(),
+                },
+            );
+        }
+    }
+
+    external_transfer_prices
+}
+
+// Function to update internet egress pricing from the catalog
+pub async fn update_networking_pricing(
+    pool: PgPool,
+    skus: &[Sku],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let external_transfer_prices = external_transfer_prices(skus);
+
+    let external_transfer_prices_len = external_transfer_prices.len();
+
+    if !external_transfer_prices.is_empty() {
+        db::insert::external_data_transfer_in_bulk(&pool, "gcp", external_transfer_prices).await?;
+    }
+
+    println!(
+        "Updated {} external pricing for {} routes.",
+        "gcp".bright_cyan(),
+        external_transfer_prices_len.to_string().bright_green()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::fixture;
+    use crate::models::catalog::CatalogResponse;
+
+    fn prices() -> HashMap<String, ExternalPrice> {
+        let page: CatalogResponse =
+            serde_json::from_str(&fixture("gcp/compute_engine_skus.json")).unwrap();
+
+        external_transfer_prices(&page.skus)
+    }
+
+    fn tiers(price: &ExternalPrice) -> Vec<(u32, u32, f32)> {
+        price
+            .tiers
+            .iter()
+            .map(|tier| (tier.start_range, tier.end_range, tier.price_per_gb))
+            .collect()
+    }
+
+    #[test]
+    fn premium_egress_to_the_world_is_priced_per_region() {
+        let prices = prices();
+
+        let mut regions: Vec<&String> = prices.keys().collect();
+        regions.sort();
+        assert_eq!(regions, ["australia-southeast1", "us-central1", "us-east1"]);
+
+        assert_eq!(prices["us-east1"].from_region_code, "us-east1");
+    }
+
+    #[test]
+    fn egress_tiers_end_where_the_next_one_starts() {
+        let prices = prices();
+
+        // the catalog doesn't list the tiers in order
+        assert_eq!(
+            tiers(&prices["us-central1"]),
+            [
+                (0, 1024, 0.12),
+                (1024, 10240, 0.11),
+                (10240, u32::MAX, 0.08)
+            ]
+        );
+        assert_eq!(
+            tiers(&prices["australia-southeast1"]),
+            [
+                (0, 1024, 0.19),
+                (1024, 10240, 0.18),
+                (10240, u32::MAX, 0.15)
+            ]
+        );
+    }
+}
@@ -0,0 +1,20 @@
+// engine name in the RDS offer, engine code
+pub const RDS_ENGINES: [(&str, &str); 5] = [
+    ("PostgreSQL", "postgres"),
+    ("MySQL", "mysql"),
+    ("MariaDB", "mariadb"),
+    ("Aurora PostgreSQL", "aurora-postgresql"),
+    ("Aurora MySQL", "aurora-mysql"),
+];
+
+// deployment option in the RDS offer, deployment option code
+pub const RDS_DEPLOYMENT_OPTIONS: [(&str, &str); 2] =
+    [("Single-AZ", "single-az"), ("Multi-AZ", "multi-az")];
+
+// volume type in the RDS offer, volume type code
+pub const RDS_VOLUME_TYPES: [(&str, &str); 4] = [
+    ("General Purpose", "gp2"),
+    ("General Purpose-GP3", "gp3"),
+    ("Provisioned IOPS", "io1"),
+    ("General Purpose-Aurora", "aurora"),
+];
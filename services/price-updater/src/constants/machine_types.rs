@@ -0,0 +1,129 @@
+// machine family, description of the core sku, description of the ram sku
+pub const GCP_MACHINE_FAMILIES: [(&str, &str, &str); 7] = [
+    ("e2", "E2 Instance Core", "E2 Instance Ram"),
+    (
+        "n1",
+        "N1 Predefined Instance Core",
+        "N1 Predefined Instance Ram",
+    ),
+    ("n2", "N2 Instance Core", "N2 Instance Ram"),
+    ("n2d", "N2D AMD Instance Core", "N2D AMD Instance Ram"),
+    ("t2d", "T2D AMD Instance Core", "T2D AMD Instance Ram"),
+    ("t2a", "T2A Arm Instance Core", "T2A Arm Instance Ram"),
+    ("c2", "Compute optimized Core", "Compute optimized Ram"),
+];
+
+// machine type, billed vcpu count, memory in GiB
+// shared-core e2 machines are billed for a fraction of a vcpu
+pub const GCP_MACHINE_TYPES: [(&str, f32, f32); 40] = [
+    ("e2-micro", 0.25, 1.0),
+    ("e2-small", 0.5, 2.0),
+    ("e2-medium", 1.0, 4.0),
+    ("e2-standard-2", 2.0, 8.0),
+    ("e2-standard-4", 4.0, 16.0),
+    ("e2-standard-8", 8.0, 32.0),
+    ("e2-standard-16", 16.0, 64.0),
+    ("e2-highmem-2", 2.0, 16.0),
+    ("e2-highmem-4", 4.0, 32.0),
+    ("e2-highmem-8", 8.0, 64.0),
+    ("e2-highcpu-2", 2.0, 2.0),
+    ("e2-highcpu-4", 4.0, 4.0),
+    ("e2-highcpu-8", 8.0, 8.0),
+    ("n1-standard-1", 1.0, 3.75),
+    ("n1-standard-2", 2.0, 7.5),
+    ("n1-standard-4", 4.0, 15.0),
+    ("n1-standard-8", 8.0, 30.0),
+    ("n2-standard-2", 2.0, 8.0),
+    ("n2-standard-4", 4.0, 16.0),
+    ("n2-standard-8", 8.0, 32.0),
+    ("n2-standard-16", 16.0, 64.0),
+    ("n2-highmem-2", 2.0, 16.0),
+    ("n2-highmem-4", 4.0, 32.0),
+    ("n2-highcpu-2", 2.0, 2.0),
+    ("n2-highcpu-4", 4.0, 4.0),
+    ("n2d-standard-2", 2.0, 8.0),
+    ("n2d-standard-4", 4.0, 16.0),
+    ("n2d-standard-8", 8.0, 32.0),
+    ("t2d-standard-1", 1.0, 4.0),
+    ("t2d-standard-2", 2.0, 8.0),
+    ("t2d-standard-4", 4.0, 16.0),
+    ("t2d-standard-8", 8.0, 32.0),
+    ("t2a-standard-1", 1.0, 4.0),
+    ("t2a-standard-2", 2.0, 8.0),
+    ("t2a-standard-4", 4.0, 16.0),
+    ("t2a-standard-8", 8.0, 32.0),
+    ("c2-standard-4", 4.0, 16.0),
+    ("c2-standard-8", 8.0, 32.0),
+    ("c2-standard-16", 16.0, 64.0),
+    ("c2-standard-30", 30.0, 120.0),
+];
+
+// description prefix of the disk sku, volume api name, storage media
+pub const GCP_DISK_TYPES: [(&str, &str, &str); 3] = [
+    ("Storage PD Capacity", "pd-standard", "HDD"),
+    ("Balanced PD Capacity", "pd-balanced", "SSD"),
+    ("SSD backed PD Capacity", "pd-ssd", "SSD"),
+];
+
+// vm size, vcpu count, memory in GiB
+pub const AZURE_VM_SIZES: [(&str, f32, f32); 30] = [
+    ("Standard_B1s", 1.0, 1.0),
+    ("Standard_B1ms", 1.0, 2.0),
+    ("Standard_B2s", 2.0, 4.0),
+    ("Standard_B2ms", 2.0, 8.0),
+    ("Standard_B4ms", 4.0, 16.0),
+    ("Standard_B8ms", 8.0, 32.0),
+    ("Standard_D2s_v5", 2.0, 8.0),
+    ("Standard_D4s_v5", 4.0, 16.0),
+    ("Standard_D8s_v5", 8.0, 32.0),
+    ("Standard_D16s_v5", 16.0, 64.0),
+    ("Standard_D2as_v5", 2.0, 8.0),
+    ("Standard_D4as_v5", 4.0, 16.0),
+    ("Standard_D8as_v5", 8.0, 32.0),
+    ("Standard_D2ps_v5", 2.0, 8.0),
+    ("Standard_D4ps_v5", 4.0, 16.0),
+    ("Standard_D8ps_v5", 8.0, 32.0),
+    ("Standard_D2pls_v5", 2.0, 4.0),
+    ("Standard_D4pls_v5", 4.0, 8.0),
+    ("Standard_E2s_v5", 2.0, 16.0),
+    ("Standard_E4s_v5", 4.0, 32.0),
+    ("Standard_E8s_v5", 8.0, 64.0),
+    ("Standard_E2ps_v5", 2.0, 16.0),
+    ("Standard_E4ps_v5", 4.0, 32.0),
+    ("Standard_F2s_v2", 2.0, 4.0),
+    ("Standard_F4s_v2", 4.0, 8.0),
+    ("Standard_F8s_v2", 8.0, 16.0),
+    ("Standard_F16s_v2", 16.0, 32.0),
+    ("Standard_D2s_v3", 2.0, 8.0),
+    ("Standard_D4s_v3", 4.0, 16.0),
+    ("Standard_D8s_v3", 8.0, 32.0),
+];
+
+// product name, meter name, volume api name, storage media, disk size in GiB
+// managed disks are sold in fixed sizes, so the price per GiB is taken from a 128 GiB disk
+pub const AZURE_DISK_TYPES: [(&str, &str, &str, &str, f32); 3] = [
+    (
+        "Premium SSD Managed Disks",
+        "P10 LRS Disk",
+        "premium-ssd",
+        "SSD",
+        128.0,
+    ),
+    (
+        "Standard SSD Managed Disks",
+        "E10 LRS Disk",
+        "standard-ssd",
+        "SSD",
+        128.0,
+    ),
+    (
+        "Standard HDD Managed Disks",
+        "S10 LRS Disk",
+        "standard-hdd",
+        "HDD",
+        128.0,
+    ),
+];
+
+// volume api name, iops and throughput in MiB/s included in the price per gb
+pub const EBS_VOLUME_BASELINES: [(&str, u32, u32); 1] = [("gp3", 3000, 125)];
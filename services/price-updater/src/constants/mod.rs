@@ -1 +1,4 @@
-pub mod regions;
+pub mod database_engines;
+pub mod machine_types;
+pub mod network_services;
+pub mod storage_classes;
@@ -0,0 +1,40 @@
+// product family (any when None), usage type, service and usage code
+pub const AWS_NETWORK_SERVICES: [(Option<&str>, &str, &str, &str); 7] = [
+    (
+        Some("Load Balancer-Application"),
+        "LoadBalancerUsage",
+        "application-load-balancer",
+        "hours",
+    ),
+    (
+        Some("Load Balancer-Application"),
+        "LCUUsage",
+        "application-load-balancer",
+        "lcu",
+    ),
+    (
+        Some("Load Balancer-Network"),
+        "LoadBalancerUsage",
+        "network-load-balancer",
+        "hours",
+    ),
+    (
+        Some("Load Balancer-Network"),
+        "LCUUsage",
+        "network-load-balancer",
+        "lcu",
+    ),
+    (
+        Some("NAT Gateway"),
+        "NatGateway-Hours",
+        "nat-gateway",
+        "hours",
+    ),
+    (
+        Some("NAT Gateway"),
+        "NatGateway-Bytes",
+        "nat-gateway",
+        "data-processed",
+    ),
+    (None, "PublicIPv4:InUseAddress", "public-ipv4", "hours"),
+];
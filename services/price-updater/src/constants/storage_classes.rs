@@ -0,0 +1,31 @@
+// storage class, usage types of its storage, PUT/COPY/POST/LIST requests, GET requests and retrieval
+pub const S3_STORAGE_CLASSES: [(&str, &str, &str, &str, Option<&str>); 4] = [
+    (
+        "STANDARD",
+        "TimedStorage-ByteHrs",
+        "Requests-Tier1",
+        "Requests-Tier2",
+        None,
+    ),
+    (
+        "STANDARD_IA",
+        "TimedStorage-SIA-ByteHrs",
+        "Requests-SIA-Tier1",
+        "Requests-SIA-Tier2",
+        Some("Retrieval-SIA"),
+    ),
+    (
+        "ONEZONE_IA",
+        "TimedStorage-ZIA-ByteHrs",
+        "Requests-ZIA-Tier1",
+        "Requests-ZIA-Tier2",
+        Some("Retrieval-ZIA"),
+    ),
+    (
+        "GLACIER_IR",
+        "TimedStorage-GIR-ByteHrs",
+        "Requests-GIR-Tier1",
+        "Requests-GIR-Tier2",
+        Some("Retrieval-GIR"),
+    ),
+];
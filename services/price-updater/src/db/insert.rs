@@ -4,16 +4,27 @@ use std::{collections::HashMap, time::Duration};
 use sqlx::Error as SqlxError;
 use sqlx::PgPool;
 
+use crate::models::database::{DatabaseInstance, DatabaseStorage};
 use crate::models::network::ExternalPrice;
 use crate::models::network::InterRegionPrice;
+use crate::models::network_service::NetworkService;
+use crate::models::object_storage::ObjectStorage;
 use crate::models::on_demand_pricing::OnDemandInstance;
 use crate::models::spot_pricing::SpotInstance;
 use crate::models::storage::Storage;
 
 const MAX_RETRIES: usize = 5;
 
+// Render an optional value as a SQL literal
+fn nullable<T: std::fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| String::from("NULL"))
+}
+
 pub async fn on_demand_pricing_in_bulk(
     pool: &PgPool,
+    provider: &str,
     instances: Vec<OnDemandInstance>,
 ) -> Result<(), SqlxError> {
     let mut retries = 0;
@@ -26,7 +37,8 @@ pub async fn on_demand_pricing_in_bulk(
             .iter()
             .map(|entry| {
                 format!(
-                    "('{}', '{}', {}, {}, {}, '{}', '{}', NOW())",
+                    "('{}', '{}', '{}', {}, {}, {}, '{}', '{}', NOW())",
+                    provider,
                     entry.region,
                     entry.instance_name,
                     entry.vcpu_count,
@@ -39,9 +51,9 @@ pub async fn on_demand_pricing_in_bulk(
             .collect();
 
         let insert_query = format!(
-            "INSERT INTO on_demand (region, instance_type, vcpu_count, memory, price_per_hour, architecture, storage, updated_at)
+            "INSERT INTO on_demand (provider, region, instance_type, vcpu_count, memory, price_per_hour, architecture, storage, updated_at)
             VALUES {}
-            ON CONFLICT (region, instance_type)
+            ON CONFLICT (provider, region, instance_type)
             DO UPDATE SET vcpu_count = excluded.vcpu_count, memory = excluded.memory, price_per_hour = excluded.price_per_hour, architecture = excluded.architecture, storage = excluded.storage, updated_at = NOW()",
             values.join(", ")
         );
@@ -72,6 +84,7 @@ pub async fn on_demand_pricing_in_bulk(
 
 pub async fn spot_pricing_in_bulk(
     pool: &PgPool,
+    provider: &str,
     region: String,
     instances: HashMap<String, Vec<SpotInstance>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -85,8 +98,12 @@ pub async fn spot_pricing_in_bulk(
     for (availability_zone, spot_prices) in instances.iter() {
         for spot_price in spot_prices.iter() {
             values_str.push_str(&format!(
-                "('{}', '{}', '{}', {}, NOW()),",
-                region, availability_zone, spot_price.instance_type, spot_price.spot_price
+                "('{}', '{}', '{}', '{}', {}, NOW()),",
+                provider,
+                region,
+                availability_zone,
+                spot_price.instance_type,
+                spot_price.spot_price
             ));
         }
     }
@@ -96,9 +113,9 @@ pub async fn spot_pricing_in_bulk(
 
     // Create the entire SQL query
     let insert_query = format!(
-        "INSERT INTO spot (region, availability_zone, instance_type, price_per_hour, updated_at)
+        "INSERT INTO spot (provider, region, availability_zone, instance_type, price_per_hour, updated_at)
         VALUES {}
-        ON CONFLICT (region, availability_zone, instance_type)
+        ON CONFLICT (provider, region, availability_zone, instance_type)
         DO UPDATE SET price_per_hour = excluded.price_per_hour, updated_at = NOW()",
         values_str
     );
@@ -114,6 +131,7 @@ pub async fn spot_pricing_in_bulk(
 
 pub async fn inter_region_data_transfer_in_bulk(
     pool: &PgPool,
+    provider: &str,
     transfer_prices: HashMap<String, InterRegionPrice>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut tx = pool.begin().await?;
@@ -126,8 +144,8 @@ pub async fn inter_region_data_transfer_in_bulk(
         let price = transfer_price.price_per_gb;
 
         values_str.push_str(&format!(
-            "('{}', '{}', {}, NOW()),",
-            from_region, to_region, price
+            "('{}', '{}', '{}', {}, NOW()),",
+            provider, from_region, to_region, price
         ));
     }
 
@@ -135,9 +153,9 @@ pub async fn inter_region_data_transfer_in_bulk(
     values_str.pop();
 
     let insert_query = format!("
-        INSERT INTO inter_region_data_transfer (from_region_code, to_region_code, price_per_gb, updated_at)
+        INSERT INTO inter_region_data_transfer (provider, from_region_code, to_region_code, price_per_gb, updated_at)
         VALUES {}
-        ON CONFLICT (from_region_code, to_region_code)
+        ON CONFLICT (provider, from_region_code, to_region_code)
         DO UPDATE SET updated_at = NOW()
     ", values_str);
 
@@ -150,6 +168,7 @@ pub async fn inter_region_data_transfer_in_bulk(
 
 pub async fn storage_pricing_in_bulk(
     pool: &PgPool,
+    provider: &str,
     storage_prices: Vec<Storage>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut tx = pool.begin().await?;
@@ -163,8 +182,17 @@ pub async fn storage_pricing_in_bulk(
         let price = storage.price_per_gb_month;
 
         values_str.push_str(&format!(
-            "('{}', '{}', '{}', {}, NOW()),",
-            region, volume_api_name, storage_media, price
+            "('{}', '{}', '{}', '{}', {}, {}, {}, {}, {}, {}, NOW()),",
+            provider,
+            region,
+            volume_api_name,
+            storage_media,
+            price,
+            nullable(storage.price_per_iops_month),
+            nullable(storage.price_per_mibps_month),
+            nullable(storage.included_iops),
+            nullable(storage.included_throughput),
+            nullable(storage.snapshot_price_per_gb_month)
         ));
     }
 
@@ -175,10 +203,10 @@ pub async fn storage_pricing_in_bulk(
 
     let insert_query = format!(
         "
-        INSERT INTO storage (region, volume_api_name, storage_media, price_per_gb_month, updated_at)
+        INSERT INTO storage (provider, region, volume_api_name, storage_media, price_per_gb_month, price_per_iops_month, price_per_mibps_month, included_iops, included_throughput, snapshot_price_per_gb_month, updated_at)
         VALUES {}
-        ON CONFLICT (region, volume_api_name)
-        DO UPDATE SET price_per_gb_month = EXCLUDED.price_per_gb_month, updated_at = NOW()",
+        ON CONFLICT (provider, region, volume_api_name)
+        DO UPDATE SET price_per_gb_month = EXCLUDED.price_per_gb_month, price_per_iops_month = EXCLUDED.price_per_iops_month, price_per_mibps_month = EXCLUDED.price_per_mibps_month, included_iops = EXCLUDED.included_iops, included_throughput = EXCLUDED.included_throughput, snapshot_price_per_gb_month = EXCLUDED.snapshot_price_per_gb_month, updated_at = NOW()",
         values_str
     );
 
@@ -189,8 +217,180 @@ pub async fn storage_pricing_in_bulk(
     Ok(())
 }
 
+pub async fn object_storage_pricing_in_bulk(
+    pool: &PgPool,
+    provider: &str,
+    storage_prices: Vec<ObjectStorage>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if storage_prices.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let values: Vec<String> = storage_prices
+        .iter()
+        .map(|storage| {
+            format!(
+                "('{}', '{}', '{}', {}, {}, {}, {}, NOW())",
+                provider,
+                storage.region,
+                storage.storage_class,
+                storage.price_per_gb_month,
+                storage.price_per_1k_put_requests,
+                storage.price_per_1k_get_requests,
+                storage.retrieval_price_per_gb
+            )
+        })
+        .collect();
+
+    let insert_query = format!(
+        "
+        INSERT INTO object_storage (provider, region, storage_class, price_per_gb_month, price_per_1k_put_requests, price_per_1k_get_requests, retrieval_price_per_gb, updated_at)
+        VALUES {}
+        ON CONFLICT (provider, region, storage_class)
+        DO UPDATE SET price_per_gb_month = EXCLUDED.price_per_gb_month, price_per_1k_put_requests = EXCLUDED.price_per_1k_put_requests, price_per_1k_get_requests = EXCLUDED.price_per_1k_get_requests, retrieval_price_per_gb = EXCLUDED.retrieval_price_per_gb, updated_at = NOW()",
+        values.join(", ")
+    );
+
+    sqlx::query(&insert_query).execute(&mut *tx).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn database_instance_pricing_in_bulk(
+    pool: &PgPool,
+    provider: &str,
+    instances: Vec<DatabaseInstance>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if instances.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let values: Vec<String> = instances
+        .iter()
+        .map(|instance| {
+            format!(
+                "('{}', '{}', '{}', '{}', '{}', {}, {}, {}, NOW())",
+                provider,
+                instance.region,
+                instance.engine,
+                instance.deployment_option,
+                instance.instance_type,
+                instance.vcpu_count,
+                instance.memory,
+                instance.price_per_hour
+            )
+        })
+        .collect();
+
+    let insert_query = format!(
+        "
+        INSERT INTO database_instances (provider, region, engine, deployment_option, instance_type, vcpu_count, memory, price_per_hour, updated_at)
+        VALUES {}
+        ON CONFLICT (provider, region, engine, deployment_option, instance_type)
+        DO UPDATE SET vcpu_count = EXCLUDED.vcpu_count, memory = EXCLUDED.memory, price_per_hour = EXCLUDED.price_per_hour, updated_at = NOW()",
+        values.join(", ")
+    );
+
+    sqlx::query(&insert_query).execute(&mut *tx).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn database_storage_pricing_in_bulk(
+    pool: &PgPool,
+    provider: &str,
+    storage_prices: Vec<DatabaseStorage>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if storage_prices.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let values: Vec<String> = storage_prices
+        .iter()
+        .map(|storage| {
+            format!(
+                "('{}', '{}', '{}', '{}', {}, {}, NOW())",
+                provider,
+                storage.region,
+                storage.deployment_option,
+                storage.volume_type,
+                storage.price_per_gb_month,
+                nullable(storage.backup_price_per_gb_month)
+            )
+        })
+        .collect();
+
+    let insert_query = format!(
+        "
+        INSERT INTO database_storage (provider, region, deployment_option, volume_type, price_per_gb_month, backup_price_per_gb_month, updated_at)
+        VALUES {}
+        ON CONFLICT (provider, region, deployment_option, volume_type)
+        DO UPDATE SET price_per_gb_month = EXCLUDED.price_per_gb_month, backup_price_per_gb_month = EXCLUDED.backup_price_per_gb_month, updated_at = NOW()",
+        values.join(", ")
+    );
+
+    sqlx::query(&insert_query).execute(&mut *tx).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn network_services_pricing_in_bulk(
+    pool: &PgPool,
+    provider: &str,
+    services: Vec<NetworkService>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if services.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let values: Vec<String> = services
+        .iter()
+        .map(|service| {
+            format!(
+                "('{}', '{}', '{}', '{}', '{}', {}, NOW())",
+                provider,
+                service.region,
+                service.service,
+                service.usage,
+                service.unit,
+                service.price_per_unit
+            )
+        })
+        .collect();
+
+    let insert_query = format!(
+        "
+        INSERT INTO network_services (provider, region, service, usage, unit, price_per_unit, updated_at)
+        VALUES {}
+        ON CONFLICT (provider, region, service, usage)
+        DO UPDATE SET unit = EXCLUDED.unit, price_per_unit = EXCLUDED.price_per_unit, updated_at = NOW()",
+        values.join(", ")
+    );
+
+    sqlx::query(&insert_query).execute(&mut *tx).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
 pub async fn external_data_transfer_in_bulk(
     pool: &PgPool,
+    provider: &str,
     external_prices: HashMap<String, ExternalPrice>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut tx = pool.begin().await?;
@@ -201,8 +401,8 @@ pub async fn external_data_transfer_in_bulk(
         let from_region_code = &external_price.from_region_code;
         for tier in &external_price.tiers {
             values_str.push_str(&format!(
-                "('{}', {}, {}, {}, NOW()),",
-                from_region_code, tier.start_range, tier.end_range, tier.price_per_gb
+                "('{}', '{}', {}, {}, {}, NOW()),",
+                provider, from_region_code, tier.start_range, tier.end_range, tier.price_per_gb
             ));
         }
     }
@@ -212,9 +412,9 @@ pub async fn external_data_transfer_in_bulk(
 
     let insert_query = format!(
         "
-        INSERT INTO external_data_transfer (from_region_code, start_range, end_range, price_per_gb, updated_at)
+        INSERT INTO external_data_transfer (provider, from_region_code, start_range, end_range, price_per_gb, updated_at)
         VALUES {}
-        ON CONFLICT (from_region_code, start_range, end_range)
+        ON CONFLICT (provider, from_region_code, start_range, end_range)
         DO UPDATE SET updated_at = NOW(), price_per_gb = EXCLUDED.price_per_gb
     ",
         values_str
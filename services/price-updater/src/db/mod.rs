@@ -1,4 +1,5 @@
 pub mod insert;
+pub mod offer_version;
 
 use anyhow::Result;
 use sqlx::PgPool;
@@ -0,0 +1,47 @@
+use sqlx::Error as SqlxError;
+use sqlx::PgPool;
+
+use crate::models::offer_index::OfferVersion;
+
+pub async fn fetch_offer_version(
+    pool: &PgPool,
+    provider: &str,
+    service: &str,
+    region: &str,
+) -> Result<Option<OfferVersion>, SqlxError> {
+    sqlx::query_as::<_, OfferVersion>(
+        "SELECT version, publication_date, etag, last_modified FROM offer_versions
+        WHERE provider = $1 AND service = $2 AND region = $3",
+    )
+    .bind(provider)
+    .bind(service)
+    .bind(region)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn save_offer_version(
+    pool: &PgPool,
+    provider: &str,
+    service: &str,
+    region: &str,
+    offer_version: &OfferVersion,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        "INSERT INTO offer_versions (provider, service, region, version, publication_date, etag, last_modified, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+        ON CONFLICT (provider, service, region)
+        DO UPDATE SET version = excluded.version, publication_date = excluded.publication_date, etag = excluded.etag, last_modified = excluded.last_modified, updated_at = NOW()",
+    )
+    .bind(provider)
+    .bind(service)
+    .bind(region)
+    .bind(&offer_version.version)
+    .bind(&offer_version.publication_date)
+    .bind(&offer_version.etag)
+    .bind(&offer_version.last_modified)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
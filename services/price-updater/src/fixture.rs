@@ -0,0 +1,5 @@
+// Contents of a file in the fixtures directory
+pub fn fixture(path: &str) -> String {
+    std::fs::read_to_string(format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), path))
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path, e))
+}
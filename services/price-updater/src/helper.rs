@@ -1,21 +1,36 @@
 use futures_util::StreamExt;
 use sqlx::PgPool;
+use strum::IntoEnumIterator;
+use types::region::{AwsRegion, AzureRegion};
 
-use crate::{
-    api::aws::{
+use crate::api::{
+    aws::{
+        database::update_database_pricing_for_region,
         instance::{update_pricing_for_region, update_spot_pricing_for_region},
         network::update_inter_region_networking_pricing,
+        network_services::update_network_services_pricing_for_region,
+        object_storage::update_object_storage_pricing_for_region,
+        offer::{fetch_region_index, EC2_OFFER, ELB_OFFER, RDS_OFFER, S3_OFFER, VPC_OFFER},
+    },
+    azure,
+    gcp::{
+        catalog::{fetch_skus, COMPUTE_ENGINE_SERVICE_ID},
+        instance::update_compute_pricing,
+        network::update_networking_pricing,
     },
-    constants::regions::AWS_REGIONS,
 };
 
 pub async fn update_on_demand_pricing_index(
     pool: PgPool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let region_index = fetch_region_index(EC2_OFFER).await?;
+
+    let region_codes: Vec<String> = AwsRegion::iter().map(|region| region.code()).collect();
+
     let regions_stream = futures_util::stream::iter(
-        AWS_REGIONS
+        region_codes
             .iter()
-            .map(|&region| update_pricing_for_region(pool.clone(), region)),
+            .map(|region| update_pricing_for_region(pool.clone(), region, &region_index)),
     );
 
     regions_stream
@@ -30,12 +45,63 @@ pub async fn update_on_demand_pricing_index(
     Ok(())
 }
 
+// Function to update object storage pricing for all regions
+pub async fn update_object_storage_pricing_index(
+    pool: PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let region_index = fetch_region_index(S3_OFFER).await?;
+
+    let region_codes: Vec<String> = AwsRegion::iter().map(|region| region.code()).collect();
+
+    let regions_stream = futures_util::stream::iter(region_codes.iter().map(|region| {
+        update_object_storage_pricing_for_region(pool.clone(), region, &region_index)
+    }));
+
+    regions_stream
+        .for_each_concurrent(6, |fut| async {
+            if let Err(err) = fut.await {
+                println!(
+                    "Failed to update object storage pricing for region: {:?}",
+                    err
+                );
+            }
+        })
+        .await;
+
+    Ok(())
+}
+
+// Function to update database pricing for all regions
+pub async fn update_database_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let region_index = fetch_region_index(RDS_OFFER).await?;
+
+    let region_codes: Vec<String> = AwsRegion::iter().map(|region| region.code()).collect();
+
+    let regions_stream = futures_util::stream::iter(
+        region_codes
+            .iter()
+            .map(|region| update_database_pricing_for_region(pool.clone(), region, &region_index)),
+    );
+
+    regions_stream
+        .for_each_concurrent(6, |fut| async {
+            if let Err(err) = fut.await {
+                println!("Failed to update database pricing for region: {:?}", err);
+            }
+        })
+        .await;
+
+    Ok(())
+}
+
 // Function to update spot pricing index for all regions
 pub async fn update_spot_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let region_codes: Vec<String> = AwsRegion::iter().map(|region| region.code()).collect();
+
     let regions_stream = futures_util::stream::iter(
-        AWS_REGIONS
+        region_codes
             .iter()
-            .map(|&region| update_spot_pricing_for_region(pool.clone(), region)),
+            .map(|region| update_spot_pricing_for_region(pool.clone(), region)),
     );
 
     regions_stream
@@ -52,7 +118,56 @@ pub async fn update_spot_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::
 
 // Function to update the network pricing index for all regions
 pub async fn update_network_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
-    update_inter_region_networking_pricing(pool).await?;
+    update_inter_region_networking_pricing(pool.clone()).await?;
+
+    // Load balancers and public addresses, NAT gateways come with the EC2 offer
+    for service in [ELB_OFFER, VPC_OFFER] {
+        let region_index = fetch_region_index(service).await?;
+
+        let region_codes: Vec<String> = AwsRegion::iter().map(|region| region.code()).collect();
+
+        let regions_stream = futures_util::stream::iter(region_codes.iter().map(|region| {
+            update_network_services_pricing_for_region(pool.clone(), service, region, &region_index)
+        }));
+
+        regions_stream
+            .for_each_concurrent(6, |fut| async {
+                if let Err(err) = fut.await {
+                    println!("Failed to update {} pricing for region: {:?}", service, err);
+                }
+            })
+            .await;
+    }
+
+    Ok(())
+}
+
+// Function to update compute, storage and network pricing for GCP from the billing catalog
+pub async fn update_gcp_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let skus = fetch_skus(COMPUTE_ENGINE_SERVICE_ID).await?;
+
+    update_compute_pricing(pool.clone(), &skus).await?;
+    update_networking_pricing(pool, &skus).await?;
+
+    Ok(())
+}
+
+// Function to update vm, disk and bandwidth pricing for all Azure regions
+pub async fn update_azure_pricing_index(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let regions_stream = futures_util::stream::iter(
+        AzureRegion::iter()
+            .map(|region| azure::instance::update_pricing_for_region(pool.clone(), region)),
+    );
+
+    regions_stream
+        .for_each_concurrent(6, |fut| async {
+            if let Err(err) = fut.await {
+                println!("Failed to update Azure pricing for region: {:?}", err);
+            }
+        })
+        .await;
+
+    azure::network::update_networking_pricing(pool).await?;
 
     Ok(())
 }
@@ -0,0 +1,9 @@
+pub mod api;
+pub mod constants;
+pub mod db;
+pub mod helper;
+pub mod models;
+pub mod types;
+
+#[cfg(test)]
+mod fixture;
@@ -1,10 +1,4 @@
-pub mod api;
-pub mod constants;
-pub mod db;
-pub mod helper;
-pub mod models;
-pub mod types;
-
+use price_updater::{db, helper};
 use std::time::Duration;
 use tokio::{join, time::sleep};
 
@@ -13,8 +7,18 @@ async fn main() -> std::io::Result<()> {
     // Create a new connection pool
     let pool = db::connect().await.unwrap();
 
+    // bring the tables up to date before any prices are written
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run migrations");
+
     let pool_for_on_demand = pool.clone();
     let pool_for_spot = pool.clone();
+    let pool_for_gcp = pool.clone();
+    let pool_for_azure = pool.clone();
+    let pool_for_object_storage = pool.clone();
+    let pool_for_database = pool.clone();
 
     // Task for update_on_demand_pricing_index every 6 hours
     let on_demand_pricing_task = tokio::spawn(async move {
@@ -30,6 +34,33 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    // Task for updating object storage pricing every 6 hours
+    let object_storage_pricing_task = tokio::spawn(async move {
+        let interval = Duration::from_secs(6 * 60 * 60);
+
+        loop {
+            if let Err(err) =
+                helper::update_object_storage_pricing_index(pool_for_object_storage.clone()).await
+            {
+                println!("Failed to update object storage pricing: {:?}", err);
+            }
+            sleep(interval).await;
+        }
+    });
+
+    // Task for updating database pricing every 6 hours
+    let database_pricing_task = tokio::spawn(async move {
+        let interval = Duration::from_secs(6 * 60 * 60);
+
+        loop {
+            if let Err(err) = helper::update_database_pricing_index(pool_for_database.clone()).await
+            {
+                println!("Failed to update database pricing: {:?}", err);
+            }
+            sleep(interval).await;
+        }
+    });
+
     // Task for update_spot_pricing_index every 2 minutes
     let spot_pricing_task = tokio::spawn(async move {
         let interval = Duration::from_secs(2 * 60);
@@ -54,11 +85,39 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    // Task for updating GCP pricing every 6 hours
+    let gcp_pricing_task = tokio::spawn(async move {
+        let interval = Duration::from_secs(6 * 60 * 60);
+
+        loop {
+            if let Err(err) = helper::update_gcp_pricing_index(pool_for_gcp.clone()).await {
+                println!("Failed to update GCP pricing: {:?}", err);
+            }
+            sleep(interval).await;
+        }
+    });
+
+    // Task for updating Azure pricing every 6 hours
+    let azure_pricing_task = tokio::spawn(async move {
+        let interval = Duration::from_secs(6 * 60 * 60);
+
+        loop {
+            if let Err(err) = helper::update_azure_pricing_index(pool_for_azure.clone()).await {
+                println!("Failed to update Azure pricing: {:?}", err);
+            }
+            sleep(interval).await;
+        }
+    });
+
     // Join all tasks to ensure they continue running
     let _ = join!(
         on_demand_pricing_task,
+        object_storage_pricing_task,
+        database_pricing_task,
         spot_pricing_task,
-        network_pricing_task
+        network_pricing_task,
+        gcp_pricing_task,
+        azure_pricing_task
     );
 
     Ok(())
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::force_f32::ForceF32;
+
+// A page of skus from the Cloud Billing Catalog API
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogResponse {
+    #[serde(default)]
+    pub skus: Vec<Sku>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Sku {
+    pub sku_id: String,
+    pub description: String,
+    pub category: Category,
+    #[serde(default)]
+    pub service_regions: Vec<String>,
+    #[serde(default)]
+    pub pricing_info: Vec<PricingInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Category {
+    pub resource_family: String,
+    pub resource_group: String,
+    pub usage_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PricingInfo {
+    pub pricing_expression: PricingExpression,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PricingExpression {
+    pub usage_unit: String,
+    #[serde(default)]
+    pub tiered_rates: Vec<TieredRate>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TieredRate {
+    #[serde(default)]
+    pub start_usage_amount: f64,
+    pub unit_price: Money,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Money {
+    pub currency_code: String,
+    // whole units of the amount, encoded as a string
+    pub units: Option<ForceF32>,
+    // nano (10^-9) units of the amount
+    pub nanos: Option<i64>,
+}
+
+impl Money {
+    pub fn amount(&self) -> f32 {
+        let units = self.units.as_ref().map(|u| u.0).unwrap_or(0.0);
+        let nanos = self.nanos.unwrap_or(0) as f32 / 1_000_000_000.0;
+
+        units + nanos
+    }
+}
+
+impl Sku {
+    // The tiered rates of the current pricing expression, ordered by usage
+    pub fn tiered_rates(&self) -> Vec<TieredRate> {
+        let mut rates = self
+            .pricing_info
+            .first()
+            .map(|info| info.pricing_expression.tiered_rates.clone())
+            .unwrap_or_default();
+
+        rates.sort_by(|a, b| {
+            a.start_usage_amount
+                .partial_cmp(&b.start_usage_amount)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        rates
+    }
+
+    // The rate charged once any free usage tiers have been exhausted
+    pub fn unit_price(&self) -> Option<f32> {
+        self.tiered_rates()
+            .last()
+            .map(|rate| rate.unit_price.amount())
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DatabaseInstance {
+    pub region: String,
+    pub engine: String,
+    pub deployment_option: String,
+    pub instance_type: String,
+    pub vcpu_count: f32,
+    pub memory: f32,
+    pub price_per_hour: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DatabaseStorage {
+    pub region: String,
+    pub deployment_option: String,
+    pub volume_type: String,
+    pub price_per_gb_month: f32,
+    pub backup_price_per_gb_month: Option<f32>,
+}
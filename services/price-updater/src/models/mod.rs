@@ -1,4 +1,10 @@
+pub mod catalog;
+pub mod database;
 pub mod network;
+pub mod network_service;
+pub mod object_storage;
+pub mod offer_index;
 pub mod on_demand_pricing;
+pub mod retail_prices;
 pub mod spot_pricing;
 pub mod storage;
@@ -1,17 +1,9 @@
-use std::collections::HashMap;
-
 use serde::{Deserialize, Serialize};
 
 use crate::types::force_u32::ForceU32;
 
 use super::on_demand_pricing::PricePerUnit;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct DataTransferResponse {
-    pub products: HashMap<String, Product>,
-    pub terms: Terms,
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Product {
@@ -30,12 +22,6 @@ pub struct ProductAttributes {
     pub to_region_code: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Terms {
-    #[serde(rename = "OnDemand")]
-    pub on_demand: HashMap<String, HashMap<String, OnDemandTerm>>,
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OnDemandTerm {
@@ -65,7 +51,7 @@ pub struct ExternalPrice {
     pub tiers: Vec<ExternalTier>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ExternalTier {
     pub start_range: u32,
     pub end_range: u32,
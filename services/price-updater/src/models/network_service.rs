@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkService {
+    pub region: String,
+    // e.g. application-load-balancer, nat-gateway or public-ipv4
+    pub service: String,
+    // e.g. hours, lcu or data-processed
+    pub usage: String,
+    pub unit: String,
+    pub price_per_unit: f32,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ObjectStorage {
+    pub region: String,
+    pub storage_class: String,
+    pub price_per_gb_month: f32,
+    pub price_per_1k_put_requests: f32,
+    pub price_per_1k_get_requests: f32,
+    pub retrieval_price_per_gb: f32,
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// offers/v1.0/aws/<service>/current/region_index.json
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionIndex {
+    pub publication_date: String,
+    pub regions: HashMap<String, RegionOffer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionOffer {
+    pub region_code: String,
+    pub current_version_url: String,
+}
+
+// offers/v1.0/aws/<service>/index.json
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionIndex {
+    pub publication_date: String,
+    pub current_version: String,
+    pub versions: HashMap<String, PublishedVersion>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedVersion {
+    pub offer_version_url: String,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct OfferVersion {
+    pub version: String,
+    pub publication_date: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
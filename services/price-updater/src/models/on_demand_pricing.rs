@@ -4,12 +4,6 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::force_f32::ForceF32;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct BulkPricingResponse {
-    pub products: Option<HashMap<String, Product>>,
-    pub terms: Option<Terms>,
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Product {
@@ -28,12 +22,11 @@ pub struct Attribute {
     pub physical_processor: Option<String>,
     pub storage_media: Option<String>,
     pub volume_api_name: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Terms {
-    #[serde(rename = "OnDemand")]
-    pub on_demand: HashMap<String, HashMap<String, OnDemandTerms>>,
+    pub group: Option<String>,
+    pub usagetype: Option<String>,
+    pub database_engine: Option<String>,
+    pub deployment_option: Option<String>,
+    pub volume_type: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -47,6 +40,8 @@ pub struct OnDemandTerms {
 #[serde(rename_all = "camelCase")]
 pub struct PriceDimension {
     pub description: Option<String>,
+    pub unit: Option<String>,
+    pub begin_range: Option<ForceF32>,
     pub price_per_unit: PricePerUnit,
 }
 
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+// A page of prices from the Azure Retail Prices API
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RetailPricesResponse {
+    #[serde(default)]
+    pub items: Vec<RetailPrice>,
+    pub next_page_link: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetailPrice {
+    pub currency_code: String,
+    #[serde(default)]
+    pub tier_minimum_units: f64,
+    pub retail_price: f64,
+    pub arm_region_name: String,
+    pub product_name: String,
+    pub sku_name: String,
+    pub service_name: String,
+    pub meter_name: String,
+    pub unit_of_measure: String,
+    pub r#type: String,
+    #[serde(default)]
+    pub arm_sku_name: String,
+}
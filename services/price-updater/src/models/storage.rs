@@ -6,4 +6,9 @@ pub struct Storage {
     pub storage_media: String,
     pub volume_api_name: String,
     pub price_per_gb_month: f32,
+    pub price_per_iops_month: Option<f32>,
+    pub price_per_mibps_month: Option<f32>,
+    pub included_iops: Option<u32>,
+    pub included_throughput: Option<u32>,
+    pub snapshot_price_per_gb_month: Option<f32>,
 }
@@ -8,5 +8,6 @@ pub fn str_to_f32<'de, D>(deserializer: D) -> Result<f32, D::Error>
 where
     D: Deserializer<'de>,
 {
-    <&str>::deserialize(deserializer).and_then(|s| s.parse().map_err(D::Error::custom))
+    // offer files are streamed, so the strings can't be borrowed from the input
+    String::deserialize(deserializer).and_then(|s| s.parse().map_err(D::Error::custom))
 }
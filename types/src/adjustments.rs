@@ -0,0 +1,164 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+use crate::region::Region;
+
+// Groups of breakdown lines a discount applies to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ServiceCategory {
+    Compute,
+    Storage,
+    Database,
+    Network,
+}
+
+// Discounts, uplifts and tax that turn list prices into what is actually paid, e.g.
+//
+// tax_rate = 20.0
+//
+// [discounts]
+// compute = 12.5
+// storage = 5.0
+//
+// [region_uplifts]
+// "aws:eu-west-1" = 3.0
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Adjustments {
+    // percentage taken off the list price of each category
+    #[serde(default)]
+    pub discounts: HashMap<ServiceCategory, f64>,
+    // percentage added to every price in a region, keyed by qualified or bare region code
+    #[serde(default)]
+    pub region_uplifts: HashMap<String, f64>,
+    // percentage of tax added after discounts and uplifts
+    #[serde(default)]
+    pub tax_rate: f64,
+}
+
+impl Adjustments {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            format!(
+                "Failed to read adjustments from {}: {}",
+                path.as_ref().display(),
+                e
+            )
+        })?;
+
+        toml::from_str(&contents).map_err(|e| {
+            format!(
+                "Failed to parse adjustments from {}: {}",
+                path.as_ref().display(),
+                e
+            )
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.discounts.values().all(|discount| *discount == 0.0)
+            && self.region_uplifts.values().all(|uplift| *uplift == 0.0)
+            && self.tax_rate == 0.0
+    }
+
+    // Multiplier from the list price to the effective price of a line in a region
+    pub fn factor(&self, category: Option<ServiceCategory>, region: &Region) -> f64 {
+        let discount = category
+            .and_then(|category| self.discounts.get(&category))
+            .copied()
+            .unwrap_or(0.0);
+
+        let uplift = region
+            .qualified_code()
+            .and_then(|code| self.region_uplifts.get(&code))
+            .or_else(|| {
+                region
+                    .code()
+                    .and_then(|code| self.region_uplifts.get(&code))
+            })
+            .copied()
+            .unwrap_or(0.0);
+
+        (1.0 - discount / 100.0) * (1.0 + uplift / 100.0) * (1.0 + self.tax_rate / 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::region::AwsRegion;
+
+    use super::*;
+
+    fn adjustments(toml: &str) -> Adjustments {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn assert_factor(adjustments: &Adjustments, category: Option<ServiceCategory>, expected: f64) {
+        let factor = adjustments.factor(category, &Region::Aws(AwsRegion::EuWest1));
+
+        assert!(
+            (factor - expected).abs() < 1e-9,
+            "factor is {}, expected {}",
+            factor,
+            expected
+        );
+    }
+
+    #[test]
+    fn no_adjustments_keep_the_list_price() {
+        let adjustments = Adjustments::default();
+
+        assert!(adjustments.is_empty());
+        assert_factor(&adjustments, Some(ServiceCategory::Compute), 1.0);
+        assert_factor(&adjustments, None, 1.0);
+    }
+
+    #[test]
+    fn discounts_apply_to_their_category_only() {
+        let adjustments = adjustments("[discounts]\ncompute = 12.5\nstorage = 5.0");
+
+        assert_factor(&adjustments, Some(ServiceCategory::Compute), 0.875);
+        assert_factor(&adjustments, Some(ServiceCategory::Storage), 0.95);
+        assert_factor(&adjustments, Some(ServiceCategory::Network), 1.0);
+        assert_factor(&adjustments, None, 1.0);
+    }
+
+    #[test]
+    fn uplifts_and_tax_compound_with_discounts() {
+        let adjustments = adjustments(
+            "tax_rate = 20.0\n\n[discounts]\ndatabase = 10.0\n\n[region_uplifts]\n\"eu-west-1\" = 5.0",
+        );
+
+        assert_factor(
+            &adjustments,
+            Some(ServiceCategory::Database),
+            0.9 * 1.05 * 1.2,
+        );
+        assert_factor(&adjustments, None, 1.05 * 1.2);
+    }
+
+    #[test]
+    fn qualified_uplifts_win_over_bare_region_codes() {
+        let adjustments = adjustments(
+            "[region_uplifts]\n\"aws:eu-west-1\" = 3.0\n\"eu-west-1\" = 50.0\n\"us-east-1\" = 10.0",
+        );
+
+        assert_factor(&adjustments, None, 1.03);
+        assert_eq!(
+            adjustments.factor(None, &Region::Aws(AwsRegion::USEast1)),
+            1.1
+        );
+    }
+
+    #[test]
+    fn uplifts_of_other_regions_are_ignored() {
+        let adjustments = adjustments("[region_uplifts]\n\"aws:us-east-1\" = 10.0");
+
+        assert!(!adjustments.is_empty());
+        assert_factor(&adjustments, Some(ServiceCategory::Compute), 1.0);
+        assert_eq!(adjustments.factor(None, &Region::None), 1.0);
+    }
+}
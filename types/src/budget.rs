@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Monthly budget of an app in USD, declared in its infra.toml or stored for the repository,
+// e.g.
+//
+// [budget]
+// warning = 400.0
+// limit = 500.0
+// max_increase = 10.0
+//
+// [budget.components]
+// "Data Transfer" = { limit = 50.0 }
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    // monthly cost above which the check warns, without failing
+    pub warning: Option<f64>,
+    // monthly cost above which the check fails
+    pub limit: Option<f64>,
+    // percentage the monthly cost may go up by in a single change before the check fails
+    pub max_increase: Option<f64>,
+    // thresholds of single lines of the breakdown, by line name
+    #[serde(default)]
+    pub components: BTreeMap<String, Threshold>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    pub warning: Option<f64>,
+    pub limit: Option<f64>,
+}
+
+impl Budget {
+    // Budget with just a hard limit, as cost limits were stored before budgets
+    pub fn with_limit(limit: f64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+}
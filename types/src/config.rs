@@ -3,7 +3,10 @@ use std::str::FromStr;
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
 
-use super::{architecture::Architecture, cloud_provider::CloudProvider, region::Region};
+use super::{
+    architecture::Architecture, budget::Budget, cloud_provider::CloudProvider, currency::Currency,
+    region::Region,
+};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RegistryCredentials {
@@ -26,6 +29,27 @@ pub struct InfrastructureConfiguration {
 
     // storage
     pub storage: Option<Storage>,
+
+    // object storage, e.g. s3 buckets
+    pub object_storage: Option<ObjectStorage>,
+
+    // managed database, e.g. rds or aurora
+    pub database: Option<Database>,
+
+    // ingress and public addressing of the nodes
+    pub network: Option<Network>,
+
+    // how the kubernetes control plane is run, self hosted by default
+    pub control_plane: Option<ControlPlaneMode>,
+
+    // autoscaling bounds of the nodes
+    pub scaling: Option<Scaling>,
+
+    // currency costs are shown in, USD by default
+    pub currency: Option<Currency>,
+
+    // monthly budget the cost bot checks the app against
+    pub budget: Option<Budget>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -40,6 +64,235 @@ pub struct Storage {
     pub size: f64,
     pub unit: StorageUnit,
     pub iops: Option<u64>,
+    // volume type to price, e.g. gp3 or io2. Defaults to gp3 when iops, throughput or snapshots are set
+    pub volume: Option<String>,
+    // provisioned throughput in MiB/s
+    pub throughput: Option<u64>,
+    pub snapshots: Option<SnapshotRetention>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotRetention {
+    // number of snapshots kept at any time
+    pub count: u32,
+    // fraction of the volume that changes between two snapshots
+    pub change_rate: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ObjectStorage {
+    // stored data in GB
+    pub size: f64,
+    // storage class, e.g. STANDARD or STANDARD_IA. Defaults to STANDARD
+    pub class: Option<String>,
+    // PUT, COPY, POST and LIST requests per month
+    pub put_requests: Option<u64>,
+    // GET requests per month
+    pub get_requests: Option<u64>,
+    // data retrieved per month in GB, only charged for infrequent access classes
+    pub retrieval: Option<f64>,
+}
+
+impl ObjectStorage {
+    // storage classes the price updater ingests prices for
+    pub const CLASSES: [&'static str; 4] = ["STANDARD", "STANDARD_IA", "ONEZONE_IA", "GLACIER_IR"];
+
+    pub fn class(&self) -> &str {
+        self.class.as_deref().unwrap_or("STANDARD")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Database {
+    // engine, e.g. postgres, mysql or aurora-postgresql
+    pub engine: String,
+    // instance class, e.g. db.t4g.medium. The cheapest one matching vcpu and memory otherwise
+    pub instance_type: Option<String>,
+    pub vcpu: Option<u32>,
+    pub memory: Option<f32>,
+    // keep a standby in a second availability zone
+    pub multi_az: Option<bool>,
+    // allocated storage in GB
+    pub storage: f64,
+    // storage type, e.g. gp2, gp3 or io1. Defaults to gp3, aurora engines always use aurora
+    pub storage_type: Option<String>,
+    // retained backups in GB, storage up to the allocated size is free
+    pub backup: Option<f64>,
+}
+
+impl Database {
+    pub fn deployment_option(&self) -> &'static str {
+        if self.multi_az.unwrap_or(false) {
+            "multi-az"
+        } else {
+            "single-az"
+        }
+    }
+
+    pub fn volume_type(&self) -> String {
+        if self.engine.starts_with("aurora") {
+            return String::from("aurora");
+        }
+
+        self.storage_type
+            .clone()
+            .unwrap_or_else(|| String::from("gp3"))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Network {
+    // load balancer in front of the app, none by default
+    pub ingress: Option<Ingress>,
+    // average load balancer capacity units consumed per hour. Defaults to 1
+    pub lcu: Option<f64>,
+    // give every node a public ipv4 address, otherwise they reach the internet through a nat
+    // gateway. Defaults to true
+    pub public_ip: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Ingress {
+    Alb,
+    Nlb,
+    None,
+}
+
+impl Ingress {
+    // service the load balancer is priced under
+    pub fn service(&self) -> Option<&'static str> {
+        match self {
+            Ingress::Alb => Some("application-load-balancer"),
+            Ingress::Nlb => Some("network-load-balancer"),
+            Ingress::None => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ControlPlaneMode {
+    SelfHosted,
+    Eks {
+        // the kubernetes version is past standard support
+        extended_support: Option<bool>,
+    },
+    None,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "UncheckedScaling")]
+pub struct Scaling {
+    pub min_replicas: u32,
+    pub max_replicas: u32,
+    // share of the range between min and max in use, between 0 and 1
+    pub utilisation: Option<Utilisation>,
+}
+
+// Scaling as written in the infra.toml, before its bounds and profiles are checked
+#[derive(Deserialize)]
+struct UncheckedScaling {
+    min_replicas: u32,
+    max_replicas: u32,
+    utilisation: Option<Utilisation>,
+}
+
+impl TryFrom<UncheckedScaling> for Scaling {
+    type Error = String;
+
+    fn try_from(scaling: UncheckedScaling) -> Result<Self, Self::Error> {
+        if scaling.min_replicas > scaling.max_replicas {
+            return Err(format!(
+                "min_replicas ({}) is above max_replicas ({})",
+                scaling.min_replicas, scaling.max_replicas
+            ));
+        }
+
+        if let Some(utilisation) = scaling.utilisation.as_ref() {
+            utilisation.validate()?;
+        }
+
+        Ok(Scaling {
+            min_replicas: scaling.min_replicas,
+            max_replicas: scaling.max_replicas,
+            utilisation: scaling.utilisation,
+        })
+    }
+}
+
+impl Scaling {
+    // replicas running on average, halfway between min and max without a utilisation profile
+    pub fn expected_replicas(&self) -> f64 {
+        let utilisation = self
+            .utilisation
+            .as_ref()
+            .map(|utilisation| utilisation.average())
+            .unwrap_or(0.5)
+            .clamp(0.0, 1.0);
+
+        self.min_replicas as f64
+            + utilisation * (self.max_replicas as f64 - self.min_replicas as f64)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Utilisation {
+    // the same 24 hourly values every day
+    Daily(Vec<f64>),
+    // 24 hourly values for weekdays and for weekends
+    Weekly {
+        weekday: Vec<f64>,
+        weekend: Vec<f64>,
+    },
+}
+
+impl Utilisation {
+    // every profile has a value for each hour of the day, between 0 and 1
+    fn validate(&self) -> Result<(), String> {
+        let profiles = match self {
+            Utilisation::Daily(hours) => vec![("utilisation", hours)],
+            Utilisation::Weekly { weekday, weekend } => {
+                vec![("weekday", weekday), ("weekend", weekend)]
+            }
+        };
+
+        for (name, hours) in profiles {
+            if hours.len() != 24 {
+                return Err(format!(
+                    "{} has {} hourly values, expected 24",
+                    name,
+                    hours.len()
+                ));
+            }
+
+            if let Some((hour, value)) = hours
+                .iter()
+                .enumerate()
+                .find(|(_, value)| !(0.0..=1.0).contains(*value))
+            {
+                return Err(format!(
+                    "{} is {} at hour {}, expected a value between 0 and 1",
+                    name, value, hour
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // average utilisation over a week
+    pub fn average(&self) -> f64 {
+        let mean = |hours: &[f64]| hours.iter().sum::<f64>() / hours.len().max(1) as f64;
+
+        match self {
+            Utilisation::Daily(hours) => mean(hours),
+            Utilisation::Weekly { weekday, weekend } => {
+                (5.0 * mean(weekday) + 2.0 * mean(weekend)) / 7.0
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -134,6 +387,8 @@ pub struct InfrastructureConfigurationBuilder {
     build_with_max_vcpu: Option<u32>,
     // max mem that can be used for the build, in megabytes
     build_with_max_memory: Option<u32>,
+    // currency the user wants costs shown in
+    currency: Option<Currency>,
 }
 
 impl InfrastructureConfigurationBuilder {
@@ -145,6 +400,7 @@ impl InfrastructureConfigurationBuilder {
             architecture: None,
             build_with_max_vcpu: None,
             build_with_max_memory: None,
+            currency: None,
         }
     }
 
@@ -181,6 +437,12 @@ impl InfrastructureConfigurationBuilder {
         self
     }
 
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+
+        self
+    }
+
     pub fn build(self) -> InfrastructureConfiguration {
         InfrastructureConfiguration {
             app: App {
@@ -202,7 +464,17 @@ impl InfrastructureConfigurationBuilder {
                 size: 0.0,
                 unit: StorageUnit::GB,
                 iops: None,
+                volume: None,
+                throughput: None,
+                snapshots: None,
             }),
+            object_storage: None,
+            database: None,
+            network: None,
+            control_plane: None,
+            scaling: None,
+            currency: self.currency,
+            budget: None,
         }
     }
 }
@@ -291,3 +563,146 @@ impl InternalAWSConfiguration {
 pub struct GlobalConfiguration {
     pub api_key: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaling(toml: &str) -> Result<Scaling, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    // a day quiet overnight and busy during the day, averaging 0.5
+    fn day() -> Vec<f64> {
+        (0..24)
+            .map(|hour| if hour < 12 { 0.25 } else { 0.75 })
+            .collect()
+    }
+
+    #[test]
+    fn expected_replicas_are_halfway_without_a_profile() {
+        let scaling = scaling("min_replicas = 2\nmax_replicas = 10").unwrap();
+
+        assert_eq!(scaling.expected_replicas(), 6.0);
+    }
+
+    #[test]
+    fn expected_replicas_follow_a_daily_profile() {
+        let scaling = Scaling {
+            min_replicas: 2,
+            max_replicas: 10,
+            utilisation: Some(Utilisation::Daily(vec![0.25; 24])),
+        };
+
+        assert_eq!(scaling.expected_replicas(), 4.0);
+    }
+
+    #[test]
+    fn expected_replicas_follow_a_weekly_profile() {
+        let scaling = Scaling {
+            min_replicas: 0,
+            max_replicas: 14,
+            utilisation: Some(Utilisation::Weekly {
+                weekday: vec![0.7; 24],
+                weekend: vec![0.0; 24],
+            }),
+        };
+
+        // 5 weekdays at 0.7 and 2 idle weekend days average 0.5
+        assert!((scaling.expected_replicas() - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn expected_replicas_stay_at_a_fixed_size() {
+        let scaling = Scaling {
+            min_replicas: 3,
+            max_replicas: 3,
+            utilisation: Some(Utilisation::Daily(day())),
+        };
+
+        assert_eq!(scaling.expected_replicas(), 3.0);
+    }
+
+    #[test]
+    fn weekly_average_weighs_weekdays_and_weekends() {
+        let utilisation = Utilisation::Weekly {
+            weekday: vec![1.0; 24],
+            weekend: day(),
+        };
+
+        assert!((utilisation.average() - (5.0 + 2.0 * 0.5) / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn daily_average_is_the_mean_of_the_hours() {
+        assert!((Utilisation::Daily(day()).average() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn profiles_are_read_from_the_infra_toml() {
+        let daily = scaling(&format!(
+            "min_replicas = 1\nmax_replicas = 5\nutilisation = {:?}",
+            day()
+        ))
+        .unwrap();
+
+        assert!(matches!(daily.utilisation, Some(Utilisation::Daily(_))));
+
+        let weekly = scaling(&format!(
+            "min_replicas = 1\nmax_replicas = 5\n[utilisation]\nweekday = {:?}\nweekend = {:?}",
+            day(),
+            vec![0.0; 24]
+        ))
+        .unwrap();
+
+        assert!(matches!(
+            weekly.utilisation,
+            Some(Utilisation::Weekly { .. })
+        ));
+    }
+
+    #[test]
+    fn min_replicas_above_max_replicas_are_rejected() {
+        let error = scaling("min_replicas = 5\nmax_replicas = 2").unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("min_replicas (5) is above max_replicas (2)"));
+    }
+
+    #[test]
+    fn profiles_without_24_hours_are_rejected() {
+        let error =
+            scaling("min_replicas = 1\nmax_replicas = 5\nutilisation = [0.5, 0.5]").unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("utilisation has 2 hourly values, expected 24"));
+
+        let error = scaling(&format!(
+            "min_replicas = 1\nmax_replicas = 5\n[utilisation]\nweekday = {:?}\nweekend = [1.0]",
+            day()
+        ))
+        .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("weekend has 1 hourly values, expected 24"));
+    }
+
+    #[test]
+    fn utilisation_outside_0_and_1_is_rejected() {
+        let mut hours = vec![0.5; 24];
+        hours[7] = 1.5;
+
+        let error = scaling(&format!(
+            "min_replicas = 1\nmax_replicas = 5\nutilisation = {:?}",
+            hours
+        ))
+        .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("utilisation is 1.5 at hour 7, expected a value between 0 and 1"));
+    }
+}
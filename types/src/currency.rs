@@ -0,0 +1,285 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+// Currencies costs can be shown in, all prices are ingested in USD
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display)]
+#[strum(ascii_case_insensitive)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[strum(serialize = "USD")]
+    Usd,
+    #[strum(serialize = "EUR")]
+    Eur,
+    #[strum(serialize = "GBP")]
+    Gbp,
+}
+
+impl Currency {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+        }
+    }
+
+    // Format an amount the way the currency is usually written, e.g. $1,234.56 or 1.234,56 €
+    pub fn format(&self, amount: f64) -> String {
+        let cents = (amount.abs() * 100.0).round() as u64;
+        let sign = if amount < 0.0 && cents > 0 { "-" } else { "" };
+
+        let (group_separator, decimal_separator) = match self {
+            Currency::Usd | Currency::Gbp => (',', '.'),
+            Currency::Eur => ('.', ','),
+        };
+
+        let whole = (cents / 100).to_string();
+
+        let mut grouped = String::new();
+
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index).is_multiple_of(3) {
+                grouped.push(group_separator);
+            }
+
+            grouped.push(digit);
+        }
+
+        let number = format!("{}{}{:02}", grouped, decimal_separator, cents % 100);
+
+        match self {
+            Currency::Usd | Currency::Gbp => format!("{}{}{}", sign, self.symbol(), number),
+            Currency::Eur => format!("{}{} {}", sign, number, self.symbol()),
+        }
+    }
+}
+
+// Rate a USD cost was converted at, kept so converted costs can be traced back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExchangeRate {
+    pub currency: Currency,
+    // units of the currency per USD
+    pub rate: f64,
+    // where the rate came from, e.g. the path of a rates file
+    pub source: String,
+    // day the rate was published, if known
+    pub date: Option<String>,
+}
+
+impl ExchangeRate {
+    // USD costs shown as they are
+    pub fn usd() -> Self {
+        Self {
+            currency: Currency::Usd,
+            rate: 1.0,
+            source: String::from("identity"),
+            date: None,
+        }
+    }
+
+    pub fn convert(&self, usd: f64) -> f64 {
+        usd * self.rate
+    }
+
+    pub fn format(&self, usd: f64) -> String {
+        self.currency.format(self.convert(usd))
+    }
+
+    // e.g. 1 USD = 0.9200 EUR (exchange_rates.toml, 2026-10-01)
+    pub fn describe(&self) -> String {
+        match &self.date {
+            Some(date) => format!(
+                "1 USD = {:.4} {} ({}, {})",
+                self.rate, self.currency, self.source, date
+            ),
+            None => format!(
+                "1 USD = {:.4} {} ({})",
+                self.rate, self.currency, self.source
+            ),
+        }
+    }
+}
+
+// Where exchange rates from USD come from
+pub trait ExchangeRateSource {
+    fn rate(&self, currency: Currency) -> Result<ExchangeRate, String>;
+}
+
+// Rates read from a toml file, e.g.
+//
+// date = "2026-10-01"
+//
+// [rates]
+// EUR = 0.92
+// GBP = 0.79
+#[derive(Deserialize, Debug)]
+pub struct StaticFileRates {
+    #[serde(skip)]
+    source: String,
+    date: Option<String>,
+    rates: HashMap<Currency, f64>,
+}
+
+impl StaticFileRates {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            format!(
+                "Failed to read exchange rates from {}: {}",
+                path.as_ref().display(),
+                e
+            )
+        })?;
+
+        let mut rates: StaticFileRates = toml::from_str(&contents).map_err(|e| {
+            format!(
+                "Failed to parse exchange rates from {}: {}",
+                path.as_ref().display(),
+                e
+            )
+        })?;
+
+        rates.source = path.as_ref().display().to_string();
+
+        Ok(rates)
+    }
+}
+
+impl ExchangeRateSource for StaticFileRates {
+    fn rate(&self, currency: Currency) -> Result<ExchangeRate, String> {
+        if currency == Currency::Usd {
+            return Ok(ExchangeRate::usd());
+        }
+
+        let rate = self
+            .rates
+            .get(&currency)
+            .ok_or_else(|| format!("No exchange rate for {} in {}", currency, self.source))?;
+
+        Ok(ExchangeRate {
+            currency,
+            rate: *rate,
+            source: self.source.clone(),
+            date: self.date.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn eur(rate: f64) -> ExchangeRate {
+        ExchangeRate {
+            currency: Currency::Eur,
+            rate,
+            source: String::from("exchange_rates.toml"),
+            date: Some(String::from("2026-10-01")),
+        }
+    }
+
+    // Rates file written to the temp dir under a name of its own
+    fn rates_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.toml", name, std::process::id()));
+
+        std::fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn amounts_are_formatted_the_way_the_currency_is_written() {
+        assert_eq!(Currency::Usd.format(1234.56), "$1,234.56");
+        assert_eq!(Currency::Gbp.format(1234567.891), "£1,234,567.89");
+        assert_eq!(Currency::Eur.format(1234.5), "1.234,50 €");
+        assert_eq!(Currency::Usd.format(999.999), "$1,000.00");
+        assert_eq!(Currency::Eur.format(0.0), "0,00 €");
+    }
+
+    #[test]
+    fn negative_amounts_keep_their_sign_unless_they_round_to_zero() {
+        assert_eq!(Currency::Usd.format(-42.5), "-$42.50");
+        assert_eq!(Currency::Eur.format(-1000.0), "-1.000,00 €");
+        assert_eq!(Currency::Gbp.format(-0.001), "£0.00");
+    }
+
+    #[test]
+    fn currencies_are_parsed_ignoring_case() {
+        assert_eq!("eur".parse::<Currency>().unwrap(), Currency::Eur);
+        assert_eq!("GBP".parse::<Currency>().unwrap(), Currency::Gbp);
+        assert!("JPY".parse::<Currency>().is_err());
+    }
+
+    #[test]
+    fn usd_costs_are_converted_at_the_rate() {
+        let rate = eur(0.92);
+
+        assert!((rate.convert(100.0) - 92.0).abs() < 1e-9);
+        assert_eq!(rate.format(1000.0), "920,00 €");
+        assert_eq!(ExchangeRate::usd().convert(12.34), 12.34);
+    }
+
+    #[test]
+    fn rates_are_described_with_their_source() {
+        assert_eq!(
+            eur(0.92).describe(),
+            "1 USD = 0.9200 EUR (exchange_rates.toml, 2026-10-01)"
+        );
+        assert_eq!(
+            ExchangeRate::usd().describe(),
+            "1 USD = 1.0000 USD (identity)"
+        );
+    }
+
+    #[test]
+    fn rates_are_read_from_a_file() {
+        let path = rates_file(
+            "rates",
+            "date = \"2026-10-01\"\n\n[rates]\nEUR = 0.92\nGBP = 0.79\n",
+        );
+
+        let rates = StaticFileRates::load(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        let rate = rates.rate(Currency::Gbp).unwrap();
+
+        assert_eq!(rate.rate, 0.79);
+        assert_eq!(rate.source, path.display().to_string());
+        assert_eq!(rate.date.as_deref(), Some("2026-10-01"));
+        assert_eq!(rates.rate(Currency::Usd).unwrap(), ExchangeRate::usd());
+    }
+
+    #[test]
+    fn currencies_missing_from_the_file_have_no_rate() {
+        let path = rates_file("rates_without_gbp", "[rates]\nEUR = 0.92\n");
+
+        let rates = StaticFileRates::load(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rates.rate(Currency::Eur).unwrap().date, None);
+        assert!(rates
+            .rate(Currency::Gbp)
+            .unwrap_err()
+            .starts_with("No exchange rate for GBP in"));
+    }
+
+    #[test]
+    fn unreadable_rates_files_are_errors() {
+        let path = rates_file("rates_invalid", "[rates]\nJPY = 150.0\n");
+
+        let error = StaticFileRates::load(&path).unwrap_err();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.starts_with("Failed to parse exchange rates from"));
+
+        let error = StaticFileRates::load("missing_exchange_rates.toml").unwrap_err();
+
+        assert!(error.starts_with("Failed to read exchange rates from missing_exchange_rates.toml"));
+    }
+}
@@ -1,11 +1,151 @@
-use super::region::AwsRegion;
+use super::{
+    config::{
+        ControlPlaneMode, Database, InfrastructureConfiguration, Network, ObjectStorage,
+        SnapshotRetention,
+    },
+    region::Region,
+};
 
 pub struct DeploymentConfiguration {
-    pub regions: Vec<AwsRegion>,
-    pub control_plane_specs: Option<(u32, f32)>,
-    pub spot_control_plane: bool,
+    pub regions: Vec<Region>,
+    pub control_plane: ControlPlane,
     pub node_specs: (u32, f32),
     pub storage_size_gb: f64,
+    // expected number of nodes, between the bounds of the scaling if any
     pub node_count: f64,
     pub outbound_data_gb: u64,
+    pub volume: Option<VolumeConfiguration>,
+    pub object_storage: Option<ObjectStorage>,
+    pub database: Option<Database>,
+    pub network: Option<Network>,
+    pub scaling: Option<NodeScaling>,
+}
+
+impl DeploymentConfiguration {
+    // Deployment an infra.toml describes, priced in the given regions
+    pub fn from_infrastructure(
+        configuration: &InfrastructureConfiguration,
+        regions: Vec<Region>,
+    ) -> Result<Self, String> {
+        let shape = configuration
+            .shape
+            .as_ref()
+            .ok_or_else(|| String::from("infra.toml has no [shape] section"))?;
+
+        let storage = configuration
+            .storage
+            .as_ref()
+            .ok_or_else(|| String::from("infra.toml has no [storage] section"))?;
+
+        if let Some(object_storage) = configuration.object_storage.as_ref() {
+            aws_only(&regions, "[object_storage]")?;
+
+            if !ObjectStorage::CLASSES.contains(&object_storage.class()) {
+                return Err(format!(
+                    "Unsupported object storage class {}, use one of {}",
+                    object_storage.class(),
+                    ObjectStorage::CLASSES.join(", ")
+                ));
+            }
+        }
+
+        if let Some(ControlPlaneMode::Eks { .. }) = configuration.control_plane {
+            aws_only(&regions, "[control_plane] mode = \"eks\"")?;
+        }
+
+        if configuration.database.is_some() {
+            aws_only(&regions, "[database]")?;
+        }
+
+        if configuration.network.is_some() {
+            aws_only(&regions, "[network]")?;
+        }
+
+        Ok(DeploymentConfiguration {
+            regions,
+            control_plane: match configuration.control_plane {
+                Some(ControlPlaneMode::Eks { extended_support }) => ControlPlane::Eks {
+                    extended_support: extended_support.unwrap_or(false),
+                },
+                Some(ControlPlaneMode::None) => ControlPlane::None,
+                Some(ControlPlaneMode::SelfHosted) | None => ControlPlane::SelfHosted {
+                    specs: Some((1, 1.0)),
+                    spot: false,
+                },
+            },
+            node_specs: (shape.vcpu, shape.memory),
+            storage_size_gb: storage.size,
+            node_count: configuration
+                .scaling
+                .as_ref()
+                .map(|scaling| scaling.expected_replicas())
+                .unwrap_or(1.0),
+            outbound_data_gb: 0,
+            // provisioned performance or snapshots without a volume type are priced on gp3
+            volume: (storage.volume.is_some()
+                || storage.iops.is_some()
+                || storage.throughput.is_some()
+                || storage.snapshots.is_some())
+            .then(|| VolumeConfiguration {
+                volume_api_name: storage
+                    .volume
+                    .clone()
+                    .unwrap_or_else(|| String::from("gp3")),
+                iops: storage.iops,
+                throughput: storage.throughput,
+                snapshots: storage.snapshots.clone(),
+            }),
+            object_storage: configuration.object_storage.clone(),
+            database: configuration.database.clone(),
+            network: configuration.network.clone(),
+            scaling: configuration.scaling.as_ref().map(|scaling| NodeScaling {
+                min: scaling.min_replicas as f64,
+                max: scaling.max_replicas as f64,
+            }),
+        })
+    }
+}
+
+// Prices of a section are only ingested for aws, anywhere else it can't be estimated
+fn aws_only(regions: &[Region], section: &str) -> Result<(), String> {
+    match regions
+        .iter()
+        .find(|region| !matches!(region, Region::Aws(_)))
+    {
+        Some(region) => Err(format!(
+            "{} is only supported on aws, not {}",
+            section,
+            region.provider()
+        )),
+        None => Ok(()),
+    }
+}
+
+// How the kubernetes control plane is run, and so billed
+pub enum ControlPlane {
+    // on an instance of its own, the cheapest spot instance when spot is set
+    SelfHosted {
+        specs: Option<(u32, f32)>,
+        spot: bool,
+    },
+    // managed by eks, billed per cluster hour
+    Eks {
+        extended_support: bool,
+    },
+    // the cluster already exists or is not kubernetes
+    None,
+}
+
+// Volume attached to every node, priced from its size and provisioned performance
+pub struct VolumeConfiguration {
+    pub volume_api_name: String,
+    pub iops: Option<u64>,
+    pub throughput: Option<u64>,
+    pub snapshots: Option<SnapshotRetention>,
+}
+
+// Bounds of the number of nodes an autoscaled deployment runs
+pub struct NodeScaling {
+    pub min: f64,
+    pub max: f64,
 }
@@ -0,0 +1,101 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use glob_match::glob_match;
+use serde::{Deserialize, Serialize};
+
+// Repository settings for finding configuration files, at the root of the repository
+pub const SETTINGS_FILE: &str = ".infralink.yml";
+
+// Shallowest files first, so the app at the root of a repository is listed before nested ones
+pub fn sort_by_depth(files: &mut [(PathBuf, String)]) {
+    files.sort_by(|a, b| {
+        let depth_a = a.0.components().count();
+        let depth_b = b.0.components().count();
+        depth_a.cmp(&depth_b)
+    });
+}
+
+fn default_include() -> Vec<String> {
+    vec![String::from("**/infra.toml")]
+}
+
+// Which files of a repository are configurations, e.g. for a monorepo
+//
+// include:
+//   - "services/**/infra.toml"
+// exclude:
+//   - "services/legacy/**"
+// environments:
+//   - name: production
+//     include: ["services/*/production/**"]
+//   - name: staging
+//     include: ["services/*/staging/**"]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Discovery {
+    // globs of configuration files, relative to the root of the repository
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    // globs of files left out even when included
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    // environments the configuration files belong to, the first matching one wins
+    #[serde(default)]
+    pub environments: Vec<Environment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Environment {
+    // name shown next to the apps of the environment
+    pub name: String,
+    // globs of the configuration files of the environment
+    pub include: Vec<String>,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self {
+            include: default_include(),
+            exclude: Vec::new(),
+            environments: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Discovery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // an empty file keeps the defaults
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(s).map_err(|e| e.to_string())
+    }
+}
+
+fn matches_any(globs: &[String], path: &Path) -> bool {
+    let path = path.to_string_lossy();
+
+    globs.iter().any(|glob| glob_match(glob, &path))
+}
+
+impl Discovery {
+    // Whether a file, relative to the root of the repository, is a configuration
+    pub fn is_configuration_file(&self, path: &Path) -> bool {
+        matches_any(&self.include, path) && !matches_any(&self.exclude, path)
+    }
+
+    // Name of the environment a configuration file belongs to, if any
+    pub fn environment(&self, path: &Path) -> Option<&str> {
+        self.environments
+            .iter()
+            .find(|environment| matches_any(&environment.include, path))
+            .map(|environment| environment.name.as_str())
+    }
+}
@@ -1,7 +1,11 @@
+pub mod adjustments;
 pub mod architecture;
+pub mod budget;
 pub mod cloud_provider;
 pub mod config;
+pub mod currency;
 pub mod deployment_configuration;
+pub mod discovery;
 pub mod instance;
 pub mod region;
 pub mod storage;
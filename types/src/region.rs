@@ -3,9 +3,13 @@ use std::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use strum_macros::EnumIter;
 
+use super::cloud_provider::CloudProvider;
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Region {
     Aws(AwsRegion),
+    Azure(AzureRegion),
+    Gcp(GcpRegion),
     None,
 }
 
@@ -13,9 +17,66 @@ impl Region {
     pub fn code(&self) -> Option<String> {
         match self {
             Region::Aws(aws_region) => Some(aws_region.code()),
+            Region::Azure(azure_region) => Some(azure_region.code()),
+            Region::Gcp(gcp_region) => Some(gcp_region.code()),
+            Region::None => None,
+        }
+    }
+
+    pub fn display_name(&self) -> Option<String> {
+        match self {
+            Region::Aws(aws_region) => Some(aws_region.display_name()),
+            Region::Azure(azure_region) => Some(azure_region.display_name()),
+            Region::Gcp(gcp_region) => Some(gcp_region.display_name()),
             Region::None => None,
         }
     }
+
+    pub fn provider(&self) -> CloudProvider {
+        match self {
+            Region::Aws(_) => CloudProvider::Aws,
+            Region::Azure(_) => CloudProvider::Azure,
+            Region::Gcp(_) => CloudProvider::Gcp,
+            Region::None => CloudProvider::None,
+        }
+    }
+
+    // Provider-qualified region identifier, e.g. aws:us-east-1
+    pub fn qualified_code(&self) -> Option<String> {
+        self.code()
+            .map(|code| format!("{}:{}", self.provider(), code))
+    }
+
+    // Parse a region code as used by the given cloud provider
+    pub fn from_provider(provider: &CloudProvider, code: &str) -> Result<Self, String> {
+        match provider {
+            CloudProvider::Aws => AwsRegion::from_str(code).map(Region::Aws),
+            CloudProvider::Azure => AzureRegion::from_str(code).map(Region::Azure),
+            CloudProvider::Gcp => GcpRegion::from_str(code).map(Region::Gcp),
+            CloudProvider::None => Err(format!("{} is an invalid region", code)),
+        }
+    }
+}
+
+// Parses a provider-qualified region identifier (aws:us-east-1), or a bare region code.
+// Region codes are unique across providers, so a bare code is matched against each of them.
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((provider, code)) = s.split_once(':') {
+            let provider = CloudProvider::from_str(provider)
+                .map_err(|_| format!("{} is an invalid cloud provider", provider))?;
+
+            return Region::from_provider(&provider, code);
+        }
+
+        AwsRegion::from_str(s)
+            .map(Region::Aws)
+            .or_else(|_| AzureRegion::from_str(s).map(Region::Azure))
+            .or_else(|_| GcpRegion::from_str(s).map(Region::Gcp))
+            .map_err(|_| format!("{} is an invalid region", s))
+    }
 }
 
 #[derive(EnumIter, Debug, PartialEq, Eq, Hash, Clone)]
@@ -211,3 +272,505 @@ impl<'de> Deserialize<'de> for AwsRegion {
         AwsRegion::from_str(&s).map_err(serde::de::Error::custom)
     }
 }
+
+#[derive(EnumIter, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum AzureRegion {
+    EastUS,
+    EastUS2,
+    CentralUS,
+    NorthCentralUS,
+    SouthCentralUS,
+    WestCentralUS,
+    WestUS,
+    WestUS2,
+    WestUS3,
+    CanadaCentral,
+    CanadaEast,
+    BrazilSouth,
+    NorthEurope,
+    WestEurope,
+    UKSouth,
+    UKWest,
+    FranceCentral,
+    GermanyWestCentral,
+    NorwayEast,
+    SwedenCentral,
+    SwitzerlandNorth,
+    PolandCentral,
+    ItalyNorth,
+    SpainCentral,
+    EastAsia,
+    SouthEastAsia,
+    JapanEast,
+    JapanWest,
+    KoreaCentral,
+    KoreaSouth,
+    CentralIndia,
+    SouthIndia,
+    WestIndia,
+    AustraliaEast,
+    AustraliaSouthEast,
+    AustraliaCentral,
+    UAENorth,
+    QatarCentral,
+    IsraelCentral,
+    SouthAfricaNorth,
+}
+
+impl FromStr for AzureRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eastus" => Ok(AzureRegion::EastUS),
+            "eastus2" => Ok(AzureRegion::EastUS2),
+            "centralus" => Ok(AzureRegion::CentralUS),
+            "northcentralus" => Ok(AzureRegion::NorthCentralUS),
+            "southcentralus" => Ok(AzureRegion::SouthCentralUS),
+            "westcentralus" => Ok(AzureRegion::WestCentralUS),
+            "westus" => Ok(AzureRegion::WestUS),
+            "westus2" => Ok(AzureRegion::WestUS2),
+            "westus3" => Ok(AzureRegion::WestUS3),
+            "canadacentral" => Ok(AzureRegion::CanadaCentral),
+            "canadaeast" => Ok(AzureRegion::CanadaEast),
+            "brazilsouth" => Ok(AzureRegion::BrazilSouth),
+            "northeurope" => Ok(AzureRegion::NorthEurope),
+            "westeurope" => Ok(AzureRegion::WestEurope),
+            "uksouth" => Ok(AzureRegion::UKSouth),
+            "ukwest" => Ok(AzureRegion::UKWest),
+            "francecentral" => Ok(AzureRegion::FranceCentral),
+            "germanywestcentral" => Ok(AzureRegion::GermanyWestCentral),
+            "norwayeast" => Ok(AzureRegion::NorwayEast),
+            "swedencentral" => Ok(AzureRegion::SwedenCentral),
+            "switzerlandnorth" => Ok(AzureRegion::SwitzerlandNorth),
+            "polandcentral" => Ok(AzureRegion::PolandCentral),
+            "italynorth" => Ok(AzureRegion::ItalyNorth),
+            "spaincentral" => Ok(AzureRegion::SpainCentral),
+            "eastasia" => Ok(AzureRegion::EastAsia),
+            "southeastasia" => Ok(AzureRegion::SouthEastAsia),
+            "japaneast" => Ok(AzureRegion::JapanEast),
+            "japanwest" => Ok(AzureRegion::JapanWest),
+            "koreacentral" => Ok(AzureRegion::KoreaCentral),
+            "koreasouth" => Ok(AzureRegion::KoreaSouth),
+            "centralindia" => Ok(AzureRegion::CentralIndia),
+            "southindia" => Ok(AzureRegion::SouthIndia),
+            "westindia" => Ok(AzureRegion::WestIndia),
+            "australiaeast" => Ok(AzureRegion::AustraliaEast),
+            "australiasoutheast" => Ok(AzureRegion::AustraliaSouthEast),
+            "australiacentral" => Ok(AzureRegion::AustraliaCentral),
+            "uaenorth" => Ok(AzureRegion::UAENorth),
+            "qatarcentral" => Ok(AzureRegion::QatarCentral),
+            "israelcentral" => Ok(AzureRegion::IsraelCentral),
+            "southafricanorth" => Ok(AzureRegion::SouthAfricaNorth),
+            _ => Err(format!("{} is an invalid region", s)),
+        }
+    }
+}
+
+impl AzureRegion {
+    pub fn code(&self) -> String {
+        match self {
+            AzureRegion::EastUS => String::from("eastus"),
+            AzureRegion::EastUS2 => String::from("eastus2"),
+            AzureRegion::CentralUS => String::from("centralus"),
+            AzureRegion::NorthCentralUS => String::from("northcentralus"),
+            AzureRegion::SouthCentralUS => String::from("southcentralus"),
+            AzureRegion::WestCentralUS => String::from("westcentralus"),
+            AzureRegion::WestUS => String::from("westus"),
+            AzureRegion::WestUS2 => String::from("westus2"),
+            AzureRegion::WestUS3 => String::from("westus3"),
+            AzureRegion::CanadaCentral => String::from("canadacentral"),
+            AzureRegion::CanadaEast => String::from("canadaeast"),
+            AzureRegion::BrazilSouth => String::from("brazilsouth"),
+            AzureRegion::NorthEurope => String::from("northeurope"),
+            AzureRegion::WestEurope => String::from("westeurope"),
+            AzureRegion::UKSouth => String::from("uksouth"),
+            AzureRegion::UKWest => String::from("ukwest"),
+            AzureRegion::FranceCentral => String::from("francecentral"),
+            AzureRegion::GermanyWestCentral => String::from("germanywestcentral"),
+            AzureRegion::NorwayEast => String::from("norwayeast"),
+            AzureRegion::SwedenCentral => String::from("swedencentral"),
+            AzureRegion::SwitzerlandNorth => String::from("switzerlandnorth"),
+            AzureRegion::PolandCentral => String::from("polandcentral"),
+            AzureRegion::ItalyNorth => String::from("italynorth"),
+            AzureRegion::SpainCentral => String::from("spaincentral"),
+            AzureRegion::EastAsia => String::from("eastasia"),
+            AzureRegion::SouthEastAsia => String::from("southeastasia"),
+            AzureRegion::JapanEast => String::from("japaneast"),
+            AzureRegion::JapanWest => String::from("japanwest"),
+            AzureRegion::KoreaCentral => String::from("koreacentral"),
+            AzureRegion::KoreaSouth => String::from("koreasouth"),
+            AzureRegion::CentralIndia => String::from("centralindia"),
+            AzureRegion::SouthIndia => String::from("southindia"),
+            AzureRegion::WestIndia => String::from("westindia"),
+            AzureRegion::AustraliaEast => String::from("australiaeast"),
+            AzureRegion::AustraliaSouthEast => String::from("australiasoutheast"),
+            AzureRegion::AustraliaCentral => String::from("australiacentral"),
+            AzureRegion::UAENorth => String::from("uaenorth"),
+            AzureRegion::QatarCentral => String::from("qatarcentral"),
+            AzureRegion::IsraelCentral => String::from("israelcentral"),
+            AzureRegion::SouthAfricaNorth => String::from("southafricanorth"),
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        match self {
+            AzureRegion::EastUS => String::from("East US"),
+            AzureRegion::EastUS2 => String::from("East US 2"),
+            AzureRegion::CentralUS => String::from("Central US"),
+            AzureRegion::NorthCentralUS => String::from("North Central US"),
+            AzureRegion::SouthCentralUS => String::from("South Central US"),
+            AzureRegion::WestCentralUS => String::from("West Central US"),
+            AzureRegion::WestUS => String::from("West US"),
+            AzureRegion::WestUS2 => String::from("West US 2"),
+            AzureRegion::WestUS3 => String::from("West US 3"),
+            AzureRegion::CanadaCentral => String::from("Canada Central"),
+            AzureRegion::CanadaEast => String::from("Canada East"),
+            AzureRegion::BrazilSouth => String::from("Brazil South"),
+            AzureRegion::NorthEurope => String::from("North Europe"),
+            AzureRegion::WestEurope => String::from("West Europe"),
+            AzureRegion::UKSouth => String::from("UK South"),
+            AzureRegion::UKWest => String::from("UK West"),
+            AzureRegion::FranceCentral => String::from("France Central"),
+            AzureRegion::GermanyWestCentral => String::from("Germany West Central"),
+            AzureRegion::NorwayEast => String::from("Norway East"),
+            AzureRegion::SwedenCentral => String::from("Sweden Central"),
+            AzureRegion::SwitzerlandNorth => String::from("Switzerland North"),
+            AzureRegion::PolandCentral => String::from("Poland Central"),
+            AzureRegion::ItalyNorth => String::from("Italy North"),
+            AzureRegion::SpainCentral => String::from("Spain Central"),
+            AzureRegion::EastAsia => String::from("East Asia"),
+            AzureRegion::SouthEastAsia => String::from("Southeast Asia"),
+            AzureRegion::JapanEast => String::from("Japan East"),
+            AzureRegion::JapanWest => String::from("Japan West"),
+            AzureRegion::KoreaCentral => String::from("Korea Central"),
+            AzureRegion::KoreaSouth => String::from("Korea South"),
+            AzureRegion::CentralIndia => String::from("Central India"),
+            AzureRegion::SouthIndia => String::from("South India"),
+            AzureRegion::WestIndia => String::from("West India"),
+            AzureRegion::AustraliaEast => String::from("Australia East"),
+            AzureRegion::AustraliaSouthEast => String::from("Australia Southeast"),
+            AzureRegion::AustraliaCentral => String::from("Australia Central"),
+            AzureRegion::UAENorth => String::from("UAE North"),
+            AzureRegion::QatarCentral => String::from("Qatar Central"),
+            AzureRegion::IsraelCentral => String::from("Israel Central"),
+            AzureRegion::SouthAfricaNorth => String::from("South Africa North"),
+        }
+    }
+
+    pub fn from_display_name(name: &str) -> Result<Self, String> {
+        match name {
+            "East US" => Ok(AzureRegion::EastUS),
+            "East US 2" => Ok(AzureRegion::EastUS2),
+            "Central US" => Ok(AzureRegion::CentralUS),
+            "North Central US" => Ok(AzureRegion::NorthCentralUS),
+            "South Central US" => Ok(AzureRegion::SouthCentralUS),
+            "West Central US" => Ok(AzureRegion::WestCentralUS),
+            "West US" => Ok(AzureRegion::WestUS),
+            "West US 2" => Ok(AzureRegion::WestUS2),
+            "West US 3" => Ok(AzureRegion::WestUS3),
+            "Canada Central" => Ok(AzureRegion::CanadaCentral),
+            "Canada East" => Ok(AzureRegion::CanadaEast),
+            "Brazil South" => Ok(AzureRegion::BrazilSouth),
+            "North Europe" => Ok(AzureRegion::NorthEurope),
+            "West Europe" => Ok(AzureRegion::WestEurope),
+            "UK South" => Ok(AzureRegion::UKSouth),
+            "UK West" => Ok(AzureRegion::UKWest),
+            "France Central" => Ok(AzureRegion::FranceCentral),
+            "Germany West Central" => Ok(AzureRegion::GermanyWestCentral),
+            "Norway East" => Ok(AzureRegion::NorwayEast),
+            "Sweden Central" => Ok(AzureRegion::SwedenCentral),
+            "Switzerland North" => Ok(AzureRegion::SwitzerlandNorth),
+            "Poland Central" => Ok(AzureRegion::PolandCentral),
+            "Italy North" => Ok(AzureRegion::ItalyNorth),
+            "Spain Central" => Ok(AzureRegion::SpainCentral),
+            "East Asia" => Ok(AzureRegion::EastAsia),
+            "Southeast Asia" => Ok(AzureRegion::SouthEastAsia),
+            "Japan East" => Ok(AzureRegion::JapanEast),
+            "Japan West" => Ok(AzureRegion::JapanWest),
+            "Korea Central" => Ok(AzureRegion::KoreaCentral),
+            "Korea South" => Ok(AzureRegion::KoreaSouth),
+            "Central India" => Ok(AzureRegion::CentralIndia),
+            "South India" => Ok(AzureRegion::SouthIndia),
+            "West India" => Ok(AzureRegion::WestIndia),
+            "Australia East" => Ok(AzureRegion::AustraliaEast),
+            "Australia Southeast" => Ok(AzureRegion::AustraliaSouthEast),
+            "Australia Central" => Ok(AzureRegion::AustraliaCentral),
+            "UAE North" => Ok(AzureRegion::UAENorth),
+            "Qatar Central" => Ok(AzureRegion::QatarCentral),
+            "Israel Central" => Ok(AzureRegion::IsraelCentral),
+            "South Africa North" => Ok(AzureRegion::SouthAfricaNorth),
+            _ => Err(String::from("Invalid display name")),
+        }
+    }
+}
+
+impl Serialize for AzureRegion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for AzureRegion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        AzureRegion::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(EnumIter, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum GcpRegion {
+    USCentral1,
+    USEast1,
+    USEast4,
+    USEast5,
+    USSouth1,
+    USWest1,
+    USWest2,
+    USWest3,
+    USWest4,
+    NorthAmericaNorthEast1,
+    NorthAmericaNorthEast2,
+    SouthAmericaEast1,
+    SouthAmericaWest1,
+    EuropeCentral2,
+    EuropeNorth1,
+    EuropeSouthWest1,
+    EuropeWest1,
+    EuropeWest2,
+    EuropeWest3,
+    EuropeWest4,
+    EuropeWest6,
+    EuropeWest8,
+    EuropeWest9,
+    EuropeWest10,
+    EuropeWest12,
+    MeCentral1,
+    MeCentral2,
+    MeWest1,
+    AsiaEast1,
+    AsiaEast2,
+    AsiaNorthEast1,
+    AsiaNorthEast2,
+    AsiaNorthEast3,
+    AsiaSouth1,
+    AsiaSouth2,
+    AsiaSouthEast1,
+    AsiaSouthEast2,
+    AustraliaSouthEast1,
+    AustraliaSouthEast2,
+    AfricaSouth1,
+}
+
+impl FromStr for GcpRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "us-central1" => Ok(GcpRegion::USCentral1),
+            "us-east1" => Ok(GcpRegion::USEast1),
+            "us-east4" => Ok(GcpRegion::USEast4),
+            "us-east5" => Ok(GcpRegion::USEast5),
+            "us-south1" => Ok(GcpRegion::USSouth1),
+            "us-west1" => Ok(GcpRegion::USWest1),
+            "us-west2" => Ok(GcpRegion::USWest2),
+            "us-west3" => Ok(GcpRegion::USWest3),
+            "us-west4" => Ok(GcpRegion::USWest4),
+            "northamerica-northeast1" => Ok(GcpRegion::NorthAmericaNorthEast1),
+            "northamerica-northeast2" => Ok(GcpRegion::NorthAmericaNorthEast2),
+            "southamerica-east1" => Ok(GcpRegion::SouthAmericaEast1),
+            "southamerica-west1" => Ok(GcpRegion::SouthAmericaWest1),
+            "europe-central2" => Ok(GcpRegion::EuropeCentral2),
+            "europe-north1" => Ok(GcpRegion::EuropeNorth1),
+            "europe-southwest1" => Ok(GcpRegion::EuropeSouthWest1),
+            "europe-west1" => Ok(GcpRegion::EuropeWest1),
+            "europe-west2" => Ok(GcpRegion::EuropeWest2),
+            "europe-west3" => Ok(GcpRegion::EuropeWest3),
+            "europe-west4" => Ok(GcpRegion::EuropeWest4),
+            "europe-west6" => Ok(GcpRegion::EuropeWest6),
+            "europe-west8" => Ok(GcpRegion::EuropeWest8),
+            "europe-west9" => Ok(GcpRegion::EuropeWest9),
+            "europe-west10" => Ok(GcpRegion::EuropeWest10),
+            "europe-west12" => Ok(GcpRegion::EuropeWest12),
+            "me-central1" => Ok(GcpRegion::MeCentral1),
+            "me-central2" => Ok(GcpRegion::MeCentral2),
+            "me-west1" => Ok(GcpRegion::MeWest1),
+            "asia-east1" => Ok(GcpRegion::AsiaEast1),
+            "asia-east2" => Ok(GcpRegion::AsiaEast2),
+            "asia-northeast1" => Ok(GcpRegion::AsiaNorthEast1),
+            "asia-northeast2" => Ok(GcpRegion::AsiaNorthEast2),
+            "asia-northeast3" => Ok(GcpRegion::AsiaNorthEast3),
+            "asia-south1" => Ok(GcpRegion::AsiaSouth1),
+            "asia-south2" => Ok(GcpRegion::AsiaSouth2),
+            "asia-southeast1" => Ok(GcpRegion::AsiaSouthEast1),
+            "asia-southeast2" => Ok(GcpRegion::AsiaSouthEast2),
+            "australia-southeast1" => Ok(GcpRegion::AustraliaSouthEast1),
+            "australia-southeast2" => Ok(GcpRegion::AustraliaSouthEast2),
+            "africa-south1" => Ok(GcpRegion::AfricaSouth1),
+            _ => Err(format!("{} is an invalid region", s)),
+        }
+    }
+}
+
+impl GcpRegion {
+    pub fn code(&self) -> String {
+        match self {
+            GcpRegion::USCentral1 => String::from("us-central1"),
+            GcpRegion::USEast1 => String::from("us-east1"),
+            GcpRegion::USEast4 => String::from("us-east4"),
+            GcpRegion::USEast5 => String::from("us-east5"),
+            GcpRegion::USSouth1 => String::from("us-south1"),
+            GcpRegion::USWest1 => String::from("us-west1"),
+            GcpRegion::USWest2 => String::from("us-west2"),
+            GcpRegion::USWest3 => String::from("us-west3"),
+            GcpRegion::USWest4 => String::from("us-west4"),
+            GcpRegion::NorthAmericaNorthEast1 => String::from("northamerica-northeast1"),
+            GcpRegion::NorthAmericaNorthEast2 => String::from("northamerica-northeast2"),
+            GcpRegion::SouthAmericaEast1 => String::from("southamerica-east1"),
+            GcpRegion::SouthAmericaWest1 => String::from("southamerica-west1"),
+            GcpRegion::EuropeCentral2 => String::from("europe-central2"),
+            GcpRegion::EuropeNorth1 => String::from("europe-north1"),
+            GcpRegion::EuropeSouthWest1 => String::from("europe-southwest1"),
+            GcpRegion::EuropeWest1 => String::from("europe-west1"),
+            GcpRegion::EuropeWest2 => String::from("europe-west2"),
+            GcpRegion::EuropeWest3 => String::from("europe-west3"),
+            GcpRegion::EuropeWest4 => String::from("europe-west4"),
+            GcpRegion::EuropeWest6 => String::from("europe-west6"),
+            GcpRegion::EuropeWest8 => String::from("europe-west8"),
+            GcpRegion::EuropeWest9 => String::from("europe-west9"),
+            GcpRegion::EuropeWest10 => String::from("europe-west10"),
+            GcpRegion::EuropeWest12 => String::from("europe-west12"),
+            GcpRegion::MeCentral1 => String::from("me-central1"),
+            GcpRegion::MeCentral2 => String::from("me-central2"),
+            GcpRegion::MeWest1 => String::from("me-west1"),
+            GcpRegion::AsiaEast1 => String::from("asia-east1"),
+            GcpRegion::AsiaEast2 => String::from("asia-east2"),
+            GcpRegion::AsiaNorthEast1 => String::from("asia-northeast1"),
+            GcpRegion::AsiaNorthEast2 => String::from("asia-northeast2"),
+            GcpRegion::AsiaNorthEast3 => String::from("asia-northeast3"),
+            GcpRegion::AsiaSouth1 => String::from("asia-south1"),
+            GcpRegion::AsiaSouth2 => String::from("asia-south2"),
+            GcpRegion::AsiaSouthEast1 => String::from("asia-southeast1"),
+            GcpRegion::AsiaSouthEast2 => String::from("asia-southeast2"),
+            GcpRegion::AustraliaSouthEast1 => String::from("australia-southeast1"),
+            GcpRegion::AustraliaSouthEast2 => String::from("australia-southeast2"),
+            GcpRegion::AfricaSouth1 => String::from("africa-south1"),
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        match self {
+            GcpRegion::USCentral1 => String::from("Iowa"),
+            GcpRegion::USEast1 => String::from("South Carolina"),
+            GcpRegion::USEast4 => String::from("Northern Virginia"),
+            GcpRegion::USEast5 => String::from("Columbus"),
+            GcpRegion::USSouth1 => String::from("Dallas"),
+            GcpRegion::USWest1 => String::from("Oregon"),
+            GcpRegion::USWest2 => String::from("Los Angeles"),
+            GcpRegion::USWest3 => String::from("Salt Lake City"),
+            GcpRegion::USWest4 => String::from("Las Vegas"),
+            GcpRegion::NorthAmericaNorthEast1 => String::from("Montreal"),
+            GcpRegion::NorthAmericaNorthEast2 => String::from("Toronto"),
+            GcpRegion::SouthAmericaEast1 => String::from("Sao Paulo"),
+            GcpRegion::SouthAmericaWest1 => String::from("Santiago"),
+            GcpRegion::EuropeCentral2 => String::from("Warsaw"),
+            GcpRegion::EuropeNorth1 => String::from("Finland"),
+            GcpRegion::EuropeSouthWest1 => String::from("Madrid"),
+            GcpRegion::EuropeWest1 => String::from("Belgium"),
+            GcpRegion::EuropeWest2 => String::from("London"),
+            GcpRegion::EuropeWest3 => String::from("Frankfurt"),
+            GcpRegion::EuropeWest4 => String::from("Netherlands"),
+            GcpRegion::EuropeWest6 => String::from("Zurich"),
+            GcpRegion::EuropeWest8 => String::from("Milan"),
+            GcpRegion::EuropeWest9 => String::from("Paris"),
+            GcpRegion::EuropeWest10 => String::from("Berlin"),
+            GcpRegion::EuropeWest12 => String::from("Turin"),
+            GcpRegion::MeCentral1 => String::from("Doha"),
+            GcpRegion::MeCentral2 => String::from("Dammam"),
+            GcpRegion::MeWest1 => String::from("Tel Aviv"),
+            GcpRegion::AsiaEast1 => String::from("Taiwan"),
+            GcpRegion::AsiaEast2 => String::from("Hong Kong"),
+            GcpRegion::AsiaNorthEast1 => String::from("Tokyo"),
+            GcpRegion::AsiaNorthEast2 => String::from("Osaka"),
+            GcpRegion::AsiaNorthEast3 => String::from("Seoul"),
+            GcpRegion::AsiaSouth1 => String::from("Mumbai"),
+            GcpRegion::AsiaSouth2 => String::from("Delhi"),
+            GcpRegion::AsiaSouthEast1 => String::from("Singapore"),
+            GcpRegion::AsiaSouthEast2 => String::from("Jakarta"),
+            GcpRegion::AustraliaSouthEast1 => String::from("Sydney"),
+            GcpRegion::AustraliaSouthEast2 => String::from("Melbourne"),
+            GcpRegion::AfricaSouth1 => String::from("Johannesburg"),
+        }
+    }
+
+    pub fn from_display_name(name: &str) -> Result<Self, String> {
+        match name {
+            "Iowa" => Ok(GcpRegion::USCentral1),
+            "South Carolina" => Ok(GcpRegion::USEast1),
+            "Northern Virginia" => Ok(GcpRegion::USEast4),
+            "Columbus" => Ok(GcpRegion::USEast5),
+            "Dallas" => Ok(GcpRegion::USSouth1),
+            "Oregon" => Ok(GcpRegion::USWest1),
+            "Los Angeles" => Ok(GcpRegion::USWest2),
+            "Salt Lake City" => Ok(GcpRegion::USWest3),
+            "Las Vegas" => Ok(GcpRegion::USWest4),
+            "Montreal" => Ok(GcpRegion::NorthAmericaNorthEast1),
+            "Toronto" => Ok(GcpRegion::NorthAmericaNorthEast2),
+            "Sao Paulo" => Ok(GcpRegion::SouthAmericaEast1),
+            "Santiago" => Ok(GcpRegion::SouthAmericaWest1),
+            "Warsaw" => Ok(GcpRegion::EuropeCentral2),
+            "Finland" => Ok(GcpRegion::EuropeNorth1),
+            "Madrid" => Ok(GcpRegion::EuropeSouthWest1),
+            "Belgium" => Ok(GcpRegion::EuropeWest1),
+            "London" => Ok(GcpRegion::EuropeWest2),
+            "Frankfurt" => Ok(GcpRegion::EuropeWest3),
+            "Netherlands" => Ok(GcpRegion::EuropeWest4),
+            "Zurich" => Ok(GcpRegion::EuropeWest6),
+            "Milan" => Ok(GcpRegion::EuropeWest8),
+            "Paris" => Ok(GcpRegion::EuropeWest9),
+            "Berlin" => Ok(GcpRegion::EuropeWest10),
+            "Turin" => Ok(GcpRegion::EuropeWest12),
+            "Doha" => Ok(GcpRegion::MeCentral1),
+            "Dammam" => Ok(GcpRegion::MeCentral2),
+            "Tel Aviv" => Ok(GcpRegion::MeWest1),
+            "Taiwan" => Ok(GcpRegion::AsiaEast1),
+            "Hong Kong" => Ok(GcpRegion::AsiaEast2),
+            "Tokyo" => Ok(GcpRegion::AsiaNorthEast1),
+            "Osaka" => Ok(GcpRegion::AsiaNorthEast2),
+            "Seoul" => Ok(GcpRegion::AsiaNorthEast3),
+            "Mumbai" => Ok(GcpRegion::AsiaSouth1),
+            "Delhi" => Ok(GcpRegion::AsiaSouth2),
+            "Singapore" => Ok(GcpRegion::AsiaSouthEast1),
+            "Jakarta" => Ok(GcpRegion::AsiaSouthEast2),
+            "Sydney" => Ok(GcpRegion::AustraliaSouthEast1),
+            "Melbourne" => Ok(GcpRegion::AustraliaSouthEast2),
+            "Johannesburg" => Ok(GcpRegion::AfricaSouth1),
+            _ => Err(String::from("Invalid display name")),
+        }
+    }
+}
+
+impl Serialize for GcpRegion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for GcpRegion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        GcpRegion::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}